@@ -0,0 +1,45 @@
+//! The overhead the wrapper adds to each callback OBS makes into a plugin,
+//! which runs for every frame and audio block of every source. Run with
+//! `cargo bench --bench callbacks`, on a machine with libobs installed.
+
+use obs_wrapper::profiler::set_profile_callbacks;
+use obs_wrapper::unwind::handle_unwind;
+
+use std::hint::black_box;
+use std::time::Instant;
+
+const ITERATIONS: u32 = 10_000_000;
+
+struct Source;
+
+fn bench(name: &str, mut func: impl FnMut()) {
+    for _ in 0..ITERATIONS / 10 {
+        func();
+    }
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        func();
+    }
+    let nanos = start.elapsed().as_nanos() as f64 / ITERATIONS as f64;
+    println!("{:<32} {:>8.2} ns/iter", name, nanos);
+}
+
+fn main() {
+    bench("bare call", || {
+        black_box(black_box(1) + 1);
+    });
+
+    bench("handle_unwind", || {
+        black_box(handle_unwind::<Source, _>("video_tick", || {
+            black_box(1) + 1
+        }));
+    });
+
+    set_profile_callbacks(true);
+    bench("handle_unwind, profiled", || {
+        black_box(handle_unwind::<Source, _>("video_tick", || {
+            black_box(1) + 1
+        }));
+    });
+    set_profile_callbacks(false);
+}
@@ -0,0 +1,241 @@
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
+use syn::{
+    Attribute, Data, DeriveInput, Error, Expr, ExprLit, Field, Fields, Ident, Lit, LitStr, Result,
+    Token, Type, Variant,
+};
+
+/// A single `name = value` or `name` argument inside `#[obs(...)]`.
+struct Arg {
+    name: Ident,
+    value: Option<Expr>,
+}
+
+impl Parse for Arg {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let name = input.parse()?;
+        let value = if input.peek(Token![=]) {
+            input.parse::<Token![=]>()?;
+            Some(input.parse()?)
+        } else {
+            None
+        };
+        Ok(Self { name, value })
+    }
+}
+
+impl Arg {
+    fn expr(self) -> Result<Expr> {
+        let name = self.name;
+        self.value
+            .ok_or_else(|| Error::new(name.span(), format!("expected `{} = ...`", name)))
+    }
+
+    fn lit_str(self) -> Result<LitStr> {
+        let name = self.name.clone();
+        match self.expr()? {
+            Expr::Lit(ExprLit {
+                lit: Lit::Str(lit), ..
+            }) => Ok(lit),
+            _ => Err(Error::new(
+                name.span(),
+                format!("expected `{} = \"...\"`", name),
+            )),
+        }
+    }
+
+    fn flag(self) -> Result<bool> {
+        match self.value {
+            None => Ok(true),
+            Some(_) => Err(Error::new(
+                self.name.span(),
+                format!("`{}` does not take a value", self.name),
+            )),
+        }
+    }
+}
+
+fn args(attrs: &[Attribute]) -> Result<Vec<Arg>> {
+    let mut args = Vec::new();
+    for attr in attrs.iter().filter(|a| a.path.is_ident("obs")) {
+        args.extend(attr.parse_args_with(Punctuated::<Arg, Token![,]>::parse_terminated)?);
+    }
+    Ok(args)
+}
+
+fn unknown(arg: &Arg) -> Error {
+    Error::new(arg.name.span(), "unknown `obs` attribute")
+}
+
+/// The `#[obs(...)]` arguments of one field.
+pub struct FieldAttrs {
+    pub ident: Ident,
+    pub ty: Type,
+    pub key: LitStr,
+    pub default: Option<Expr>,
+    pub description: Option<LitStr>,
+    pub min: Option<Expr>,
+    pub max: Option<Expr>,
+    pub step: Option<Expr>,
+    pub slider: bool,
+    pub text: Option<LitStr>,
+    pub list: bool,
+    pub skip: bool,
+}
+
+impl FieldAttrs {
+    fn from_field(field: &Field) -> Result<Self> {
+        let ident = field.ident.clone().expect("named field");
+        let mut attrs = Self {
+            key: LitStr::new(&ident.to_string(), ident.span()),
+            ident,
+            ty: field.ty.clone(),
+            default: None,
+            description: None,
+            min: None,
+            max: None,
+            step: None,
+            slider: false,
+            text: None,
+            list: false,
+            skip: false,
+        };
+
+        for arg in args(&field.attrs)? {
+            if arg.name == "key" {
+                attrs.key = arg.lit_str()?;
+            } else if arg.name == "default" {
+                attrs.default = Some(arg.expr()?);
+            } else if arg.name == "description" {
+                attrs.description = Some(arg.lit_str()?);
+            } else if arg.name == "min" {
+                attrs.min = Some(arg.expr()?);
+            } else if arg.name == "max" {
+                attrs.max = Some(arg.expr()?);
+            } else if arg.name == "step" {
+                attrs.step = Some(arg.expr()?);
+            } else if arg.name == "slider" {
+                attrs.slider = arg.flag()?;
+            } else if arg.name == "text" {
+                attrs.text = Some(arg.lit_str()?);
+            } else if arg.name == "list" {
+                attrs.list = arg.flag()?;
+            } else if arg.name == "skip" {
+                attrs.skip = arg.flag()?;
+            } else {
+                return Err(unknown(&arg));
+            }
+        }
+
+        Ok(attrs)
+    }
+
+    /// The last segment of the field type, e.g. `f64` or `String`.
+    pub fn type_name(&self) -> Option<String> {
+        match &self.ty {
+            Type::Path(path) if path.qself.is_none() => {
+                path.path.segments.last().map(|s| s.ident.to_string())
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns whether the field type is `String`, whose defaults are
+    /// written as string literals.
+    pub fn is_string(&self) -> bool {
+        self.type_name().as_deref() == Some("String")
+    }
+
+    /// The expression the field falls back to when the setting is missing.
+    pub fn default_value(&self) -> proc_macro2::TokenStream {
+        let ty = &self.ty;
+        match &self.default {
+            Some(value) if self.is_string() => {
+                quote::quote! { ::std::string::String::from(#value) }
+            }
+            Some(value) => quote::quote! { #value },
+            None => quote::quote! { <#ty as ::std::default::Default>::default() },
+        }
+    }
+
+    /// The label shown next to the property, defaulting to the key.
+    pub fn description(&self) -> LitStr {
+        self.description.clone().unwrap_or_else(|| self.key.clone())
+    }
+}
+
+/// Parses the attributes of every field of a struct with named fields.
+pub fn named_fields(input: &DeriveInput) -> Result<Vec<FieldAttrs>> {
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(Error::new_spanned(
+                    &input.ident,
+                    "only structs with named fields are supported",
+                ))
+            }
+        },
+        _ => {
+            return Err(Error::new_spanned(
+                &input.ident,
+                "only structs with named fields are supported",
+            ))
+        }
+    };
+
+    fields.iter().map(FieldAttrs::from_field).collect()
+}
+
+/// The `#[obs(...)]` arguments of one enum variant.
+pub struct VariantAttrs {
+    pub ident: Ident,
+    pub key: LitStr,
+    pub description: LitStr,
+}
+
+impl VariantAttrs {
+    fn from_variant(variant: &Variant) -> Result<Self> {
+        if !matches!(variant.fields, Fields::Unit) {
+            return Err(Error::new_spanned(
+                variant,
+                "only variants without fields are supported",
+            ));
+        }
+
+        let ident = variant.ident.clone();
+        let mut key = LitStr::new(&ident.to_string(), ident.span());
+        let mut description = None;
+
+        for arg in args(&variant.attrs)? {
+            if arg.name == "key" {
+                key = arg.lit_str()?;
+            } else if arg.name == "description" {
+                description = Some(arg.lit_str()?);
+            } else {
+                return Err(unknown(&arg));
+            }
+        }
+
+        Ok(Self {
+            description: description.unwrap_or_else(|| key.clone()),
+            ident,
+            key,
+        })
+    }
+}
+
+/// Parses the attributes of every variant of a fieldless enum.
+pub fn unit_variants(input: &DeriveInput) -> Result<Vec<VariantAttrs>> {
+    let variants = match &input.data {
+        Data::Enum(data) if !data.variants.is_empty() => &data.variants,
+        _ => {
+            return Err(Error::new_spanned(
+                &input.ident,
+                "only enums with at least one variant are supported",
+            ))
+        }
+    };
+
+    variants.iter().map(VariantAttrs::from_variant).collect()
+}
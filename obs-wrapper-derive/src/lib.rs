@@ -0,0 +1,91 @@
+//! Derive macros for [obs-wrapper](https://docs.rs/obs-wrapper), re-exported
+//! from it when the `derive` feature is enabled.
+
+extern crate proc_macro;
+
+use proc_macro::TokenStream;
+use syn::{parse_macro_input, DeriveInput, ItemMod};
+
+mod attrs;
+mod list;
+mod object;
+mod properties;
+mod settings;
+
+/// Implements `obs_wrapper::data::ObsSettings` for a struct with named fields.
+///
+/// Each field is stored under its own name, which can be changed with
+/// `#[obs(key = "...")]`. `#[obs(default = ...)]` sets the value used when the
+/// setting is missing, otherwise [`Default::default`] is used.
+#[proc_macro_derive(ObsSettings, attributes(obs))]
+pub fn derive_obs_settings(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    settings::expand(input)
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
+}
+
+/// Implements `obs_wrapper::source::ObsProperties` for a struct with named
+/// fields, adding one property per field.
+///
+/// Shares the `#[obs(...)]` attributes of [`ObsSettings`], with
+/// `description`, `min`, `max`, `step`, `slider`, `text`, `list` and `skip`
+/// controlling how each property is shown.
+#[proc_macro_derive(ObsProperties, attributes(obs))]
+pub fn derive_obs_properties(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    properties::expand(input)
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
+}
+
+/// Implements `obs_wrapper::source::ObsListEnum` for a fieldless enum, along
+/// with storing it in settings as a string.
+///
+/// Variants are stored as their name unless renamed with `#[obs(key = "...")]`
+/// and labelled with `#[obs(description = "...")]`. Unknown stored values are
+/// read back as the first variant.
+#[proc_macro_derive(ObsList, attributes(obs))]
+pub fn derive_obs_list(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    list::expand(input)
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
+}
+
+/// Generates the source registration of the types in an inline module.
+///
+/// For every type implementing `obs_wrapper::source::Sourceable` in the
+/// module, the `*Source` traits implemented for it in the same module are
+/// looked up and a `source_builder` function is generated, returning an
+/// `obs_wrapper::source::SourceInfoBuilder` with each of them enabled. The
+/// output flags are inferred by `build` as usual:
+///
+/// ```ignore
+/// #[obs_object]
+/// mod filter {
+///     impl Sourceable for FocusFilter { ... }
+///     impl CreatableSource<Data> for FocusFilter { ... }
+///     impl VideoRenderSource<Data> for FocusFilter { ... }
+/// }
+///
+/// let source = FocusFilter::source_builder(load_context).build();
+/// ```
+///
+/// Only implementations inside the module are seen, so traits implemented
+/// elsewhere must still be enabled on the returned builder.
+#[proc_macro_attribute]
+pub fn obs_object(args: TokenStream, input: TokenStream) -> TokenStream {
+    if !args.is_empty() {
+        return syn::Error::new(
+            proc_macro2::Span::call_site(),
+            "`#[obs_object]` does not take arguments",
+        )
+        .to_compile_error()
+        .into();
+    }
+    let input = parse_macro_input!(input as ItemMod);
+    object::expand(input)
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
+}
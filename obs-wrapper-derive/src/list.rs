@@ -0,0 +1,75 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{DeriveInput, Result};
+
+use crate::attrs::unit_variants;
+
+pub fn expand(input: DeriveInput) -> Result<TokenStream> {
+    let variants = unit_variants(&input)?;
+    let name = &input.ident;
+
+    let first = &variants[0].ident;
+    let parse = variants.iter().map(|variant| {
+        let ident = &variant.ident;
+        let key = &variant.key;
+        quote! { #key => #name::#ident, }
+    });
+    let keys = variants.iter().map(|variant| {
+        let ident = &variant.ident;
+        let key = &variant.key;
+        quote! { #name::#ident => #key, }
+    });
+    let keys_default = keys.clone();
+    let list = variants.iter().map(|variant| {
+        let ident = &variant.ident;
+        let key = &variant.key;
+        let description = &variant.description;
+        quote! { (#name::#ident, #key, #description), }
+    });
+
+    Ok(quote! {
+        impl ::obs_wrapper::source::ObsListEnum for #name {
+            fn variants() -> &'static [(Self, &'static str, &'static str)] {
+                &[#(#list)*]
+            }
+        }
+
+        impl ::obs_wrapper::data::FromDataItem for #name {
+            fn typ() -> ::obs_wrapper::data::DataType {
+                ::obs_wrapper::data::DataType::String
+            }
+
+            unsafe fn from_item_unchecked(item: *mut ::obs_wrapper::obs_sys::obs_data_item_t) -> Self {
+                let value = <::std::string::String as ::obs_wrapper::data::FromDataItem>::from_item_unchecked(item);
+                match value.as_str() {
+                    #(#parse)*
+                    _ => #name::#first,
+                }
+            }
+        }
+
+        impl ::obs_wrapper::data::IntoDataItem for #name {
+            unsafe fn set_item(
+                &self,
+                data: *mut ::obs_wrapper::obs_sys::obs_data_t,
+                name: &::obs_wrapper::string::ObsString,
+            ) {
+                let value: &str = match self {
+                    #(#keys)*
+                };
+                <str as ::obs_wrapper::data::IntoDataItem>::set_item(value, data, name)
+            }
+
+            unsafe fn set_default_item(
+                &self,
+                data: *mut ::obs_wrapper::obs_sys::obs_data_t,
+                name: &::obs_wrapper::string::ObsString,
+            ) {
+                let value: &str = match self {
+                    #(#keys_default)*
+                };
+                <str as ::obs_wrapper::data::IntoDataItem>::set_default_item(value, data, name)
+            }
+        }
+    })
+}
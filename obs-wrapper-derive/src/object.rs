@@ -0,0 +1,220 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{Error, GenericArgument, Item, ItemImpl, ItemMod, PathArguments, Result, Type};
+
+/// The builder method enabling each source trait, with the traits that set the
+/// same callback in place of it.
+const TRAITS: &[(&str, &str, &[&str])] = &[
+    ("GetNameSource", "get_name", &["GetNameTypeDataSource"]),
+    ("GetNameTypeDataSource", "get_name_type_data", &[]),
+    ("CreatableSource", "create", &["TryCreatableSource"]),
+    ("TryCreatableSource", "try_create", &[]),
+    ("UpdateSource", "update", &["TryUpdateSource"]),
+    ("TryUpdateSource", "try_update", &[]),
+    ("GetWidthSource", "get_width", &[]),
+    ("GetHeightSource", "get_height", &[]),
+    ("ActivateSource", "activate", &[]),
+    ("DeactivateSource", "deactivate", &[]),
+    (
+        "VideoRenderSource",
+        "video_render",
+        &["TransitionVideoRenderSource", "TryVideoRenderSource"],
+    ),
+    (
+        "TryVideoRenderSource",
+        "try_video_render",
+        &["TransitionVideoRenderSource"],
+    ),
+    (
+        "TransitionVideoRenderSource",
+        "transition_video_render",
+        &[],
+    ),
+    (
+        "AudioRenderSource",
+        "audio_render",
+        &["TransitionAudioRenderSource"],
+    ),
+    (
+        "TransitionAudioRenderSource",
+        "transition_audio_render",
+        &[],
+    ),
+    (
+        "GetPropertiesSource",
+        "get_properties",
+        &["TryGetPropertiesSource"],
+    ),
+    ("TryGetPropertiesSource", "try_get_properties", &[]),
+    ("EnumActiveSource", "enum_active_sources", &[]),
+    ("EnumAllSource", "enum_all_sources", &[]),
+    ("TransitionStartSource", "transition_start", &[]),
+    ("TransitionStopSource", "transition_stop", &[]),
+    ("VideoTickSource", "video_tick", &["TryVideoTickSource"]),
+    ("TryVideoTickSource", "try_video_tick", &[]),
+    (
+        "FilterAudioSource",
+        "filter_audio",
+        &["TryFilterAudioSource"],
+    ),
+    ("TryFilterAudioSource", "try_filter_audio", &[]),
+    ("FilterVideoSource", "filter_video", &[]),
+    ("GetDefaultsSource", "get_defaults", &[]),
+    ("MediaPlayPauseSource", "media_play_pause", &[]),
+    ("MediaRestartSource", "media_restart", &[]),
+    ("MediaStopSource", "media_stop", &[]),
+    ("MediaNextSource", "media_next", &[]),
+    ("MediaPreviousSource", "media_previous", &[]),
+    ("MediaGetDurationSource", "media_get_duration", &[]),
+    ("MediaGetTimeSource", "media_get_time", &[]),
+    ("MediaGetStateSource", "media_get_state", &[]),
+    ("MouseClickSource", "mouse_click", &[]),
+    ("MouseMoveSource", "mouse_move", &[]),
+    ("MouseWheelSource", "mouse_wheel", &[]),
+    ("FocusSource", "focus", &[]),
+    ("KeyClickSource", "key_click", &[]),
+];
+
+/// A type implementing `Sourceable` in the module, with the source traits
+/// implemented for it.
+struct Object {
+    ty: Type,
+    data: Option<Type>,
+    traits: Vec<(String, &'static str)>,
+}
+
+fn impl_trait(item: &ItemImpl) -> Option<(String, Option<Type>)> {
+    let (_, path, _) = item.trait_.as_ref()?;
+    let segment = path.segments.last()?;
+    let data = match &segment.arguments {
+        PathArguments::AngleBracketed(args) => args.args.iter().find_map(|arg| match arg {
+            GenericArgument::Type(ty) => Some(ty.clone()),
+            _ => None,
+        }),
+        _ => None,
+    };
+    Some((segment.ident.to_string(), data))
+}
+
+fn same_type(a: &Type, b: &Type) -> bool {
+    quote!(#a).to_string() == quote!(#b).to_string()
+}
+
+pub fn expand(mut module: ItemMod) -> Result<TokenStream> {
+    let items = match &mut module.content {
+        Some((_, items)) => items,
+        None => {
+            return Err(Error::new_spanned(
+                &module.ident,
+                "`#[obs_object]` must be placed on an inline module",
+            ))
+        }
+    };
+
+    let mut objects = Vec::<Object>::new();
+    for item in items.iter() {
+        if let Item::Impl(item) = item {
+            if let Some((name, None)) = impl_trait(item) {
+                if name == "Sourceable" {
+                    if !item.generics.params.is_empty() {
+                        return Err(Error::new_spanned(
+                            &item.generics,
+                            "`#[obs_object]` does not support generic sources",
+                        ));
+                    }
+                    objects.push(Object {
+                        ty: (*item.self_ty).clone(),
+                        data: None,
+                        traits: Vec::new(),
+                    });
+                }
+            }
+        }
+    }
+
+    if objects.is_empty() {
+        return Err(Error::new_spanned(
+            &module.ident,
+            "`#[obs_object]` found no `Sourceable` implementation in the module",
+        ));
+    }
+
+    for item in items.iter() {
+        let item = match item {
+            Item::Impl(item) => item,
+            _ => continue,
+        };
+        let (name, data) = match impl_trait(item) {
+            Some((name, Some(data))) => (name, data),
+            _ => continue,
+        };
+        let method = match TRAITS.iter().find(|(t, _, _)| *t == name) {
+            Some((_, method, _)) => *method,
+            None => continue,
+        };
+        let object = match objects.iter_mut().find(|o| same_type(&o.ty, &item.self_ty)) {
+            Some(object) => object,
+            None => continue,
+        };
+
+        let existing = object.data.get_or_insert_with(|| data.clone());
+        if !same_type(existing, &data) {
+            return Err(Error::new_spanned(
+                &data,
+                format!(
+                    "every source trait must use the same data type, `{}` here and `{}` before",
+                    quote!(#data),
+                    quote!(#existing)
+                ),
+            ));
+        }
+        object.traits.push((name, method));
+    }
+
+    let mut generated = Vec::new();
+    for object in &objects {
+        for (name, _) in &object.traits {
+            let replaced = TRAITS.iter().find(|(t, _, _)| t == name).unwrap().2;
+            if let Some((other, _)) = object
+                .traits
+                .iter()
+                .find(|(other, _)| replaced.contains(&other.as_str()))
+            {
+                return Err(Error::new_spanned(
+                    &object.ty,
+                    format!("implement only one of `{}` and `{}`", name, other),
+                ));
+            }
+        }
+
+        let ty = &object.ty;
+        let data = object
+            .data
+            .as_ref()
+            .map_or_else(|| quote!(()), |data| quote!(#data));
+        let enables = object
+            .traits
+            .iter()
+            .map(|(_, method)| format_ident!("enable_{}", method));
+
+        generated.push(quote! {
+            impl #ty {
+                /// A builder with every source trait implemented in this
+                /// module enabled, generated by `#[obs_object]`.
+                pub fn source_builder(
+                    load_context: &::obs_wrapper::module::LoadContext,
+                ) -> ::obs_wrapper::source::SourceInfoBuilder<#ty, #data> {
+                    load_context
+                        .create_source_builder::<#ty, #data>()
+                        #(.#enables())*
+                }
+            }
+        });
+    }
+
+    for tokens in generated {
+        items.push(Item::Verbatim(tokens));
+    }
+
+    Ok(quote!(#module))
+}
@@ -0,0 +1,99 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{DeriveInput, Error, Result};
+
+use crate::attrs::{named_fields, FieldAttrs};
+
+fn add_property(field: &FieldAttrs) -> Result<TokenStream> {
+    let ty = &field.ty;
+    let key = &field.key;
+    let description = field.description();
+    let name = quote! { ::obs_wrapper::obs_string!(#key) };
+    let description = quote! { ::obs_wrapper::obs_string!(#description) };
+
+    if field.list {
+        return Ok(quote! {
+            properties.add_enum_list::<#ty>(#name, #description);
+        });
+    }
+
+    let type_name = field.type_name().unwrap_or_default();
+    let prop = match type_name.as_str() {
+        "bool" => quote! { ::obs_wrapper::source::BoolProp },
+        "String" => {
+            let text = match field.text.as_ref().map(|t| t.value()).as_deref() {
+                None | Some("default") => quote! { Default },
+                Some("password") => quote! { Password },
+                Some("multiline") => quote! { Multiline },
+                Some(_) => {
+                    return Err(Error::new_spanned(
+                        &field.text,
+                        "expected one of \"default\", \"password\" or \"multiline\"",
+                    ))
+                }
+            };
+            quote! {
+                ::obs_wrapper::source::TextProp::new(::obs_wrapper::source::TextType::#text)
+            }
+        }
+        "i8" | "i16" | "i32" | "i64" | "isize" | "u8" | "u16" | "u32" | "u64" | "usize" => {
+            let mut prop = quote! { ::obs_wrapper::source::NumberProp::<#ty>::new_int() };
+            if let Some(step) = &field.step {
+                prop = quote! { #prop.with_step(#step) };
+            }
+            number_modifiers(field, prop)
+        }
+        "f32" | "f64" => {
+            let step = field
+                .step
+                .as_ref()
+                .map(|step| quote! { #step })
+                .unwrap_or_else(|| quote! { 0.01 });
+            let prop = quote! { ::obs_wrapper::source::NumberProp::<#ty>::new_float(#step) };
+            number_modifiers(field, prop)
+        }
+        _ => {
+            return Err(Error::new_spanned(
+                ty,
+                "unsupported property type, mark enums with `#[obs(list)]` or skip the field with `#[obs(skip)]`",
+            ))
+        }
+    };
+
+    Ok(quote! {
+        properties.add(#name, #description, #prop);
+    })
+}
+
+fn number_modifiers(field: &FieldAttrs, mut prop: TokenStream) -> TokenStream {
+    prop = match (&field.min, &field.max) {
+        (Some(min), Some(max)) => quote! { #prop.with_range((#min)..=(#max)) },
+        (Some(min), None) => quote! { #prop.with_range((#min)..) },
+        (None, Some(max)) => quote! { #prop.with_range(..=(#max)) },
+        (None, None) => prop,
+    };
+    if field.slider {
+        prop = quote! { #prop.with_slider() };
+    }
+    prop
+}
+
+pub fn expand(input: DeriveInput) -> Result<TokenStream> {
+    let fields = named_fields(&input)?;
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let add = fields
+        .iter()
+        .filter(|field| !field.skip)
+        .map(add_property)
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(quote! {
+        impl #impl_generics ::obs_wrapper::source::ObsProperties for #name #ty_generics #where_clause {
+            fn add_properties(properties: &mut ::obs_wrapper::source::Properties) {
+                #(#add)*
+            }
+        }
+    })
+}
@@ -0,0 +1,59 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{DeriveInput, Result};
+
+use crate::attrs::named_fields;
+
+pub fn expand(input: DeriveInput) -> Result<TokenStream> {
+    let fields = named_fields(&input)?;
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let read = fields.iter().map(|field| {
+        let ident = &field.ident;
+        let ty = &field.ty;
+        let key = &field.key;
+        let default = field.default_value();
+        quote! {
+            #ident: data
+                .get::<#ty, _>(::obs_wrapper::obs_string!(#key))
+                .unwrap_or_else(|| #default)
+        }
+    });
+
+    let defaults = fields.iter().map(|field| {
+        let ty = &field.ty;
+        let key = &field.key;
+        let default = field.default_value();
+        quote! {
+            let value: #ty = #default;
+            data.set_default(::obs_wrapper::obs_string!(#key), &value);
+        }
+    });
+
+    let write = fields.iter().map(|field| {
+        let ident = &field.ident;
+        let key = &field.key;
+        quote! {
+            data.set(::obs_wrapper::obs_string!(#key), &self.#ident);
+        }
+    });
+
+    Ok(quote! {
+        impl #impl_generics ::obs_wrapper::data::ObsSettings for #name #ty_generics #where_clause {
+            fn from_data(data: &::obs_wrapper::data::DataObj) -> Self {
+                Self {
+                    #(#read,)*
+                }
+            }
+
+            fn apply_defaults(data: &mut ::obs_wrapper::data::DataObj) {
+                #(#defaults)*
+            }
+
+            fn write_to(&self, data: &mut ::obs_wrapper::data::DataObj) {
+                #(#write)*
+            }
+        }
+    })
+}
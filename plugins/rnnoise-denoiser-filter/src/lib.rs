@@ -78,7 +78,10 @@ impl UpdateSource<Data> for RnnoiseDenoiserFilter {
 }
 
 impl FilterAudioSource<Data> for RnnoiseDenoiserFilter {
-    fn filter_audio(data: &mut Option<Data>, audio: &mut audio::AudioDataContext) {
+    fn filter_audio(
+        data: &mut Option<Data>,
+        audio: &mut audio::AudioDataContext,
+    ) -> audio::FilterAudioResult {
         if let Some(data) = data {
             let state = &mut data.state;
             let input_ring_buffer = &mut data.input;
@@ -174,6 +177,7 @@ impl FilterAudioSource<Data> for RnnoiseDenoiserFilter {
                 }
             }
         }
+        audio::FilterAudioResult::Pass
     }
 }
 
@@ -194,9 +198,7 @@ impl Module for RnnoiseDenoiserFilter {
             .enable_filter_audio()
             .build();
 
-        load_context.register_source(source);
-
-        true
+        load_context.register_source(source).is_ok()
     }
 
     fn description() -> ObsString {
@@ -406,9 +406,7 @@ impl Module for ScrollFocusFilter {
             .enable_video_tick()
             .build();
 
-        load_context.register_source(source);
-
-        true
+        load_context.register_source(source).is_ok()
     }
 
     fn description() -> ObsString {
@@ -0,0 +1,103 @@
+//! Annotations describing what a plugin was doing, written to the OBS log
+//! when something goes wrong so that a crash report carries more than a
+//! stack trace:
+//!
+//! ```ignore
+//! crash::annotate("my_plugin.device", device.name());
+//! crash::annotate("my_plugin.state", format!("{} frames queued", queue.len()));
+//! ```
+//!
+//! Annotations are written when a panic is caught in a callback, and on
+//! `bcrash` if [`install_crash_handler`](crate::crash::install_crash_handler)
+//! was called. They can also be written at any time with
+//! [`log_annotations`](crate::crash::log_annotations).
+
+use obs_sys::{base_set_crash_handler, blog, blogva, LOG_ERROR};
+
+use std::os::raw::{c_char, c_void};
+use std::sync::{Mutex, MutexGuard, TryLockError};
+
+// The `va_list` parameter of crash handlers, as bindgen declares it: on
+// x86_64 outside of Windows `va_list` is an array, which decays to a pointer
+// to its element in parameters. Elsewhere it is passed as is.
+#[cfg(all(target_arch = "x86_64", not(windows)))]
+type VaList = *mut obs_sys::__va_list_tag;
+#[cfg(not(all(target_arch = "x86_64", not(windows))))]
+type VaList = obs_sys::va_list;
+
+static ANNOTATIONS: Mutex<Vec<(String, String)>> = Mutex::new(Vec::new());
+
+fn lock() -> MutexGuard<'static, Vec<(String, String)>> {
+    ANNOTATIONS.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Sets the annotation `key` to `value`, replacing its previous value.
+///
+/// Keys are shared by every plugin loaded into the process, so they should be
+/// prefixed with the plugin name.
+pub fn annotate(key: &str, value: impl Into<String>) {
+    let value = value.into();
+    let mut annotations = lock();
+    match annotations.iter_mut().find(|(k, _)| k == key) {
+        Some((_, v)) => *v = value,
+        None => annotations.push((key.to_owned(), value)),
+    }
+}
+
+/// Removes the annotation `key`, e.g. once the state it describes is gone.
+pub fn remove_annotation(key: &str) {
+    lock().retain(|(k, _)| k != key);
+}
+
+/// Returns a copy of the current annotations, in the order they were first
+/// set.
+pub fn annotations() -> Vec<(String, String)> {
+    lock().clone()
+}
+
+/// Writes the current annotations to the OBS log as errors.
+pub fn log_annotations() {
+    // This may run while the thread holding the lock is crashing, in which
+    // case the annotations are skipped rather than deadlocking.
+    let annotations = match ANNOTATIONS.try_lock() {
+        Ok(annotations) => annotations,
+        Err(TryLockError::Poisoned(e)) => e.into_inner(),
+        Err(TryLockError::WouldBlock) => return,
+    };
+
+    for (key, value) in annotations.iter() {
+        let line = format!("  {}: {}\0", key, value);
+        unsafe {
+            blog(
+                LOG_ERROR as i32,
+                "%s\0".as_ptr() as *const c_char,
+                line.as_ptr() as *const c_char,
+            );
+        }
+    }
+}
+
+unsafe extern "C" fn crash_handler(format: *const c_char, args: VaList, _: *mut c_void) {
+    blogva(LOG_ERROR as i32, format, args);
+    blog(
+        LOG_ERROR as i32,
+        "Crash annotations:\0".as_ptr() as *const c_char,
+    );
+    log_annotations();
+    std::process::abort();
+}
+
+/// Installs a `bcrash` handler that logs the crash message and the current
+/// annotations, then aborts.
+///
+/// This replaces the crash handler of the application, such as the one the
+/// OBS frontend uses to write its crash reports, so it is meant for plugins
+/// loaded into other applications embedding libobs. Inside the OBS frontend,
+/// crashes are no longer written to its crash reports after this is called.
+/// libobs keeps a single handler and gives no access to the previous one, so
+/// it cannot be chained to.
+///
+/// See [OBS documentation](https://obsproject.com/docs/reference-libobs-util-base.html#c.base_set_crash_handler)
+pub fn install_crash_handler() {
+    unsafe { base_set_crash_handler(Some(crash_handler), std::ptr::null_mut()) }
+}
@@ -4,17 +4,28 @@ use std::{borrow::Cow, ffi::CStr, marker::PhantomData};
 use obs_sys::{
     obs_data_array_count, obs_data_array_item, obs_data_array_release, obs_data_array_t,
     obs_data_clear, obs_data_create, obs_data_create_from_json, obs_data_create_from_json_file,
-    obs_data_create_from_json_file_safe, obs_data_erase, obs_data_get_json, obs_data_item_byname,
+    obs_data_create_from_json_file_safe, obs_data_erase, obs_data_get_json, obs_data_get_quat,
+    obs_data_get_vec2, obs_data_get_vec3, obs_data_get_vec4, obs_data_item_byname,
     obs_data_item_get_array, obs_data_item_get_bool, obs_data_item_get_double,
     obs_data_item_get_int, obs_data_item_get_obj, obs_data_item_get_string, obs_data_item_gettype,
     obs_data_item_numtype, obs_data_item_release, obs_data_item_t, obs_data_number_type,
     obs_data_number_type_OBS_DATA_NUM_DOUBLE, obs_data_number_type_OBS_DATA_NUM_INT,
-    obs_data_release, obs_data_t, obs_data_type, obs_data_type_OBS_DATA_ARRAY,
+    obs_data_release, obs_data_set_bool, obs_data_set_default_bool, obs_data_set_default_double,
+    obs_data_set_default_int, obs_data_set_default_string, obs_data_set_double, obs_data_set_int,
+    obs_data_set_quat, obs_data_set_string, obs_data_set_vec2, obs_data_set_vec3,
+    obs_data_set_vec4, obs_data_t, obs_data_type, obs_data_type_OBS_DATA_ARRAY,
     obs_data_type_OBS_DATA_BOOLEAN, obs_data_type_OBS_DATA_NUMBER, obs_data_type_OBS_DATA_OBJECT,
-    obs_data_type_OBS_DATA_STRING, size_t,
+    obs_data_type_OBS_DATA_STRING, quat, size_t, vec2, vec3, vec4,
 };
 
-use crate::{string::ObsString, wrapper::PtrWrapper};
+use crate::{
+    graphics::{Vec2, Vec3, Vec4},
+    string::ObsString,
+    wrapper::PtrWrapper,
+};
+
+#[cfg(feature = "derive")]
+pub use obs_wrapper_derive::ObsSettings;
 
 #[derive(Debug, Eq, PartialEq, Copy, Clone)]
 pub enum DataType {
@@ -66,6 +77,15 @@ impl FromDataItem for Cow<'_, str> {
     }
 }
 
+impl FromDataItem for String {
+    fn typ() -> DataType {
+        DataType::String
+    }
+    unsafe fn from_item_unchecked(item: *mut obs_data_item_t) -> Self {
+        Cow::<str>::from_item_unchecked(item).into_owned()
+    }
+}
+
 macro_rules! impl_get_int {
     ($($t:ty)*) => {
         $(
@@ -128,6 +148,170 @@ impl FromDataItem for DataArray<'_> {
     }
 }
 
+/// A value that can be written to a [`DataObj`], either as the value itself
+/// or as its default.
+pub trait IntoDataItem {
+    /// # Safety
+    /// `data` must point to a valid `obs_data_t`.
+    unsafe fn set_item(&self, data: *mut obs_data_t, name: &ObsString);
+    /// # Safety
+    /// `data` must point to a valid `obs_data_t`.
+    unsafe fn set_default_item(&self, data: *mut obs_data_t, name: &ObsString);
+}
+
+impl IntoDataItem for str {
+    unsafe fn set_item(&self, data: *mut obs_data_t, name: &ObsString) {
+        let value = ObsString::from(self);
+        obs_data_set_string(data, name.as_ptr(), value.as_ptr());
+    }
+    unsafe fn set_default_item(&self, data: *mut obs_data_t, name: &ObsString) {
+        let value = ObsString::from(self);
+        obs_data_set_default_string(data, name.as_ptr(), value.as_ptr());
+    }
+}
+
+impl IntoDataItem for String {
+    unsafe fn set_item(&self, data: *mut obs_data_t, name: &ObsString) {
+        self.as_str().set_item(data, name)
+    }
+    unsafe fn set_default_item(&self, data: *mut obs_data_t, name: &ObsString) {
+        self.as_str().set_default_item(data, name)
+    }
+}
+
+macro_rules! impl_set_int {
+    ($($t:ty)*) => {
+        $(
+            impl IntoDataItem for $t {
+                unsafe fn set_item(&self, data: *mut obs_data_t, name: &ObsString) {
+                    obs_data_set_int(data, name.as_ptr(), *self as i64);
+                }
+                unsafe fn set_default_item(&self, data: *mut obs_data_t, name: &ObsString) {
+                    obs_data_set_default_int(data, name.as_ptr(), *self as i64);
+                }
+            }
+        )*
+    };
+}
+
+impl_set_int!(i64 u64 i32 u32 i16 u16 i8 u8 isize usize);
+
+macro_rules! impl_set_double {
+    ($($t:ty)*) => {
+        $(
+            impl IntoDataItem for $t {
+                unsafe fn set_item(&self, data: *mut obs_data_t, name: &ObsString) {
+                    obs_data_set_double(data, name.as_ptr(), *self as f64);
+                }
+                unsafe fn set_default_item(&self, data: *mut obs_data_t, name: &ObsString) {
+                    obs_data_set_default_double(data, name.as_ptr(), *self as f64);
+                }
+            }
+        )*
+    };
+}
+
+impl_set_double!(f64 f32);
+
+impl IntoDataItem for bool {
+    unsafe fn set_item(&self, data: *mut obs_data_t, name: &ObsString) {
+        obs_data_set_bool(data, name.as_ptr(), *self);
+    }
+    unsafe fn set_default_item(&self, data: *mut obs_data_t, name: &ObsString) {
+        obs_data_set_default_bool(data, name.as_ptr(), *self);
+    }
+}
+
+/// The name of a setting, checked and stored once instead of being converted
+/// to a C string on every lookup:
+///
+/// ```ignore
+/// const ZOOM: SettingsKey = SettingsKey::new("zoom\0");
+///
+/// fn video_tick(data: &mut Option<Data>, _seconds: f32) {
+///     let zoom: f64 = data.settings.get(ZOOM).unwrap_or(1.);
+///     // ...
+/// }
+///
+/// properties.add(ZOOM, obs_string!("Zoom"), NumberProp::new_float(0.1));
+/// ```
+///
+/// Keys are accepted wherever a name is, as they convert to an [`ObsString`]
+/// without allocating.
+#[derive(Debug, Eq, PartialEq, Hash, Clone, Copy)]
+pub struct SettingsKey(&'static str);
+
+impl SettingsKey {
+    /// Creates a key from a string ending in its nul terminator.
+    ///
+    /// # Panics
+    ///
+    /// Panics, at compile time when used in a `const`, if `key` is not nul
+    /// terminated or contains another nul.
+    pub const fn new(key: &'static str) -> Self {
+        let bytes = key.as_bytes();
+        if bytes.is_empty() || bytes[bytes.len() - 1] != 0 {
+            panic!("settings keys must end with a nul terminator");
+        }
+        let mut i = 0;
+        while i < bytes.len() - 1 {
+            if bytes[i] == 0 {
+                panic!("settings keys must not contain a nul before the end");
+            }
+            i += 1;
+        }
+        Self(key)
+    }
+
+    /// The key without its nul terminator.
+    pub fn as_str(&self) -> &'static str {
+        &self.0[..self.0.len() - 1]
+    }
+
+    pub fn as_ptr(&self) -> *const std::os::raw::c_char {
+        self.0.as_ptr() as *const std::os::raw::c_char
+    }
+}
+
+impl From<SettingsKey> for ObsString {
+    fn from(key: SettingsKey) -> Self {
+        // The terminator was checked when creating the key.
+        unsafe { ObsString::from_nul_terminted_str(key.0) }
+    }
+}
+
+/// A settings struct that is read from and written to a [`DataObj`], usually
+/// implemented with `#[derive(ObsSettings)]` (requires the `derive` feature).
+///
+/// ```ignore
+/// #[derive(ObsSettings)]
+/// struct Settings {
+///     #[obs(key = "zoom", default = 1.5)]
+///     zoom: f64,
+///     #[obs(default = true)]
+///     follow_mouse: bool,
+///     screen: String,
+/// }
+///
+/// impl UpdateSource<Data> for Filter {
+///     fn update(data: &mut Option<Data>, settings: &mut DataObj, _: &mut GlobalContext) {
+///         let settings = Settings::from_data(settings);
+///         // ...
+///     }
+/// }
+/// ```
+///
+/// Fields are stored under their own name unless renamed with `key`, and
+/// fall back to `default` (or [`Default::default`]) when missing.
+pub trait ObsSettings: Sized {
+    /// Reads every field, using the defaults for missing values.
+    fn from_data(data: &DataObj) -> Self;
+    /// Registers the default of every field, usually called from `get_defaults`.
+    fn apply_defaults(data: &mut DataObj);
+    /// Writes every field.
+    fn write_to(&self, data: &mut DataObj);
+}
+
 /// A smart pointer to `obs_data_t`
 pub struct DataObj<'parent> {
     raw: *mut obs_data_t,
@@ -211,6 +395,20 @@ impl DataObj<'_> {
             None
         }
     }
+    /// Sets a property on this object.
+    pub fn set<T: IntoDataItem + ?Sized, N: Into<ObsString>>(&mut self, name: N, value: &T) {
+        let name = name.into();
+        unsafe { value.set_item(self.raw, &name) }
+    }
+    /// Sets the default of a property, used when it has not been set.
+    pub fn set_default<T: IntoDataItem + ?Sized, N: Into<ObsString>>(
+        &mut self,
+        name: N,
+        value: &T,
+    ) {
+        let name = name.into();
+        unsafe { value.set_default_item(self.raw, &name) }
+    }
     /// Creates a JSON representation of this object.
     pub fn get_json(&self) -> Option<String> {
         unsafe {
@@ -236,6 +434,103 @@ impl DataObj<'_> {
             obs_data_erase(self.raw, name.as_ptr());
         }
     }
+
+    fn has_item(&self, name: &ObsString) -> bool {
+        unsafe {
+            let mut item_ptr = obs_data_item_byname(self.raw, name.as_ptr());
+            if item_ptr.is_null() {
+                false
+            } else {
+                obs_data_item_release(&mut item_ptr);
+                true
+            }
+        }
+    }
+
+    /// Sets a vector, which is stored as an object with `x` and `y` members.
+    pub fn set_vec2(&mut self, name: impl Into<ObsString>, value: &Vec2) {
+        let name = name.into();
+        let value: vec2 = value.clone().into();
+        unsafe { obs_data_set_vec2(self.raw, name.as_ptr(), &value) }
+    }
+
+    pub fn get_vec2(&self, name: impl Into<ObsString>) -> Option<Vec2> {
+        let name = name.into();
+        if !self.has_item(&name) {
+            return None;
+        }
+        let mut value = vec2::default();
+        unsafe { obs_data_get_vec2(self.raw, name.as_ptr(), &mut value) };
+        Some(value.into())
+    }
+
+    /// Sets a vector, which is stored as an object with `x`, `y` and `z`
+    /// members.
+    pub fn set_vec3(&mut self, name: impl Into<ObsString>, value: &Vec3) {
+        let name = name.into();
+        let value: vec3 = value.clone().into();
+        unsafe { obs_data_set_vec3(self.raw, name.as_ptr(), &value) }
+    }
+
+    pub fn get_vec3(&self, name: impl Into<ObsString>) -> Option<Vec3> {
+        let name = name.into();
+        if !self.has_item(&name) {
+            return None;
+        }
+        let mut value = vec3::default();
+        unsafe { obs_data_get_vec3(self.raw, name.as_ptr(), &mut value) };
+        Some(value.into())
+    }
+
+    /// Sets a vector, which is stored as an object with `x`, `y`, `z` and `w`
+    /// members.
+    pub fn set_vec4(&mut self, name: impl Into<ObsString>, value: &Vec4) {
+        let name = name.into();
+        let value: vec4 = value.clone().into();
+        unsafe { obs_data_set_vec4(self.raw, name.as_ptr(), &value) }
+    }
+
+    pub fn get_vec4(&self, name: impl Into<ObsString>) -> Option<Vec4> {
+        let name = name.into();
+        if !self.has_item(&name) {
+            return None;
+        }
+        let mut value = vec4::default();
+        unsafe { obs_data_get_vec4(self.raw, name.as_ptr(), &mut value) };
+        Some(value.into())
+    }
+
+    /// Sets a quaternion given as the `x`, `y`, `z` and `w` components of a
+    /// [`Vec4`], which shares the layout of the OBS `quat`.
+    pub fn set_quat(&mut self, name: impl Into<ObsString>, value: &Vec4) {
+        let name = name.into();
+        let value: vec4 = value.clone().into();
+        unsafe {
+            obs_data_set_quat(
+                self.raw,
+                name.as_ptr(),
+                &value as *const vec4 as *const quat,
+            )
+        }
+    }
+
+    /// Reads a quaternion as the `x`, `y`, `z` and `w` components of a
+    /// [`Vec4`].
+    pub fn get_quat(&self, name: impl Into<ObsString>) -> Option<Vec4> {
+        let name = name.into();
+        if !self.has_item(&name) {
+            return None;
+        }
+        let mut value = vec4::default();
+        unsafe {
+            obs_data_get_quat(
+                self.raw,
+                name.as_ptr(),
+                &mut value as *mut vec4 as *mut quat,
+            )
+        };
+        Some(value.into())
+    }
 }
 
 impl Drop for DataObj<'_> {
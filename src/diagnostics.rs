@@ -0,0 +1,137 @@
+use crate::source::audio::MonitoringType;
+use obs_sys::{
+    obs_enum_sources, obs_source_audio_active, obs_source_enabled, obs_source_enum_filters,
+    obs_source_get_audio_mixers, obs_source_get_balance_value, obs_source_get_id,
+    obs_source_get_monitoring_type, obs_source_get_name, obs_source_get_output_flags,
+    obs_source_get_sync_offset, obs_source_get_volume, obs_source_muted, obs_source_t,
+    MAX_AUDIO_MIXES, OBS_SOURCE_AUDIO,
+};
+use serde_json::{json, Value};
+use std::convert::TryFrom;
+use std::ffi::{c_void, CStr};
+use std::os::raw::c_char;
+
+/// A filter attached to an audio source.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FilterNode {
+    pub name: String,
+    pub id: String,
+    pub enabled: bool,
+}
+
+/// The routing state of a single audio source.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AudioSourceNode {
+    pub name: String,
+    pub id: String,
+    /// The mixer tracks this source is assigned to, starting at 1.
+    pub tracks: Vec<u32>,
+    /// `None` if OBS reported a monitoring type this crate does not know about.
+    pub monitoring: Option<MonitoringType>,
+    pub volume: f32,
+    pub balance: f32,
+    pub muted: bool,
+    pub sync_offset_ns: i64,
+    pub audio_active: bool,
+    /// Filters in the order they are applied.
+    pub filters: Vec<FilterNode>,
+}
+
+/// A snapshot of how the audio of every input source is routed, for debugging
+/// support issues such as a microphone missing from a track.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AudioRoutingGraph {
+    pub sources: Vec<AudioSourceNode>,
+}
+
+impl AudioRoutingGraph {
+    /// Walks all input sources that produce audio.
+    pub fn capture() -> Self {
+        let mut graph = Self::default();
+        unsafe {
+            obs_enum_sources(Some(enum_source), &mut graph as *mut _ as *mut c_void);
+        }
+        graph
+    }
+
+    pub fn to_json(&self) -> Value {
+        Value::Array(
+            self.sources
+                .iter()
+                .map(|source| {
+                    json!({
+                        "name": source.name,
+                        "id": source.id,
+                        "tracks": source.tracks,
+                        "monitoring": source.monitoring.map(|m| format!("{:?}", m)),
+                        "volume": source.volume,
+                        "balance": source.balance,
+                        "muted": source.muted,
+                        "sync_offset_ns": source.sync_offset_ns,
+                        "audio_active": source.audio_active,
+                        "filters": source.filters.iter().map(|filter| json!({
+                            "name": filter.name,
+                            "id": filter.id,
+                            "enabled": filter.enabled,
+                        })).collect::<Vec<_>>(),
+                    })
+                })
+                .collect(),
+        )
+    }
+}
+
+unsafe fn to_string(ptr: *const c_char) -> String {
+    if ptr.is_null() {
+        String::new()
+    } else {
+        CStr::from_ptr(ptr).to_string_lossy().into_owned()
+    }
+}
+
+unsafe extern "C" fn enum_source(param: *mut c_void, source: *mut obs_source_t) -> bool {
+    let graph = &mut *(param as *mut AudioRoutingGraph);
+
+    if obs_source_get_output_flags(source) & OBS_SOURCE_AUDIO == 0 {
+        return true;
+    }
+
+    let mixers = obs_source_get_audio_mixers(source);
+    let mut node = AudioSourceNode {
+        name: to_string(obs_source_get_name(source)),
+        id: to_string(obs_source_get_id(source)),
+        tracks: (0..MAX_AUDIO_MIXES)
+            .filter(|track| mixers & (1 << track) != 0)
+            .map(|track| track + 1)
+            .collect(),
+        monitoring: MonitoringType::try_from(obs_source_get_monitoring_type(source)).ok(),
+        volume: obs_source_get_volume(source),
+        balance: obs_source_get_balance_value(source),
+        muted: obs_source_muted(source),
+        sync_offset_ns: obs_source_get_sync_offset(source),
+        audio_active: obs_source_audio_active(source),
+        filters: vec![],
+    };
+
+    obs_source_enum_filters(
+        source,
+        Some(enum_filter),
+        &mut node.filters as *mut _ as *mut c_void,
+    );
+
+    graph.sources.push(node);
+    true
+}
+
+unsafe extern "C" fn enum_filter(
+    _parent: *mut obs_source_t,
+    child: *mut obs_source_t,
+    param: *mut c_void,
+) {
+    let filters = &mut *(param as *mut Vec<FilterNode>);
+    filters.push(FilterNode {
+        name: to_string(obs_source_get_name(child)),
+        id: to_string(obs_source_get_id(child)),
+        enabled: obs_source_enabled(child),
+    });
+}
@@ -0,0 +1,123 @@
+//! A [`Display`] renders into a native window, which is how the preview and
+//! projectors of OBS are drawn. Plugins embedding their own preview create a
+//! display for their window and draw into it from a callback:
+//!
+//! ```ignore
+//! let mut display = Display::new(window, width, height, 0xFF000000)?;
+//! display.add_draw_callback(|render, _cx, _cy| render.render_main_texture());
+//! ```
+
+use crate::graphics::GraphicsColorFormat;
+use crate::source::context::VideoRenderContext;
+use crate::unwind::handle_unwind;
+use obs_sys::{
+    gs_init_data, gs_window, gs_zstencil_format_GS_ZS_NONE, obs_display_add_draw_callback,
+    obs_display_create, obs_display_destroy, obs_display_enabled, obs_display_remove_draw_callback,
+    obs_display_resize, obs_display_set_background_color, obs_display_set_enabled,
+    obs_display_size, obs_display_t,
+};
+
+use std::ffi::c_void;
+
+type DrawCallback = Box<dyn FnMut(&mut VideoRenderContext, u32, u32) + Send>;
+
+/// Wrapper around [`obs_display_t`], a swap chain drawing into a native
+/// window.
+///
+/// Draw callbacks run on the graphics thread, and are removed along with the
+/// display when it is dropped.
+///
+/// See [OBS documentation](https://obsproject.com/docs/reference-core.html#displays)
+pub struct Display {
+    raw: *mut obs_display_t,
+    callbacks: Vec<*mut DrawCallback>,
+}
+
+impl Display {
+    /// Creates a display for `window`, filling it with `background_color`
+    /// (in `0xAABBGGRR` format) before the draw callbacks run.
+    ///
+    /// Returns `None` if OBS fails to create the swap chain.
+    pub fn new(window: gs_window, width: u32, height: u32, background_color: u32) -> Option<Self> {
+        let init = gs_init_data {
+            window,
+            cx: width,
+            cy: height,
+            num_backbuffers: 0,
+            format: GraphicsColorFormat::BGRA.as_raw(),
+            zsformat: gs_zstencil_format_GS_ZS_NONE,
+            adapter: 0,
+        };
+        let raw = unsafe { obs_display_create(&init, background_color) };
+        if raw.is_null() {
+            None
+        } else {
+            Some(Self {
+                raw,
+                callbacks: vec![],
+            })
+        }
+    }
+
+    /// Adds a callback drawing into the display, called with its size.
+    pub fn add_draw_callback<F: FnMut(&mut VideoRenderContext, u32, u32) + Send + 'static>(
+        &mut self,
+        func: F,
+    ) {
+        let data: *mut DrawCallback = Box::into_raw(Box::new(Box::new(func)));
+        unsafe {
+            obs_display_add_draw_callback(self.raw, Some(draw_callback), data as *mut c_void);
+        }
+        self.callbacks.push(data);
+    }
+
+    /// Resizes the swap chain, which should follow the size of the window.
+    pub fn resize(&mut self, width: u32, height: u32) {
+        unsafe { obs_display_resize(self.raw, width, height) }
+    }
+
+    pub fn size(&self) -> (u32, u32) {
+        let mut width = 0;
+        let mut height = 0;
+        unsafe { obs_display_size(self.raw, &mut width, &mut height) };
+        (width, height)
+    }
+
+    pub fn set_enabled(&mut self, enabled: bool) {
+        unsafe { obs_display_set_enabled(self.raw, enabled) }
+    }
+
+    pub fn enabled(&self) -> bool {
+        unsafe { obs_display_enabled(self.raw) }
+    }
+
+    pub fn set_background_color(&mut self, color: u32) {
+        unsafe { obs_display_set_background_color(self.raw, color) }
+    }
+
+    pub fn as_ptr(&self) -> *mut obs_display_t {
+        self.raw
+    }
+}
+
+impl Drop for Display {
+    fn drop(&mut self) {
+        unsafe {
+            for data in self.callbacks.drain(..) {
+                obs_display_remove_draw_callback(
+                    self.raw,
+                    Some(draw_callback),
+                    data as *mut c_void,
+                );
+                drop(Box::from_raw(data));
+            }
+            obs_display_destroy(self.raw);
+        }
+    }
+}
+
+unsafe extern "C" fn draw_callback(param: *mut c_void, cx: u32, cy: u32) {
+    let func = &mut *(param as *mut DrawCallback);
+    let mut render = VideoRenderContext::new();
+    handle_unwind::<Display, _>("draw_callback", || func(&mut render, cx, cy));
+}
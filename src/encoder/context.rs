@@ -0,0 +1,13 @@
+use crate::prelude::DataObj;
+use crate::source::context::GlobalContext;
+
+pub struct CreatableEncoderContext<'a> {
+    pub settings: DataObj<'a>,
+    pub global: &'a mut GlobalContext,
+}
+
+impl<'a> CreatableEncoderContext<'a> {
+    pub(crate) unsafe fn from_raw(settings: DataObj<'a>, global: &'a mut GlobalContext) -> Self {
+        Self { settings, global }
+    }
+}
@@ -0,0 +1,226 @@
+use super::context::CreatableEncoderContext;
+use super::traits::*;
+use super::{EncoderContext, EncoderFrame, EncoderOutput, EncoderTexture};
+use crate::data::DataObj;
+use crate::source::context::GlobalContext;
+use crate::source::properties::Properties;
+use crate::unwind::handle_unwind;
+use crate::video::VideoFormat;
+use crate::wrapper::PtrWrapper;
+use std::convert::TryFrom;
+use std::ffi::c_void;
+use std::mem::forget;
+use std::os::raw::c_char;
+
+use obs_sys::{
+    encoder_frame, encoder_packet, obs_data_t, obs_encoder_get_height,
+    obs_encoder_get_preferred_video_format, obs_encoder_get_type, obs_encoder_t,
+    obs_encoder_type_OBS_ENCODER_VIDEO, obs_encoder_video, obs_properties, size_t,
+    video_format_VIDEO_FORMAT_NONE, video_output_get_format,
+};
+
+struct DataWrapper<D> {
+    data: Option<D>,
+    encoder: *mut obs_encoder_t,
+    /// The format and height of the frames, which are fixed while the encoder
+    /// exists.
+    frame_format: Option<(VideoFormat, u32)>,
+    /// The data of the last packet, kept until the next call.
+    packet: Vec<u8>,
+    /// The headers OBS was last given, which it reads until asking again.
+    extra_data: Vec<u8>,
+    sei_data: Vec<u8>,
+}
+
+impl<D> DataWrapper<D> {
+    fn new(data: Option<D>, encoder: *mut obs_encoder_t) -> Self {
+        Self {
+            data,
+            encoder,
+            frame_format: None,
+            packet: Vec::new(),
+            extra_data: Vec::new(),
+            sei_data: Vec::new(),
+        }
+    }
+
+    unsafe fn frame_format(&mut self) -> (VideoFormat, u32) {
+        let encoder = self.encoder;
+        *self.frame_format.get_or_insert_with(|| {
+            if obs_encoder_get_type(encoder) != obs_encoder_type_OBS_ENCODER_VIDEO {
+                return (VideoFormat::None, 0);
+            }
+            let mut format = obs_encoder_get_preferred_video_format(encoder);
+            if format == video_format_VIDEO_FORMAT_NONE {
+                format = video_output_get_format(obs_encoder_video(encoder));
+            }
+            let format = VideoFormat::try_from(format).unwrap_or(VideoFormat::None);
+            (format, obs_encoder_get_height(encoder))
+        })
+    }
+}
+
+pub unsafe extern "C" fn get_name<D, F: GetNameEncoder<D>>(
+    _type_data: *mut c_void,
+) -> *const c_char {
+    handle_unwind::<F, _>("get_name", || F::get_name().as_cached_ptr()).unwrap_or(std::ptr::null())
+}
+
+pub unsafe extern "C" fn create_default_data<D>(
+    _settings: *mut obs_data_t,
+    encoder: *mut obs_encoder_t,
+) -> *mut c_void {
+    let data = Box::new(DataWrapper::<D>::new(None, encoder));
+    Box::into_raw(data) as *mut c_void
+}
+
+pub unsafe extern "C" fn create<D, F: CreatableEncoder<D>>(
+    settings: *mut obs_data_t,
+    encoder: *mut obs_encoder_t,
+) -> *mut c_void {
+    let mut global = GlobalContext::new();
+    let settings = DataObj::from_raw(settings);
+    let mut create = CreatableEncoderContext::from_raw(settings, &mut global);
+
+    let data = handle_unwind::<F, _>("create", || {
+        F::create(&mut create, EncoderContext { encoder })
+    });
+    forget(create.settings);
+
+    match data.flatten() {
+        Some(data) => Box::into_raw(Box::new(DataWrapper::new(Some(data), encoder))) as *mut c_void,
+        None => std::ptr::null_mut(),
+    }
+}
+
+pub unsafe extern "C" fn destroy<D>(data: *mut c_void) {
+    let wrapper: Box<DataWrapper<D>> = Box::from_raw(data as *mut DataWrapper<D>);
+    handle_unwind::<D, _>("drop", || drop(wrapper));
+}
+
+pub unsafe extern "C" fn encode<D, F: EncodeEncoder<D>>(
+    data: *mut c_void,
+    frame: *mut encoder_frame,
+    packet: *mut encoder_packet,
+    received_packet: *mut bool,
+) -> bool {
+    let wrapper: &mut DataWrapper<D> = &mut *(data as *mut DataWrapper<D>);
+    let (format, height) = wrapper.frame_format();
+    let frame = match EncoderFrame::from_raw(frame, format, height) {
+        Some(frame) => frame,
+        None => return false,
+    };
+    let DataWrapper {
+        data,
+        encoder,
+        packet: buffer,
+        ..
+    } = wrapper;
+    let mut output = EncoderOutput::from_raw(*encoder, packet, received_packet, buffer);
+    handle_unwind::<F, _>("encode", || F::encode(data, &frame, &mut output)).unwrap_or(false)
+}
+
+pub unsafe extern "C" fn encode_texture<D, F: EncodeTextureEncoder<D>>(
+    data: *mut c_void,
+    handle: u32,
+    pts: i64,
+    lock_key: u64,
+    next_key: *mut u64,
+    packet: *mut encoder_packet,
+    received_packet: *mut bool,
+) -> bool {
+    let DataWrapper {
+        data,
+        encoder,
+        packet: buffer,
+        ..
+    } = &mut *(data as *mut DataWrapper<D>);
+    let mut texture = match EncoderTexture::from_raw(handle, pts, lock_key, next_key) {
+        Some(texture) => texture,
+        None => return false,
+    };
+    let mut output = EncoderOutput::from_raw(*encoder, packet, received_packet, buffer);
+    handle_unwind::<F, _>("encode_texture", || {
+        F::encode_texture(data, &mut texture, &mut output)
+    })
+    .unwrap_or(false)
+}
+
+pub unsafe extern "C" fn get_frame_size<D, F: GetFrameSizeEncoder<D>>(data: *mut c_void) -> size_t {
+    let wrapper: &mut DataWrapper<D> = &mut *(data as *mut DataWrapper<D>);
+    handle_unwind::<F, _>("get_frame_size", || F::get_frame_size(&mut wrapper.data)).unwrap_or(0)
+        as size_t
+}
+
+/// Keeps `bytes` in `buffer` and points OBS at them.
+unsafe fn write_buffer(
+    bytes: Option<Vec<u8>>,
+    buffer: &mut Vec<u8>,
+    data: *mut *mut u8,
+    size: *mut size_t,
+) -> bool {
+    match bytes {
+        Some(bytes) if !data.is_null() && !size.is_null() => {
+            *buffer = bytes;
+            *data = buffer.as_mut_ptr();
+            *size = buffer.len() as size_t;
+            true
+        }
+        _ => false,
+    }
+}
+
+pub unsafe extern "C" fn get_extra_data<D, F: GetExtraDataEncoder<D>>(
+    data: *mut c_void,
+    extra_data: *mut *mut u8,
+    size: *mut size_t,
+) -> bool {
+    let wrapper: &mut DataWrapper<D> = &mut *(data as *mut DataWrapper<D>);
+    let bytes = handle_unwind::<F, _>("get_extra_data", || F::get_extra_data(&mut wrapper.data));
+    write_buffer(bytes.flatten(), &mut wrapper.extra_data, extra_data, size)
+}
+
+pub unsafe extern "C" fn get_sei_data<D, F: GetSeiDataEncoder<D>>(
+    data: *mut c_void,
+    sei_data: *mut *mut u8,
+    size: *mut size_t,
+) -> bool {
+    let wrapper: &mut DataWrapper<D> = &mut *(data as *mut DataWrapper<D>);
+    let bytes = handle_unwind::<F, _>("get_sei_data", || F::get_sei_data(&mut wrapper.data));
+    write_buffer(bytes.flatten(), &mut wrapper.sei_data, sei_data, size)
+}
+
+pub unsafe extern "C" fn update<D, F: UpdateEncoder<D>>(
+    data: *mut c_void,
+    settings: *mut obs_data_t,
+) -> bool {
+    let wrapper: &mut DataWrapper<D> = &mut *(data as *mut DataWrapper<D>);
+    let mut settings = DataObj::from_raw(settings);
+    let updated = handle_unwind::<F, _>("update", || F::update(&mut wrapper.data, &mut settings));
+    forget(settings);
+    updated.unwrap_or(false)
+}
+
+pub unsafe extern "C" fn get_properties<D, F: GetPropertiesEncoder<D>>(
+    data: *mut c_void,
+) -> *mut obs_properties {
+    // OBS passes no data when asking for the properties of the type.
+    let mut none = None;
+    let data = match (data as *mut DataWrapper<D>).as_mut() {
+        Some(wrapper) => &mut wrapper.data,
+        None => &mut none,
+    };
+
+    handle_unwind::<F, _>("get_properties", || {
+        let mut properties = Properties::new();
+        F::get_properties(data, &mut properties);
+        properties.into_raw()
+    })
+    .unwrap_or(std::ptr::null_mut())
+}
+
+pub unsafe extern "C" fn get_defaults<D, F: GetDefaultsEncoder<D>>(settings: *mut obs_data_t) {
+    let mut settings = DataObj::from_raw(settings);
+    handle_unwind::<F, _>("get_defaults", || F::get_defaults(&mut settings));
+    forget(settings);
+}
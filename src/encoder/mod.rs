@@ -0,0 +1,477 @@
+//! Encoders turn raw frames into compressed packets, which are handed to
+//! encoded outputs such as muxers and streaming protocols. This module wraps
+//! the encoders and packets an output receives, so they can be read without
+//! touching raw pointers, and registers encoder types of a plugin:
+//!
+//! ```ignore
+//! let encoder = load_context
+//!     .create_encoder_builder::<HardwareEncoder, Data>()
+//!     .enable_get_name()
+//!     .enable_create()
+//!     .enable_encode()
+//!     .enable_encode_texture()
+//!     .build();
+//! load_context.register_encoder(encoder).is_ok()
+//! ```
+
+#![allow(non_upper_case_globals)]
+
+use crate::native_enum;
+use crate::video::VideoFormat;
+use obs_sys::{
+    encoder_frame, encoder_packet, obs_encoder_active, obs_encoder_get_codec,
+    obs_encoder_get_extra_data, obs_encoder_get_id, obs_encoder_get_name, obs_encoder_get_type,
+    obs_encoder_info, obs_encoder_packet_ref, obs_encoder_packet_release, obs_encoder_t,
+    obs_encoder_type, obs_encoder_type_OBS_ENCODER_AUDIO, obs_encoder_type_OBS_ENCODER_VIDEO,
+    size_t, MAX_AV_PLANES, OBS_ENCODER_CAP_PASS_TEXTURE,
+};
+use paste::item;
+
+use std::{convert::TryFrom, ffi::CStr, marker::PhantomData, slice};
+
+pub mod context;
+mod ffi;
+pub mod traits;
+
+pub use context::*;
+pub use traits::*;
+
+native_enum!(EncoderType, obs_encoder_type {
+    Audio => OBS_ENCODER_AUDIO,
+    Video => OBS_ENCODER_VIDEO
+});
+
+/// Context wrapping an OBS encoder.
+///
+/// See [OBS documentation](https://obsproject.com/docs/reference-encoders.html#c.obs_encoder_t)
+pub struct EncoderContext {
+    encoder: *mut obs_encoder_t,
+}
+
+impl EncoderContext {
+    pub(crate) unsafe fn from_raw(encoder: *mut obs_encoder_t) -> Option<Self> {
+        if encoder.is_null() {
+            None
+        } else {
+            Some(Self { encoder })
+        }
+    }
+
+    pub fn encoder_id(&self) -> Option<&str> {
+        unsafe { ptr_to_str(obs_encoder_get_id(self.encoder)) }
+    }
+
+    pub fn name(&self) -> Option<&str> {
+        unsafe { ptr_to_str(obs_encoder_get_name(self.encoder)) }
+    }
+
+    /// The codec of the encoder, e.g. `"h264"` or `"aac"`.
+    pub fn codec(&self) -> Option<&str> {
+        unsafe { ptr_to_str(obs_encoder_get_codec(self.encoder)) }
+    }
+
+    pub fn encoder_type(&self) -> Option<EncoderType> {
+        EncoderType::try_from(unsafe { obs_encoder_get_type(self.encoder) }).ok()
+    }
+
+    pub fn active(&self) -> bool {
+        unsafe { obs_encoder_active(self.encoder) }
+    }
+
+    /// Returns the codec headers of the encoder, such as the SPS and PPS of
+    /// an h264 encoder, which muxers write before the first packet.
+    ///
+    /// See [OBS documentation](https://obsproject.com/docs/reference-encoders.html#c.obs_encoder_get_extra_data)
+    pub fn extra_data(&self) -> Option<&[u8]> {
+        let mut data = std::ptr::null_mut();
+        let mut size = 0;
+        unsafe {
+            if obs_encoder_get_extra_data(self.encoder, &mut data, &mut size) && !data.is_null() {
+                Some(slice::from_raw_parts(data, size as usize))
+            } else {
+                None
+            }
+        }
+    }
+
+    pub fn as_ptr(&self) -> *mut obs_encoder_t {
+        self.encoder
+    }
+}
+
+unsafe fn ptr_to_str<'a>(ptr: *const std::os::raw::c_char) -> Option<&'a str> {
+    if ptr.is_null() {
+        None
+    } else {
+        CStr::from_ptr(ptr).to_str().ok()
+    }
+}
+
+/// A packet of encoded data, borrowed from OBS for the duration of a
+/// callback. Use [`EncoderPacket::to_owned`] to keep it around longer.
+///
+/// See [OBS documentation](https://obsproject.com/docs/reference-encoders.html#c.encoder_packet)
+pub struct EncoderPacket<'a> {
+    packet: *mut encoder_packet,
+    __data: PhantomData<&'a encoder_packet>,
+}
+
+impl<'a> EncoderPacket<'a> {
+    /// Wraps a packet passed to a callback by OBS, returning `None` if it is
+    /// null.
+    ///
+    /// # Safety
+    /// `packet` must be null or point to a packet that lives for `'a`.
+    pub unsafe fn from_raw(packet: *mut encoder_packet) -> Option<Self> {
+        if packet.is_null() {
+            None
+        } else {
+            Some(Self {
+                packet,
+                __data: PhantomData,
+            })
+        }
+    }
+
+    fn raw(&self) -> &encoder_packet {
+        unsafe { &*self.packet }
+    }
+
+    pub fn data(&self) -> &[u8] {
+        let raw = self.raw();
+        if raw.data.is_null() {
+            &[]
+        } else {
+            unsafe { slice::from_raw_parts(raw.data, raw.size as usize) }
+        }
+    }
+
+    /// Presentation timestamp, in units of [`timebase`](Self::timebase).
+    pub fn pts(&self) -> i64 {
+        self.raw().pts
+    }
+
+    /// Decode timestamp, in units of [`timebase`](Self::timebase).
+    pub fn dts(&self) -> i64 {
+        self.raw().dts
+    }
+
+    /// The timebase of the timestamps as `(numerator, denominator)`.
+    pub fn timebase(&self) -> (i32, i32) {
+        (self.raw().timebase_num, self.raw().timebase_den)
+    }
+
+    /// Decode timestamp in microseconds.
+    pub fn dts_usec(&self) -> i64 {
+        self.raw().dts_usec
+    }
+
+    /// Decode timestamp in microseconds on the system clock.
+    pub fn sys_dts_usec(&self) -> i64 {
+        self.raw().sys_dts_usec
+    }
+
+    pub fn keyframe(&self) -> bool {
+        self.raw().keyframe
+    }
+
+    pub fn encoder_type(&self) -> Option<EncoderType> {
+        EncoderType::try_from(self.raw().type_).ok()
+    }
+
+    /// The audio track the packet belongs to.
+    pub fn track_index(&self) -> usize {
+        self.raw().track_idx as usize
+    }
+
+    /// Priority of a video packet, used to decide which packets to drop.
+    pub fn priority(&self) -> i32 {
+        self.raw().priority
+    }
+
+    /// If this packet is dropped, the next packet must have at least this
+    /// priority for transmission to continue.
+    pub fn drop_priority(&self) -> i32 {
+        self.raw().drop_priority
+    }
+
+    /// The encoder that produced the packet.
+    pub fn encoder(&self) -> Option<EncoderContext> {
+        unsafe { EncoderContext::from_raw(self.raw().encoder) }
+    }
+
+    /// Takes a reference to the packet data, so it can be kept after the
+    /// callback returns, e.g. in a send queue.
+    pub fn to_owned(&self) -> OwnedEncoderPacket {
+        let mut packet = encoder_packet::default();
+        unsafe { obs_encoder_packet_ref(&mut packet, self.packet) };
+        OwnedEncoderPacket { packet }
+    }
+}
+
+/// An [`EncoderPacket`] holding a reference to its data, which is released
+/// when dropped.
+pub struct OwnedEncoderPacket {
+    packet: encoder_packet,
+}
+
+// The packet data is reference counted atomically by OBS.
+unsafe impl Send for OwnedEncoderPacket {}
+
+impl OwnedEncoderPacket {
+    pub fn as_packet(&self) -> EncoderPacket<'_> {
+        EncoderPacket {
+            packet: &self.packet as *const _ as *mut _,
+            __data: PhantomData,
+        }
+    }
+}
+
+impl Drop for OwnedEncoderPacket {
+    fn drop(&mut self) {
+        unsafe { obs_encoder_packet_release(&mut self.packet) }
+    }
+}
+
+/// Raw video or audio handed to [`EncodeEncoder::encode`].
+///
+/// See [OBS documentation](https://obsproject.com/docs/reference-encoders.html#c.encoder_frame)
+pub struct EncoderFrame<'a> {
+    frame: &'a encoder_frame,
+    format: VideoFormat,
+    height: u32,
+}
+
+impl<'a> EncoderFrame<'a> {
+    /// `format` and `height` are those of video frames, and
+    /// [`VideoFormat::None`] for audio.
+    pub(crate) unsafe fn from_raw(
+        frame: *mut encoder_frame,
+        format: VideoFormat,
+        height: u32,
+    ) -> Option<Self> {
+        Some(Self {
+            frame: frame.as_ref()?,
+            format,
+            height,
+        })
+    }
+
+    /// Presentation timestamp, in units of the timebase of the encoder.
+    pub fn pts(&self) -> i64 {
+        self.frame.pts
+    }
+
+    /// The number of audio frames, or 0 for video.
+    pub fn frames(&self) -> u32 {
+        self.frame.frames
+    }
+
+    /// The format of a video frame, or [`VideoFormat::None`] for audio.
+    pub fn format(&self) -> VideoFormat {
+        self.format
+    }
+
+    /// The number of bytes between two rows of a video plane, or the size of
+    /// an audio plane.
+    pub fn linesize(&self, plane: usize) -> usize {
+        if plane >= MAX_AV_PLANES as usize {
+            return 0;
+        }
+        self.frame.linesize[plane] as usize
+    }
+
+    /// Returns the whole of `plane`, including the line padding of video.
+    /// Audio has a plane per channel unless its format is interleaved.
+    pub fn plane(&self, plane: usize) -> Option<&[u8]> {
+        if plane >= MAX_AV_PLANES as usize {
+            return None;
+        }
+        let ptr = self.frame.data[plane];
+        if ptr.is_null() {
+            return None;
+        }
+        let len = match self.format {
+            VideoFormat::None => self.linesize(plane),
+            format if plane < format.planes() => {
+                self.linesize(plane) * format.plane_height(plane, self.height) as usize
+            }
+            _ => return None,
+        };
+        Some(unsafe { slice::from_raw_parts(ptr, len) })
+    }
+}
+
+/// A texture shared with [`EncodeTextureEncoder::encode_texture`].
+///
+/// The texture is locked with a keyed mutex: it is acquired with
+/// [`lock_key`](Self::lock_key) and must be released with the key OBS
+/// expects next, which defaults to the lock key.
+pub struct EncoderTexture<'a> {
+    handle: u32,
+    pts: i64,
+    lock_key: u64,
+    next_key: &'a mut u64,
+}
+
+impl<'a> EncoderTexture<'a> {
+    pub(crate) unsafe fn from_raw(
+        handle: u32,
+        pts: i64,
+        lock_key: u64,
+        next_key: *mut u64,
+    ) -> Option<Self> {
+        // GS_INVALID_HANDLE
+        if handle == u32::MAX {
+            return None;
+        }
+        Some(Self {
+            handle,
+            pts,
+            lock_key,
+            next_key: next_key.as_mut()?,
+        })
+    }
+
+    /// The shared handle of the texture, which can be opened with
+    /// `GraphicsTexture::open_shared` or the API of the encoder.
+    pub fn handle(&self) -> u32 {
+        self.handle
+    }
+
+    /// Presentation timestamp, in units of the timebase of the encoder.
+    pub fn pts(&self) -> i64 {
+        self.pts
+    }
+
+    pub fn lock_key(&self) -> u64 {
+        self.lock_key
+    }
+
+    /// Sets the key the texture was released with.
+    pub fn set_next_key(&mut self, key: u64) {
+        *self.next_key = key;
+    }
+}
+
+/// Receives the packet an encode callback produces, if any.
+pub struct EncoderOutput<'a> {
+    encoder: *mut obs_encoder_t,
+    packet: *mut encoder_packet,
+    received: *mut bool,
+    buffer: &'a mut Vec<u8>,
+}
+
+impl<'a> EncoderOutput<'a> {
+    pub(crate) unsafe fn from_raw(
+        encoder: *mut obs_encoder_t,
+        packet: *mut encoder_packet,
+        received: *mut bool,
+        buffer: &'a mut Vec<u8>,
+    ) -> Self {
+        Self {
+            encoder,
+            packet,
+            received,
+            buffer,
+        }
+    }
+
+    /// Hands a packet to OBS, with timestamps in units of the timebase of
+    /// the encoder. OBS fills in the timebase and copies the data, so it only
+    /// needs to live until the callback returns.
+    pub fn write(&mut self, data: &[u8], pts: i64, dts: i64, keyframe: bool) {
+        self.buffer.clear();
+        self.buffer.extend_from_slice(data);
+        unsafe {
+            if let Some(packet) = self.packet.as_mut() {
+                packet.data = self.buffer.as_mut_ptr();
+                packet.size = self.buffer.len() as size_t;
+                packet.pts = pts;
+                packet.dts = dts;
+                packet.keyframe = keyframe;
+                packet.type_ = obs_encoder_get_type(self.encoder);
+            }
+            if let Some(received) = self.received.as_mut() {
+                *received = true;
+            }
+        }
+    }
+}
+
+pub struct EncoderInfo {
+    info: Box<obs_encoder_info>,
+}
+
+impl EncoderInfo {
+    /// # Safety
+    /// Creates a raw pointer from a box and could cause UB is misused.
+    pub unsafe fn into_raw(self) -> *mut obs_encoder_info {
+        Box::into_raw(self.info)
+    }
+}
+
+/// The EncoderInfoBuilder that handles creating the [EncoderInfo](https://obsproject.com/docs/reference-encoders.html#c.obs_encoder_info) object.
+///
+/// Works the same way as [`SourceInfoBuilder`](crate::source::SourceInfoBuilder): each trait that
+/// is implemented for the encoder needs to be enabled using this builder. OBS requires
+/// [`EncodeEncoder`] for every encoder, and [`GetFrameSizeEncoder`] for audio encoders.
+pub struct EncoderInfoBuilder<T: Encodable, D> {
+    __encoder: PhantomData<T>,
+    __data: PhantomData<D>,
+    info: obs_encoder_info,
+}
+
+impl<T: Encodable, D> EncoderInfoBuilder<T, D> {
+    pub(crate) fn new() -> Self {
+        Self {
+            __encoder: PhantomData,
+            __data: PhantomData,
+            info: obs_encoder_info {
+                id: T::get_id().as_cached_ptr(),
+                type_: T::get_type().into(),
+                codec: T::get_codec().as_cached_ptr(),
+                create: Some(ffi::create_default_data::<D>),
+                destroy: Some(ffi::destroy::<D>),
+                type_data: std::ptr::null_mut(),
+                ..Default::default()
+            },
+        }
+    }
+
+    pub fn build(mut self) -> EncoderInfo {
+        if self.info.encode_texture.is_some() {
+            self.info.caps |= OBS_ENCODER_CAP_PASS_TEXTURE;
+        }
+
+        EncoderInfo {
+            info: Box::new(self.info),
+        }
+    }
+}
+
+macro_rules! impl_encoder_builder {
+    ($($f:ident => $t:ident)*) => ($(
+        item! {
+            impl<D, T: Encodable + [<$t>]<D>> EncoderInfoBuilder<T, D> {
+                pub fn [<enable_$f>](mut self) -> Self {
+                    self.info.[<$f>] = Some(ffi::[<$f>]::<D, T>);
+                    self
+                }
+            }
+        }
+    )*)
+}
+
+impl_encoder_builder! {
+    get_name => GetNameEncoder
+    create => CreatableEncoder
+    encode => EncodeEncoder
+    encode_texture => EncodeTextureEncoder
+    get_frame_size => GetFrameSizeEncoder
+    get_extra_data => GetExtraDataEncoder
+    get_sei_data => GetSeiDataEncoder
+    update => UpdateEncoder
+    get_properties => GetPropertiesEncoder
+    get_defaults => GetDefaultsEncoder
+}
@@ -0,0 +1,83 @@
+use super::context::CreatableEncoderContext;
+use super::{EncoderContext, EncoderFrame, EncoderOutput, EncoderTexture, EncoderType};
+use crate::data::DataObj;
+use crate::source::properties::Properties;
+use crate::string::ObsString;
+
+pub trait Encodable {
+    fn get_id() -> ObsString;
+    fn get_type() -> EncoderType;
+    /// The codec of the packets, e.g. `"h264"` or `"aac"`, which outputs use
+    /// to decide whether they can take them.
+    fn get_codec() -> ObsString;
+}
+
+pub trait GetNameEncoder<D> {
+    fn get_name() -> ObsString;
+}
+
+pub trait CreatableEncoder<D> {
+    /// Creates the encoder when an output using it starts. Returning `None`,
+    /// e.g. because a hardware encoder is not available, fails the start.
+    fn create(create: &mut CreatableEncoderContext, encoder: EncoderContext) -> Option<D>;
+}
+
+/// Encodes raw frames or audio copied to system memory. Every encoder needs
+/// this, including those also implementing [`EncodeTextureEncoder`], which
+/// OBS falls back to when textures cannot be shared.
+pub trait EncodeEncoder<D> {
+    /// Encodes `frame`, writing a packet to `output` once one is ready.
+    /// Returning `false` is an encoder error, which stops the outputs using
+    /// the encoder.
+    fn encode(data: &mut Option<D>, frame: &EncoderFrame, output: &mut EncoderOutput) -> bool;
+}
+
+/// Encodes frames straight from a texture shared by OBS, sparing hardware
+/// encoders the copy through system memory.
+pub trait EncodeTextureEncoder<D> {
+    /// Encodes `texture`, writing a packet to `output` once one is ready,
+    /// see [`EncodeEncoder::encode`].
+    fn encode_texture(
+        data: &mut Option<D>,
+        texture: &mut EncoderTexture,
+        output: &mut EncoderOutput,
+    ) -> bool;
+}
+
+/// Required by audio encoders.
+pub trait GetFrameSizeEncoder<D> {
+    /// The number of audio frames the encoder takes at a time.
+    fn get_frame_size(data: &mut Option<D>) -> usize;
+}
+
+/// Provides the codec headers muxers write before the first packet, such as
+/// the SPS and PPS of an h264 encoder.
+pub trait GetExtraDataEncoder<D> {
+    /// Returns the headers, or `None` if the encoder has none. The bytes are
+    /// kept until the encoder is destroyed or this is called again.
+    fn get_extra_data(data: &mut Option<D>) -> Option<Vec<u8>>;
+}
+
+/// Provides the supplemental enhancement information of a video encoder,
+/// which OBS sends ahead of the first keyframe.
+pub trait GetSeiDataEncoder<D> {
+    /// Returns the SEI, or `None` if the encoder has none. The bytes are kept
+    /// until the encoder is destroyed or this is called again.
+    fn get_sei_data(data: &mut Option<D>) -> Option<Vec<u8>>;
+}
+
+pub trait UpdateEncoder<D> {
+    /// Applies changed settings while the encoder is running, e.g. a new
+    /// bitrate. Returns `false` if they could not be applied.
+    fn update(data: &mut Option<D>, settings: &mut DataObj) -> bool;
+}
+
+pub trait GetPropertiesEncoder<D> {
+    /// `data` is `None` when OBS asks for the properties of the type rather
+    /// than of a running encoder.
+    fn get_properties(data: &mut Option<D>, properties: &mut Properties);
+}
+
+pub trait GetDefaultsEncoder<D> {
+    fn get_defaults(settings: &mut DataObj);
+}
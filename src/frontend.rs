@@ -0,0 +1,203 @@
+//! Controlling the OBS frontend from a plugin, through `obs-frontend-api`.
+//!
+//! The frontend API is a separate library shipped with the OBS application,
+//! so this module is only available with the `frontend` feature, and plugins
+//! using it can only be loaded by the OBS application itself.
+
+use crate::source::{SourceContext, SourceRef};
+use crate::symbol::{Library, Symbol};
+use obs_sys::obs_source_t;
+
+use std::ffi::CString;
+use std::os::raw::{c_char, c_void};
+use std::ptr::NonNull;
+
+#[link(name = "obs-frontend-api")]
+extern "C" {
+    // The frontend API is not covered by the generated bindings.
+    fn obs_frontend_take_screenshot();
+    fn obs_frontend_take_source_screenshot(source: *mut obs_source_t);
+    fn obs_frontend_preview_program_mode_active() -> bool;
+    fn obs_frontend_set_preview_program_mode(enable: bool);
+    fn obs_frontend_get_current_preview_scene() -> *mut obs_source_t;
+    fn obs_frontend_set_current_preview_scene(scene: *mut obs_source_t);
+    fn obs_frontend_preview_program_trigger_transition();
+    fn obs_frontend_recording_active() -> bool;
+    fn obs_frontend_recording_pause(pause: bool);
+    fn obs_frontend_recording_paused() -> bool;
+    fn obs_frontend_add_dock(dock: *mut c_void) -> *mut c_void;
+}
+
+fn frontend_anchor() -> *const c_void {
+    obs_frontend_take_screenshot as *const c_void
+}
+
+const FRONTEND: Library = Library::new(b"obs-frontend-api\0", frontend_anchor);
+
+// Added in OBS 26.0.
+#[allow(non_upper_case_globals)]
+pub(crate) static obs_frontend_start_virtualcam: Symbol<unsafe extern "C" fn()> =
+    Symbol::new(&FRONTEND, b"obs_frontend_start_virtualcam\0");
+#[allow(non_upper_case_globals)]
+pub(crate) static obs_frontend_stop_virtualcam: Symbol<unsafe extern "C" fn()> =
+    Symbol::new(&FRONTEND, b"obs_frontend_stop_virtualcam\0");
+#[allow(non_upper_case_globals)]
+pub(crate) static obs_frontend_virtualcam_active: Symbol<unsafe extern "C" fn() -> bool> =
+    Symbol::new(&FRONTEND, b"obs_frontend_virtualcam_active\0");
+
+// Added in OBS 30.0, looked up at runtime so that plugins also load on older
+// versions.
+#[allow(non_upper_case_globals)]
+pub(crate) static obs_frontend_add_dock_by_id: Symbol<
+    unsafe extern "C" fn(*const c_char, *const c_char, *mut c_void) -> bool,
+> = Symbol::new(&FRONTEND, b"obs_frontend_add_dock_by_id\0");
+#[allow(non_upper_case_globals)]
+pub(crate) static obs_frontend_remove_dock: Symbol<unsafe extern "C" fn(*const c_char)> =
+    Symbol::new(&FRONTEND, b"obs_frontend_remove_dock\0");
+
+/// A Qt widget created by the plugin with whichever Qt bindings or UI
+/// toolkit it uses, to be handed to the frontend.
+///
+/// # Safety
+/// [`as_qwidget`](Self::as_qwidget) must return a valid `QWidget *` created on
+/// the Qt main thread, whose ownership passes to the frontend once added.
+pub unsafe trait QtWidget {
+    fn as_qwidget(&self) -> *mut c_void;
+}
+
+/// A raw `QWidget *`, for toolkits without their own [`QtWidget`]
+/// implementation.
+pub struct RawQtWidget(NonNull<c_void>);
+
+impl RawQtWidget {
+    /// # Safety
+    /// `widget` must be a `QWidget *` meeting the requirements of
+    /// [`QtWidget`].
+    pub unsafe fn from_raw(widget: *mut c_void) -> Option<Self> {
+        NonNull::new(widget).map(Self)
+    }
+}
+
+unsafe impl QtWidget for RawQtWidget {
+    fn as_qwidget(&self) -> *mut c_void {
+        self.0.as_ptr()
+    }
+}
+
+/// Adds `widget` as a dock titled `title`, listed in the frontend's Docks
+/// menu and saved with the window layout under `id`. Returns `false` if a
+/// dock with `id` already exists, or before OBS 30.0.
+///
+/// Must be called on the Qt main thread, e.g. from
+/// [`Module::post_load`](crate::module::Module::post_load).
+///
+/// See [`supports_docks`](crate::version::supports_docks), and
+/// [`add_legacy_dock`] for older versions.
+pub fn add_dock(id: &str, title: &str, widget: impl QtWidget) -> bool {
+    let add_dock_by_id = match obs_frontend_add_dock_by_id.get() {
+        Some(add_dock_by_id) => add_dock_by_id,
+        None => return false,
+    };
+    let (id, title) = match (CString::new(id), CString::new(title)) {
+        (Ok(id), Ok(title)) => (id, title),
+        _ => return false,
+    };
+    unsafe { add_dock_by_id(id.as_ptr(), title.as_ptr(), widget.as_qwidget()) }
+}
+
+/// Removes and destroys the dock added with `id`. Does nothing before OBS
+/// 30.0.
+pub fn remove_dock(id: &str) {
+    if let (Some(remove_dock), Ok(id)) = (obs_frontend_remove_dock.get(), CString::new(id)) {
+        unsafe { remove_dock(id.as_ptr()) }
+    }
+}
+
+/// Adds `dock`, which must be a `QDockWidget` with an object name, to the
+/// frontend's Docks menu. Returns `false` if it could not be added.
+///
+/// Must be called on the Qt main thread. Deprecated since OBS 30.0 in favour
+/// of [`add_dock`].
+pub fn add_legacy_dock(dock: impl QtWidget) -> bool {
+    unsafe { !obs_frontend_add_dock(dock.as_qwidget()).is_null() }
+}
+
+/// Starts the virtual camera, as if the user clicked "Start Virtual Camera".
+///
+/// Requires OBS 26.0 or later and does nothing on older versions, see
+/// [`version::supports_virtualcam`](crate::version::supports_virtualcam).
+pub fn start_virtualcam() {
+    if let Some(start_virtualcam) = obs_frontend_start_virtualcam.get() {
+        unsafe { start_virtualcam() }
+    }
+}
+
+/// Requires OBS 26.0 or later and does nothing on older versions.
+pub fn stop_virtualcam() {
+    if let Some(stop_virtualcam) = obs_frontend_stop_virtualcam.get() {
+        unsafe { stop_virtualcam() }
+    }
+}
+
+/// Requires OBS 26.0 or later and returns `false` on older versions.
+pub fn virtualcam_active() -> bool {
+    match obs_frontend_virtualcam_active.get() {
+        Some(virtualcam_active) => unsafe { virtualcam_active() },
+        None => false,
+    }
+}
+
+pub fn recording_active() -> bool {
+    unsafe { obs_frontend_recording_active() }
+}
+
+/// Pauses or resumes the recording, as the frontend's pause hotkeys do. Does
+/// nothing if not recording, or if the recording output cannot be paused,
+/// e.g. because it shares its encoders with the stream.
+pub fn set_recording_paused(pause: bool) {
+    unsafe { obs_frontend_recording_pause(pause) }
+}
+
+pub fn recording_paused() -> bool {
+    unsafe { obs_frontend_recording_paused() }
+}
+
+/// Takes a screenshot of the program output, saved like the frontend's own
+/// screenshot hotkey.
+pub fn take_screenshot() {
+    unsafe { obs_frontend_take_screenshot() }
+}
+
+/// Takes a screenshot of `source`, saved like the frontend's own screenshot
+/// hotkey.
+pub fn take_source_screenshot(source: &SourceContext) {
+    unsafe { obs_frontend_take_source_screenshot(source.as_ptr()) }
+}
+
+/// Returns whether studio mode, with separate preview and program scenes, is
+/// enabled.
+pub fn studio_mode_active() -> bool {
+    unsafe { obs_frontend_preview_program_mode_active() }
+}
+
+pub fn set_studio_mode(enable: bool) {
+    unsafe { obs_frontend_set_preview_program_mode(enable) }
+}
+
+/// Returns the scene shown in the preview, or `None` if studio mode is not
+/// enabled.
+pub fn current_preview_scene() -> Option<SourceRef> {
+    unsafe { SourceRef::from_raw(obs_frontend_get_current_preview_scene()) }
+}
+
+/// Sets the scene shown in the preview. Does nothing if studio mode is not
+/// enabled.
+pub fn set_current_preview_scene(scene: &SourceContext) {
+    unsafe { obs_frontend_set_current_preview_scene(scene.as_ptr()) }
+}
+
+/// Transitions the preview scene to program, as if the user clicked
+/// "Transition" in studio mode.
+pub fn trigger_studio_mode_transition() {
+    unsafe { obs_frontend_preview_program_trigger_transition() }
+}
@@ -0,0 +1,103 @@
+//! Compiling an effect is slow, and every instance of a source usually needs
+//! the same one. Effects loaded through this cache are compiled by the first
+//! instance asking for them and shared with every later one, until the last
+//! handle is dropped:
+//!
+//! ```ignore
+//! let effect = cache::from_string(obs_string!(include_str!("./blur.effect")), obs_string!("blur"))?;
+//! let mut effect = effect.lock();
+//! let param = effect.get_effect_param_by_name::<GraphicsEffectFloatParam>(obs_string!("radius"));
+//! ```
+
+use super::GraphicsEffect;
+use crate::string::ObsString;
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard, Weak};
+
+struct CachedEffect {
+    effect: Mutex<GraphicsEffect>,
+}
+
+// Effects are only used from the graphics thread, the mutex makes sure of
+// exclusive access otherwise.
+unsafe impl Send for CachedEffect {}
+unsafe impl Sync for CachedEffect {}
+
+#[derive(Hash, PartialEq, Eq)]
+enum EffectKey {
+    File(String),
+    Source(String),
+}
+
+static CACHE: Mutex<Option<HashMap<EffectKey, Weak<CachedEffect>>>> = Mutex::new(None);
+
+/// A handle to an effect in the cache, which can be cloned freely.
+#[derive(Clone)]
+pub struct SharedEffect {
+    inner: Arc<CachedEffect>,
+}
+
+impl SharedEffect {
+    /// Locks the effect for use, e.g. in `video_render`.
+    pub fn lock(&self) -> MutexGuard<'_, GraphicsEffect> {
+        self.inner.effect.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+fn cached(key: &EffectKey) -> Option<SharedEffect> {
+    let cache = CACHE.lock().unwrap_or_else(|e| e.into_inner());
+    let inner = cache.as_ref()?.get(key)?.upgrade()?;
+    Some(SharedEffect { inner })
+}
+
+// The cache is not locked while compiling, since that enters graphics and
+// another thread in graphics may be waiting for the cache. If two threads
+// compile the same effect, the one inserting last uses the other's effect.
+fn get_or_load(
+    key: EffectKey,
+    load: impl FnOnce() -> Option<GraphicsEffect>,
+) -> Option<SharedEffect> {
+    if let Some(effect) = cached(&key) {
+        return Some(effect);
+    }
+
+    let loaded = Arc::new(CachedEffect {
+        effect: Mutex::new(load()?),
+    });
+
+    // An effect compiled in vain is dropped after unlocking, as destroying it
+    // enters graphics too.
+    let inner = {
+        let mut cache = CACHE.lock().unwrap_or_else(|e| e.into_inner());
+        let cache = cache.get_or_insert_with(HashMap::new);
+        cache.retain(|_, effect| effect.strong_count() > 0);
+
+        let entry = cache.entry(key).or_default();
+        match entry.upgrade() {
+            Some(inner) => inner,
+            None => {
+                *entry = Arc::downgrade(&loaded);
+                loaded.clone()
+            }
+        }
+    };
+    drop(loaded);
+    Some(SharedEffect { inner })
+}
+
+/// Returns the effect compiled from the file at `path`, compiling it if no
+/// handle to it exists yet.
+pub fn from_file(path: ObsString) -> Option<SharedEffect> {
+    get_or_load(EffectKey::File(path.as_str().to_owned()), || {
+        GraphicsEffect::from_file(path)
+    })
+}
+
+/// Returns the effect compiled from `source`, compiling it if no handle to an
+/// effect with the same source exists yet.
+pub fn from_string(source: ObsString, name: ObsString) -> Option<SharedEffect> {
+    get_or_load(EffectKey::Source(source.as_str().to_owned()), || {
+        GraphicsEffect::from_effect_string(source, name)
+    })
+}
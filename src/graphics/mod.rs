@@ -1,3 +1,8 @@
+/// Sharing compiled effects between sources
+pub mod cache;
+
+use crate::symbol::{Symbol, LIBOBS};
+use crate::unwind::handle_unwind;
 use crate::{Error, Result};
 use core::convert::TryFrom;
 use core::ptr::null_mut;
@@ -11,10 +16,13 @@ use obs_sys::{
     gs_color_format_GS_R8, gs_color_format_GS_R8G8, gs_color_format_GS_RG16F,
     gs_color_format_GS_RG32F, gs_color_format_GS_RGBA, gs_color_format_GS_RGBA16,
     gs_color_format_GS_RGBA16F, gs_color_format_GS_RGBA32F, gs_color_format_GS_UNKNOWN,
-    gs_effect_create, gs_effect_destroy, gs_effect_get_param_by_name, gs_effect_get_param_info,
-    gs_effect_param_info, gs_effect_set_next_sampler, gs_effect_set_vec2, gs_effect_t, gs_eparam_t,
-    gs_sample_filter, gs_sample_filter_GS_FILTER_ANISOTROPIC, gs_sample_filter_GS_FILTER_LINEAR,
-    gs_sample_filter_GS_FILTER_MIN_LINEAR_MAG_MIP_POINT,
+    gs_effect_create, gs_effect_create_from_file, gs_effect_destroy, gs_effect_get_param_by_name,
+    gs_effect_get_param_info, gs_effect_loop, gs_effect_param_info, gs_effect_set_bool,
+    gs_effect_set_color, gs_effect_set_float, gs_effect_set_int, gs_effect_set_matrix4,
+    gs_effect_set_next_sampler, gs_effect_set_texture, gs_effect_set_vec2, gs_effect_set_vec3,
+    gs_effect_set_vec4, gs_effect_t, gs_eparam_t, gs_get_device_name, gs_get_device_obj,
+    gs_get_device_type, gs_sample_filter, gs_sample_filter_GS_FILTER_ANISOTROPIC,
+    gs_sample_filter_GS_FILTER_LINEAR, gs_sample_filter_GS_FILTER_MIN_LINEAR_MAG_MIP_POINT,
     gs_sample_filter_GS_FILTER_MIN_LINEAR_MAG_POINT_MIP_LINEAR,
     gs_sample_filter_GS_FILTER_MIN_MAG_LINEAR_MIP_POINT,
     gs_sample_filter_GS_FILTER_MIN_MAG_POINT_MIP_LINEAR,
@@ -29,20 +37,54 @@ use obs_sys::{
     gs_shader_param_type_GS_SHADER_PARAM_UNKNOWN, gs_shader_param_type_GS_SHADER_PARAM_VEC2,
     gs_shader_param_type_GS_SHADER_PARAM_VEC3, gs_shader_param_type_GS_SHADER_PARAM_VEC4,
     gs_texture_create, gs_texture_destroy, gs_texture_get_height, gs_texture_get_width,
-    gs_texture_map, gs_texture_set_image, gs_texture_t, gs_texture_unmap, obs_allow_direct_render,
+    gs_texture_map, gs_texture_set_image, gs_texture_t, gs_texture_unmap, matrix4,
+    obs_add_main_render_callback, obs_allow_direct_render,
     obs_allow_direct_render_OBS_ALLOW_DIRECT_RENDERING,
     obs_allow_direct_render_OBS_NO_DIRECT_RENDERING, obs_enter_graphics, obs_leave_graphics,
-    obs_source_draw, vec2, vec3, vec4, GS_DYNAMIC,
+    obs_remove_main_render_callback, obs_source_draw, vec2, vec3, vec4, GS_DEVICE_DIRECT3D_11,
+    GS_DEVICE_OPENGL, GS_DYNAMIC,
 };
 use paste::item;
+use std::mem::ManuallyDrop;
 use std::{
-    ffi::{CStr, CString},
+    ffi::{c_void, CStr, CString},
     ptr,
 };
 use std::{os::raw::c_int, slice};
 
+use super::source::context::VideoRenderContext;
 use super::string::ObsString;
 
+// The generated bindings come from Linux headers, which leave out the
+// platform specific texture sharing functions.
+// Available on macOS since OBS 27.0.
+#[cfg(any(windows, target_os = "macos"))]
+#[allow(non_upper_case_globals)]
+static gs_texture_open_shared: Symbol<unsafe extern "C" fn(u32) -> *mut gs_texture_t> =
+    Symbol::new(&LIBOBS, b"gs_texture_open_shared\0");
+
+#[cfg(windows)]
+extern "C" {
+    fn gs_texture_get_shared_handle(tex: *mut gs_texture_t) -> u32;
+}
+
+// Added in OBS 27.1, after the version the generated bindings cover.
+#[cfg(target_os = "linux")]
+#[allow(non_upper_case_globals)]
+pub(crate) static gs_texture_create_from_dmabuf: Symbol<
+    unsafe extern "C" fn(
+        u32,
+        u32,
+        u32,
+        gs_color_format,
+        u32,
+        *const c_int,
+        *const u32,
+        *const u32,
+        *const u64,
+    ) -> *mut gs_texture_t,
+> = Symbol::new(&LIBOBS, b"gs_texture_create_from_dmabuf\0");
+
 /// Guard to guarantee that we exit graphics context properly.
 /// This does not prevent one from calling APIs that are not supposed to be called outside of the context.
 struct GraphicsGuard;
@@ -69,6 +111,53 @@ impl Drop for GraphicsGuard {
     }
 }
 
+/// The API the graphics module renders with.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum GraphicsDeviceType {
+    OpenGL,
+    Direct3D11,
+}
+
+/// Returns the API the graphics module renders with, or `None` if video has
+/// not been started.
+pub fn device_type() -> Option<GraphicsDeviceType> {
+    match GraphicsGuard::with_enter(|| unsafe { gs_get_device_type() }) as u32 {
+        GS_DEVICE_OPENGL => Some(GraphicsDeviceType::OpenGL),
+        GS_DEVICE_DIRECT3D_11 => Some(GraphicsDeviceType::Direct3D11),
+        _ => None,
+    }
+}
+
+/// Returns the name of the graphics device, such as `"OpenGL"` or
+/// `"Direct3D 11"`.
+pub fn device_name() -> Option<String> {
+    GraphicsGuard::with_enter(|| unsafe {
+        let name = gs_get_device_name();
+        if name.is_null() {
+            None
+        } else {
+            Some(CStr::from_ptr(name).to_string_lossy().into_owned())
+        }
+    })
+}
+
+/// Runs `func` inside the graphics context with the native device of the
+/// graphics module, for sharing it with other GPU libraries.
+///
+/// With Direct3D 11 the pointer is the `ID3D11Device`. With OpenGL it is null,
+/// but the OBS context is current on the thread while `func` runs, so it can
+/// be queried with e.g. `eglGetCurrentContext` or `wglGetCurrentContext`.
+///
+/// # Safety
+/// The device is shared with OBS, so any state changed through it, such as
+/// bound resources or render targets, must be restored before returning.
+/// The pointer must not be used after `func` returns.
+///
+/// See [OBS documentation](https://obsproject.com/docs/reference-libobs-graphics-graphics.html#c.gs_get_device_obj)
+pub unsafe fn with_device<R>(func: impl FnOnce(*mut c_void) -> R) -> R {
+    GraphicsGuard::with_enter(|| func(gs_get_device_obj()))
+}
+
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum ShaderParamType {
     Unknown,
@@ -142,6 +231,19 @@ impl GraphicsEffect {
         }
     }
 
+    /// Compiles the effect file at `path`, usually one in the data directory
+    /// of the module.
+    pub fn from_file(path: ObsString) -> Option<Self> {
+        let raw = GraphicsGuard::with_enter(|| unsafe {
+            gs_effect_create_from_file(path.as_ptr(), std::ptr::null_mut())
+        });
+        if raw.is_null() {
+            None
+        } else {
+            Some(Self { raw })
+        }
+    }
+
     pub fn get_effect_param_by_name<T: TryFrom<GraphicsEffectParam>>(
         &mut self,
         name: ObsString,
@@ -156,6 +258,28 @@ impl GraphicsEffect {
         }
     }
 
+    /// Calls `draw` once for every pass of `technique`, beginning and ending
+    /// the technique and its passes around it, like
+    /// `while (gs_effect_loop(effect, "Draw"))` in C. Does nothing if the
+    /// effect has no such technique.
+    ///
+    /// ```ignore
+    /// effect.loop_technique(render, obs_string!("Draw"), |_render| unsafe {
+    ///     gs_draw_sprite(texture.as_ptr(), 0, width, height);
+    /// });
+    /// ```
+    pub fn loop_technique<F: FnMut(&mut VideoRenderContext)>(
+        &mut self,
+        render: &mut VideoRenderContext,
+        technique: ObsString,
+        mut draw: F,
+    ) {
+        // Every pass must be looped through for the technique to be ended.
+        while unsafe { gs_effect_loop(self.raw, technique.as_ptr()) } {
+            draw(render);
+        }
+    }
+
     /// # Safety
     /// Returns a mutable pointer to an effect which if modified could cause UB.
     pub unsafe fn as_ptr(&self) -> *mut gs_effect_t {
@@ -230,10 +354,40 @@ macro_rules! impl_graphics_effects {
 }
 
 impl_graphics_effects! {
+    Bool
+    Float
+    Int
     Vec2
+    Vec3
+    Vec4
+    Mat4
     Texture
 }
 
+impl GraphicsEffectBoolParam {
+    pub fn set_bool(&mut self, _context: &GraphicsEffectContext, value: bool) {
+        unsafe {
+            gs_effect_set_bool(self.effect.raw, value);
+        }
+    }
+}
+
+impl GraphicsEffectFloatParam {
+    pub fn set_float(&mut self, _context: &GraphicsEffectContext, value: f32) {
+        unsafe {
+            gs_effect_set_float(self.effect.raw, value);
+        }
+    }
+}
+
+impl GraphicsEffectIntParam {
+    pub fn set_int(&mut self, _context: &GraphicsEffectContext, value: i32) {
+        unsafe {
+            gs_effect_set_int(self.effect.raw, value as c_int);
+        }
+    }
+}
+
 impl GraphicsEffectVec2Param {
     pub fn set_vec2(&mut self, _context: &GraphicsEffectContext, value: &Vec2) {
         unsafe {
@@ -242,7 +396,44 @@ impl GraphicsEffectVec2Param {
     }
 }
 
+impl GraphicsEffectVec3Param {
+    pub fn set_vec3(&mut self, _context: &GraphicsEffectContext, value: &Vec3) {
+        unsafe {
+            gs_effect_set_vec3(self.effect.raw, &value.raw);
+        }
+    }
+}
+
+impl GraphicsEffectVec4Param {
+    pub fn set_vec4(&mut self, _context: &GraphicsEffectContext, value: &Vec4) {
+        unsafe {
+            gs_effect_set_vec4(self.effect.raw, &value.raw);
+        }
+    }
+
+    /// Sets the parameter to a color in `0xAARRGGBB` format.
+    pub fn set_color(&mut self, _context: &GraphicsEffectContext, argb: u32) {
+        unsafe {
+            gs_effect_set_color(self.effect.raw, argb);
+        }
+    }
+}
+
+impl GraphicsEffectMat4Param {
+    pub fn set_matrix4(&mut self, _context: &GraphicsEffectContext, value: &Matrix4) {
+        unsafe {
+            gs_effect_set_matrix4(self.effect.raw, value.as_ptr());
+        }
+    }
+}
+
 impl GraphicsEffectTextureParam {
+    pub fn set_texture(&mut self, _context: &GraphicsEffectContext, value: &GraphicsTexture) {
+        unsafe {
+            gs_effect_set_texture(self.effect.raw, value.raw);
+        }
+    }
+
     pub fn set_next_sampler(
         &mut self,
         _context: &GraphicsEffectContext,
@@ -608,6 +799,18 @@ macro_rules! vector_impls {
             }
         }
 
+        impl From<$name> for $rust_name {
+            fn from(raw: $name) -> Self {
+                Self { raw }
+            }
+        }
+
+        impl From<$rust_name> for $name {
+            fn from(value: $rust_name) -> Self {
+                value.raw
+            }
+        }
+
         impl Default for $rust_name {
             fn default() -> Self {
                 $(
@@ -626,6 +829,53 @@ vector_impls! {
     Vec4, vec4 => x y z w,
 }
 
+/// A 4x4 matrix in the layout of the OBS `matrix4`, where each row is a
+/// [`Vec4`] and the last row holds the translation.
+#[derive(Clone)]
+#[repr(C)]
+pub struct Matrix4 {
+    rows: [vec4; 4],
+}
+
+impl Matrix4 {
+    pub fn identity() -> Self {
+        Self::from_rows([
+            [1., 0., 0., 0.],
+            [0., 1., 0., 0.],
+            [0., 0., 1., 0.],
+            [0., 0., 0., 1.],
+        ])
+    }
+
+    pub fn from_rows(rows: [[f32; 4]; 4]) -> Self {
+        let mut matrix = Self {
+            rows: [vec4::default(); 4],
+        };
+        for (row, values) in matrix.rows.iter_mut().zip(rows.iter()) {
+            row.__bindgen_anon_1.ptr = *values;
+        }
+        matrix
+    }
+
+    pub fn rows(&self) -> [[f32; 4]; 4] {
+        let mut rows = [[0.; 4]; 4];
+        for (values, row) in rows.iter_mut().zip(self.rows.iter()) {
+            *values = unsafe { row.__bindgen_anon_1.ptr };
+        }
+        rows
+    }
+
+    fn as_ptr(&self) -> *const matrix4 {
+        self as *const Self as *const matrix4
+    }
+}
+
+impl Default for Matrix4 {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
 /// Wrapper around [`gs_texture_t`](https://obsproject.com/docs/reference-libobs-graphics-graphics.html#c.gs_texture_t)
 pub struct GraphicsTexture {
     raw: *mut gs_texture_t,
@@ -655,7 +905,15 @@ impl GraphicsTexture {
         });
     }
 
-    pub fn draw(&self, x: c_int, y: c_int, cx: u32, cy: u32, flip: bool) {
+    pub fn draw(
+        &self,
+        _render: &mut VideoRenderContext,
+        x: c_int,
+        y: c_int,
+        cx: u32,
+        cy: u32,
+        flip: bool,
+    ) {
         unsafe {
             obs_source_draw(self.raw, x, y, cx, cy, flip);
         }
@@ -669,6 +927,83 @@ impl GraphicsTexture {
     pub unsafe fn as_ptr(&self) -> *mut gs_texture_t {
         self.raw
     }
+
+    /// Opens a texture shared by another process or device, such as a DXGI
+    /// shared handle on Windows or an IOSurface ID on macOS, without copying
+    /// it through system memory. Returns `None` if the handle could not be
+    /// opened.
+    ///
+    /// Returns `None` before OBS 27.0 on macOS.
+    ///
+    /// See [OBS documentation](https://obsproject.com/docs/reference-libobs-graphics-graphics.html#c.gs_texture_open_shared)
+    #[cfg(any(windows, target_os = "macos"))]
+    pub fn open_shared(handle: u32) -> Option<Self> {
+        let open_shared = gs_texture_open_shared.get()?;
+        let raw = GraphicsGuard::with_enter(|| unsafe { open_shared(handle) });
+        if raw.is_null() {
+            None
+        } else {
+            Some(Self { raw })
+        }
+    }
+
+    /// Returns the DXGI shared handle of a texture created as shared, for
+    /// other processes to open it.
+    #[cfg(windows)]
+    pub fn shared_handle(&self) -> Option<u32> {
+        // GS_INVALID_HANDLE
+        match GraphicsGuard::with_enter(|| unsafe { gs_texture_get_shared_handle(self.raw) }) {
+            u32::MAX => None,
+            handle => Some(handle),
+        }
+    }
+
+    /// Imports a DMA-BUF, e.g. from a PipeWire stream or a Wayland compositor,
+    /// as a texture with the EGL renderer. `drm_format` is a `DRM_FORMAT_*`
+    /// fourcc. Returns `None` if the buffer could not be imported.
+    ///
+    /// The file descriptors stay owned by the caller.
+    ///
+    /// Returns `None` before OBS 27.1, see
+    /// [`supports_dmabuf`](crate::version::supports_dmabuf).
+    #[cfg(target_os = "linux")]
+    pub fn from_dmabuf(
+        width: u32,
+        height: u32,
+        drm_format: u32,
+        color_format: GraphicsColorFormat,
+        planes: &[DmaBufPlane],
+    ) -> Option<Self> {
+        let create_from_dmabuf = gs_texture_create_from_dmabuf.get()?;
+        let fds: Vec<c_int> = planes.iter().map(|p| p.fd).collect();
+        let strides: Vec<u32> = planes.iter().map(|p| p.stride).collect();
+        let offsets: Vec<u32> = planes.iter().map(|p| p.offset).collect();
+        let modifiers: Vec<u64> = planes.iter().map(|p| p.modifier).collect();
+        let raw = GraphicsGuard::with_enter(|| unsafe {
+            create_from_dmabuf(
+                width,
+                height,
+                drm_format,
+                color_format.as_raw(),
+                planes.len() as u32,
+                fds.as_ptr(),
+                strides.as_ptr(),
+                offsets.as_ptr(),
+                modifiers.as_ptr(),
+            )
+        });
+        if raw.is_null() {
+            None
+        } else {
+            Some(Self { raw })
+        }
+    }
+
+    /// Wraps a texture owned by OBS. The result must not be dropped, since
+    /// that would destroy the texture.
+    pub(crate) unsafe fn from_raw(raw: *mut gs_texture_t) -> ManuallyDrop<Self> {
+        ManuallyDrop::new(Self { raw })
+    }
 }
 
 impl Drop for GraphicsTexture {
@@ -679,6 +1014,18 @@ impl Drop for GraphicsTexture {
     }
 }
 
+/// A plane of a DMA-BUF imported with [`GraphicsTexture::from_dmabuf`].
+#[cfg(target_os = "linux")]
+#[derive(Debug, Clone, Copy)]
+pub struct DmaBufPlane {
+    pub fd: c_int,
+    pub stride: u32,
+    pub offset: u32,
+    /// The `DRM_FORMAT_MOD_*` layout modifier, `DRM_FORMAT_MOD_INVALID` if
+    /// unknown.
+    pub modifier: u64,
+}
+
 /// Represents a mapped texture blob from [`GraphicsTexture`].
 pub struct MappedTexture<'tex> {
     tex: &'tex mut GraphicsTexture,
@@ -748,3 +1095,39 @@ impl Drop for MappedTexture<'_> {
         });
     }
 }
+
+type MainRenderFn = Box<dyn FnMut(&mut VideoRenderContext, u32, u32) + Send>;
+
+/// A closure that draws on top of the main canvas during the main render pass,
+/// registered with [`obs_add_main_render_callback`].
+///
+/// The closure receives the base canvas size and runs on the graphics thread.
+/// It is removed when this handle is dropped.
+pub struct MainRenderCallback {
+    func: *mut MainRenderFn,
+}
+
+impl MainRenderCallback {
+    pub fn new<F: FnMut(&mut VideoRenderContext, u32, u32) + Send + 'static>(func: F) -> Self {
+        let func: *mut MainRenderFn = Box::into_raw(Box::new(Box::new(func)));
+        unsafe {
+            obs_add_main_render_callback(Some(main_render_callback), func as *mut c_void);
+        }
+        Self { func }
+    }
+}
+
+impl Drop for MainRenderCallback {
+    fn drop(&mut self) {
+        unsafe {
+            obs_remove_main_render_callback(Some(main_render_callback), self.func as *mut c_void);
+            drop(Box::from_raw(self.func));
+        }
+    }
+}
+
+unsafe extern "C" fn main_render_callback(param: *mut c_void, cx: u32, cy: u32) {
+    let func = &mut *(param as *mut MainRenderFn);
+    let mut render = VideoRenderContext::new();
+    handle_unwind::<MainRenderCallback, _>("main_render_callback", || func(&mut render, cx, cy));
+}
@@ -85,11 +85,9 @@
 //!             .enable_get_name()
 //!             .build();
 //!    
-//!         // Tell OBS about the source so that it will show it.
-//!         load_context.register_source(source);
-//!    
-//!         // Nothing could have gone wrong, so return true.
-//!         true
+//!         // Tell OBS about the source so that it will show it. This fails if
+//!         // OBS rejects the source, which is logged along with the reason.
+//!         load_context.register_source(source).is_ok()
 //!     }
 //!    
 //!     fn description() -> ObsString {
@@ -115,26 +113,69 @@
 /// Raw bindings of OBS C API
 pub use obs_sys;
 
+/// Annotations written to the log on crashes
+pub mod crash;
+/// Diagnostic snapshots of OBS state
+pub mod diagnostics;
+/// Native windows rendering OBS content
+pub mod display;
+/// Encoders and the packets they produce
+pub mod encoder;
+/// Controlling the OBS frontend
+#[cfg(feature = "frontend")]
+pub mod frontend;
 /// Tools required for manipulating graphics in OBS
 pub mod graphics;
 /// Logger for logging to OBS console
 pub mod log;
+/// Buffers allocated with the OBS allocator
+pub mod memory;
 /// Tools for creating modules
 pub mod module;
+/// Tools for creating outputs
+pub mod output;
+/// Profiling plugin code with the OBS profiler
+pub mod profiler;
+/// Background async runtime
+#[cfg(feature = "tokio")]
+pub mod runtime;
+/// Scenes and the items arranged in them
+pub mod scene;
+/// Tools for creating services
+pub mod service;
 /// Tools for creating sources
 pub mod source;
 /// String macros
 pub mod string;
+/// Running work on OBS threads
+pub mod tasks;
+/// Running plugin code against libobs in tests
+#[cfg(feature = "testing")]
+pub mod testing;
+/// Clocks and frame pacing helpers
+pub mod timing;
+/// Panic handling for callbacks invoked by OBS
+pub mod unwind;
 /// `obs_data_t` handling
 pub mod data;
+/// Querying the version of the running libobs
+pub mod version;
+/// Raw video frames and formats
+pub mod video;
+/// Rendering source trees separately from the main output
+pub mod view;
 /// FFI pointer wrapper
 mod wrapper;
+/// Functions looked up at runtime
+mod symbol;
 
 mod native_enum;
 
+pub use version::version;
+
 /// Re-exports of a bunch of popular tools
 pub mod prelude {
-    pub use crate::data::{DataArray, DataObj, FromDataItem};
+    pub use crate::data::{DataArray, DataObj, FromDataItem, IntoDataItem, ObsSettings};
     pub use crate::module::*;
     pub use crate::source::context::*;
     pub use crate::string::*;
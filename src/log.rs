@@ -1,8 +1,26 @@
+//! Logging to the OBS log, either through the [`log`](https://docs.rs/log)
+//! crate with [`Logger`](crate::log::Logger), or directly with this crate's own macros:
+//!
+//! ```ignore
+//! obs_wrapper::info!("Loaded {} presets", presets.len());
+//! obs_wrapper::warn!(target: "my_plugin::capture", "Device {} went away", name);
+//! ```
+//!
+//! The macros need no logger to be installed. They are filtered by the same
+//! level as [`Logger`](crate::log::Logger), defaulting to `Info`, and their
+//! arguments are not formatted at all when filtered out.
+
+use std::fmt;
 use std::os::raw::c_char;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 
-use log::{Level, LevelFilter, Log, Metadata, Record, SetLoggerError};
+pub use log::{Level, LevelFilter};
+use log::{Log, Metadata, Record, SetLoggerError};
 use obs_sys::{blog, LOG_DEBUG, LOG_ERROR, LOG_INFO, LOG_WARNING, _bindgen_ty_1};
 
+static MAX_LEVEL: AtomicUsize = AtomicUsize::new(LevelFilter::Info as usize);
+static PROMOTE_DEBUG: AtomicBool = AtomicBool::new(false);
+
 /// A logger that plugs into OBS's logging system.
 ///
 /// Since OBS only has 4 logging levels and the lowest level is
@@ -46,6 +64,8 @@ impl Logger {
     /// This may fail if there is already a logger.
     pub fn init(self) -> Result<(), SetLoggerError> {
         log::set_max_level(self.max_level);
+        set_max_level(self.max_level);
+        PROMOTE_DEBUG.store(self.promote_debug, Ordering::Relaxed);
         log::set_boxed_logger(Box::new(self))?;
         Ok(())
     }
@@ -67,34 +87,20 @@ impl Logger {
 
 impl Log for Logger {
     fn enabled(&self, metadata: &Metadata) -> bool {
-        metadata.level() >= self.max_level
+        metadata.level() <= self.max_level
     }
 
     fn log(&self, record: &Record) {
         if !self.enabled(record.metadata()) {
             return;
         }
-        let level = record.level();
-        let native_level = to_native_level(level, self.promote_debug);
         let target = if !record.target().is_empty() {
             record.target()
         } else {
             record.module_path().unwrap_or_default()
         };
 
-        let line = if self.promote_debug && level <= Level::Debug {
-            format!("({}) [{}] {}\0", level, target, record.args())
-        } else {
-            format!("[{}] {}\0", target, record.args())
-        };
-
-        unsafe {
-            blog(
-                native_level as i32,
-                "%s\0".as_ptr() as *const c_char,
-                line.as_ptr() as *const c_char,
-            );
-        }
+        write_line(record.level(), target, *record.args(), self.promote_debug);
     }
 
     fn flush(&self) {
@@ -102,6 +108,102 @@ impl Log for Logger {
     }
 }
 
+/// Sets the maximum level of the logging macros, which [`Logger::init`] also
+/// sets to the logger's level.
+pub fn set_max_level(max_level: LevelFilter) {
+    MAX_LEVEL.store(max_level as usize, Ordering::Relaxed);
+}
+
+/// Returns whether the logging macros write messages of `level`.
+pub fn enabled(level: Level) -> bool {
+    level as usize <= MAX_LEVEL.load(Ordering::Relaxed)
+}
+
+#[doc(hidden)]
+pub fn __write(level: Level, target: &str, args: fmt::Arguments) {
+    write_line(level, target, args, PROMOTE_DEBUG.load(Ordering::Relaxed));
+}
+
+fn write_line(level: Level, target: &str, args: fmt::Arguments, promote_debug: bool) {
+    let native_level = to_native_level(level, promote_debug);
+
+    let line = if promote_debug && level >= Level::Debug {
+        format!("({}) [{}] {}\0", level, target, args)
+    } else {
+        format!("[{}] {}\0", target, args)
+    };
+
+    unsafe {
+        blog(
+            native_level as i32,
+            "%s\0".as_ptr() as *const c_char,
+            line.as_ptr() as *const c_char,
+        );
+    }
+}
+
+/// Writes a message to the OBS log at the given [`Level`], with an optional
+/// target that defaults to the calling module.
+///
+/// The message is formatted only if `level` is [`enabled`].
+#[macro_export]
+macro_rules! obs_log {
+    (target: $target:expr, $level:expr, $($arg:tt)+) => {{
+        let level = $level;
+        if $crate::log::enabled(level) {
+            $crate::log::__write(level, $target, format_args!($($arg)+));
+        }
+    }};
+    ($level:expr, $($arg:tt)+) => {
+        $crate::obs_log!(target: module_path!(), $level, $($arg)+)
+    };
+}
+
+/// Writes a message to the OBS log as `LOG_ERROR`, see [`obs_log!`].
+#[macro_export]
+macro_rules! error {
+    (target: $target:expr, $($arg:tt)+) => {
+        $crate::obs_log!(target: $target, $crate::log::Level::Error, $($arg)+)
+    };
+    ($($arg:tt)+) => {
+        $crate::obs_log!($crate::log::Level::Error, $($arg)+)
+    };
+}
+
+/// Writes a message to the OBS log as `LOG_WARNING`, see [`obs_log!`].
+#[macro_export]
+macro_rules! warn {
+    (target: $target:expr, $($arg:tt)+) => {
+        $crate::obs_log!(target: $target, $crate::log::Level::Warn, $($arg)+)
+    };
+    ($($arg:tt)+) => {
+        $crate::obs_log!($crate::log::Level::Warn, $($arg)+)
+    };
+}
+
+/// Writes a message to the OBS log as `LOG_INFO`, see [`obs_log!`].
+#[macro_export]
+macro_rules! info {
+    (target: $target:expr, $($arg:tt)+) => {
+        $crate::obs_log!(target: $target, $crate::log::Level::Info, $($arg)+)
+    };
+    ($($arg:tt)+) => {
+        $crate::obs_log!($crate::log::Level::Info, $($arg)+)
+    };
+}
+
+/// Writes a message to the OBS log as `LOG_DEBUG`, or `LOG_INFO` if the
+/// [`Logger`] promotes debug logs, see [`obs_log!`].
+#[macro_export]
+macro_rules! debug {
+    (target: $target:expr, $($arg:tt)+) => {
+        $crate::obs_log!(target: $target, $crate::log::Level::Debug, $($arg)+)
+    };
+    ($($arg:tt)+) => {
+        $crate::obs_log!($crate::log::Level::Debug, $($arg)+)
+    };
+}
+
 fn to_native_level(level: Level, promote_debug: bool) -> _bindgen_ty_1 {
     match level {
         Level::Error => LOG_ERROR,
@@ -0,0 +1,135 @@
+//! Memory allocated with OBS's own allocator, for buffers whose ownership is
+//! handed to OBS and which it frees with `bfree`, such as packet data an
+//! encoder passes on or frames it keeps.
+//!
+//! Allocations made here are also counted by OBS, so a buffer that is never
+//! freed shows up as a leak in the log when OBS exits.
+
+use obs_sys::{bfree, bmalloc, bmemdup, bnum_allocs, brealloc};
+
+use std::ops::{Deref, DerefMut};
+use std::os::raw::c_void;
+use std::ptr::NonNull;
+use std::slice;
+
+/// A byte buffer allocated with `bmalloc`, freed with `bfree` when dropped
+/// unless its ownership is passed to OBS with [`into_raw`](Self::into_raw).
+///
+/// See [OBS documentation](https://obsproject.com/docs/reference-libobs-util-bmem.html)
+pub struct ObsBuffer {
+    ptr: NonNull<u8>,
+    len: usize,
+}
+
+// The buffer is plain memory exclusively owned by this value.
+unsafe impl Send for ObsBuffer {}
+unsafe impl Sync for ObsBuffer {}
+
+impl ObsBuffer {
+    /// Allocates a zeroed buffer of `len` bytes.
+    pub fn zeroed(len: usize) -> Self {
+        let buffer = Self::alloc(len);
+        unsafe { std::ptr::write_bytes(buffer.ptr.as_ptr(), 0, len) };
+        buffer
+    }
+
+    /// Allocates a buffer holding a copy of `data`.
+    pub fn from_slice(data: &[u8]) -> Self {
+        if data.is_empty() {
+            return Self::alloc(0);
+        }
+        let ptr = unsafe { bmemdup(data.as_ptr() as *const c_void, data.len() as _) };
+        Self {
+            ptr: Self::check(ptr, data.len()),
+            len: data.len(),
+        }
+    }
+
+    /// Takes ownership of a buffer of `len` bytes allocated by OBS.
+    ///
+    /// # Safety
+    /// `ptr` must be non-null, allocated with `bmalloc` or a function using
+    /// it, at least `len` bytes long and not owned by anything else.
+    pub unsafe fn from_raw(ptr: *mut u8, len: usize) -> Self {
+        Self {
+            ptr: NonNull::new_unchecked(ptr),
+            len,
+        }
+    }
+
+    /// Gives up ownership of the buffer, to be freed by OBS or by
+    /// [`from_raw`](Self::from_raw) with the same length.
+    pub fn into_raw(self) -> *mut u8 {
+        let ptr = self.ptr.as_ptr();
+        std::mem::forget(self);
+        ptr
+    }
+
+    /// Resizes the buffer, keeping its contents up to the smaller of the two
+    /// sizes. New bytes are zeroed.
+    pub fn resize(&mut self, len: usize) {
+        let ptr = unsafe { brealloc(self.ptr.as_ptr() as *mut c_void, len.max(1) as _) };
+        self.ptr = Self::check(ptr, len);
+        if len > self.len {
+            unsafe { std::ptr::write_bytes(self.ptr.as_ptr().add(self.len), 0, len - self.len) };
+        }
+        self.len = len;
+    }
+
+    pub fn as_ptr(&self) -> *mut u8 {
+        self.ptr.as_ptr()
+    }
+
+    fn alloc(len: usize) -> Self {
+        // `bmalloc` warns about zero-sized allocations and allocates one byte.
+        let ptr = unsafe { bmalloc(len.max(1) as _) };
+        Self {
+            ptr: Self::check(ptr, len),
+            len,
+        }
+    }
+
+    fn check(ptr: *mut c_void, len: usize) -> NonNull<u8> {
+        // OBS crashes itself when out of memory, this is only a safeguard.
+        NonNull::new(ptr as *mut u8)
+            .unwrap_or_else(|| panic!("bmalloc failed to allocate {} bytes", len))
+    }
+}
+
+impl Deref for ObsBuffer {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        unsafe { slice::from_raw_parts(self.ptr.as_ptr(), self.len) }
+    }
+}
+
+impl DerefMut for ObsBuffer {
+    fn deref_mut(&mut self) -> &mut [u8] {
+        unsafe { slice::from_raw_parts_mut(self.ptr.as_ptr(), self.len) }
+    }
+}
+
+impl Clone for ObsBuffer {
+    fn clone(&self) -> Self {
+        Self::from_slice(self)
+    }
+}
+
+impl From<&[u8]> for ObsBuffer {
+    fn from(data: &[u8]) -> Self {
+        Self::from_slice(data)
+    }
+}
+
+impl Drop for ObsBuffer {
+    fn drop(&mut self) {
+        unsafe { bfree(self.ptr.as_ptr() as *mut c_void) }
+    }
+}
+
+/// Returns the number of allocations made by OBS's allocator that have not
+/// been freed yet, which OBS reports as leaks when it exits.
+pub fn allocation_count() -> i64 {
+    unsafe { bnum_allocs() as i64 }
+}
@@ -1,11 +1,40 @@
+use crate::encoder::{traits::Encodable, EncoderInfo, EncoderInfoBuilder};
+use crate::output::{traits::Outputable, OutputInfo, OutputInfoBuilder};
+use crate::service::{traits::Serviceable, RawServiceInfo, ServiceInfo, ServiceInfoBuilder};
 use crate::source::{traits::Sourceable, SourceInfo, SourceInfoBuilder};
 use crate::string::ObsString;
-use obs_sys::{obs_module_t, obs_register_source_s, obs_source_info, size_t};
+use crate::unwind::handle_unwind;
+use obs_sys::{
+    lookup_t, obs_encoder_info, obs_enum_encoder_types, obs_enum_output_types,
+    obs_enum_service_types, obs_enum_source_types, obs_module_load_locale, obs_module_t,
+    obs_output_info, obs_register_encoder_s, obs_register_output_s, obs_register_service_s,
+    obs_register_source_s, obs_source_info, size_t, text_lookup_destroy, text_lookup_getstr,
+};
+use std::ffi::{CStr, CString};
+use std::fmt;
 use std::marker::PhantomData;
+use std::os::raw::{c_char, c_void};
+use std::sync::{Arc, Mutex, RwLock};
+
+type UnloadHook = Box<dyn FnOnce() + Send>;
+
+static UNLOAD_HOOKS: Mutex<Vec<UnloadHook>> = Mutex::new(Vec::new());
+
+struct Lookup(*mut lookup_t);
+
+// Lookups are not changed after being loaded, and reading them is safe from
+// any thread.
+unsafe impl Send for Lookup {}
+unsafe impl Sync for Lookup {}
+
+static LOCALE: RwLock<Option<Lookup>> = RwLock::new(None);
 
 pub struct LoadContext {
     __marker: PhantomData<()>,
     sources: Vec<*mut obs_source_info>,
+    outputs: Vec<*mut obs_output_info>,
+    encoders: Vec<*mut obs_encoder_info>,
+    services: Vec<*mut RawServiceInfo>,
 }
 
 impl LoadContext {
@@ -16,6 +45,9 @@ impl LoadContext {
         LoadContext {
             __marker: PhantomData,
             sources: vec![],
+            outputs: vec![],
+            encoders: vec![],
+            services: vec![],
         }
     }
 
@@ -23,13 +55,208 @@ impl LoadContext {
         SourceInfoBuilder::new()
     }
 
-    pub fn register_source(&mut self, source: SourceInfo) {
-        let pointer = unsafe {
+    /// Registers a source type with OBS, failing if its id is taken or OBS
+    /// rejects it. Failures are also written to the OBS log.
+    pub fn register_source(&mut self, source: SourceInfo) -> Result<(), RegistrationError> {
+        unsafe {
             let pointer = source.into_raw();
-            obs_register_source_s(pointer, std::mem::size_of::<obs_source_info>() as size_t);
-            pointer
-        };
-        self.sources.push(pointer);
+            let info = &*pointer;
+            let result = register(
+                obs_enum_source_types,
+                info.id,
+                info.version,
+                (info.type_data, info.free_type_data),
+                || obs_register_source_s(pointer, std::mem::size_of::<obs_source_info>() as size_t),
+            );
+            match result {
+                Ok(()) => self.sources.push(pointer),
+                Err(ref err) => {
+                    crate::error!("Failed to register source: {}", err);
+                    drop(Box::from_raw(pointer));
+                }
+            }
+            result
+        }
+    }
+
+    pub fn create_output_builder<T: Outputable, D>(&self) -> OutputInfoBuilder<T, D> {
+        OutputInfoBuilder::new()
+    }
+
+    /// Registers an output type with OBS, failing if its id is taken or OBS
+    /// rejects it. Failures are also written to the OBS log.
+    pub fn register_output(&mut self, output: OutputInfo) -> Result<(), RegistrationError> {
+        unsafe {
+            let pointer = output.into_raw();
+            let info = &*pointer;
+            let result = register(
+                obs_enum_output_types,
+                info.id,
+                0,
+                (info.type_data, info.free_type_data),
+                || obs_register_output_s(pointer, std::mem::size_of::<obs_output_info>() as size_t),
+            );
+            match result {
+                Ok(()) => self.outputs.push(pointer),
+                Err(ref err) => {
+                    crate::error!("Failed to register output: {}", err);
+                    drop(Box::from_raw(pointer));
+                }
+            }
+            result
+        }
+    }
+
+    pub fn create_encoder_builder<T: Encodable, D>(&self) -> EncoderInfoBuilder<T, D> {
+        EncoderInfoBuilder::new()
+    }
+
+    /// Registers an encoder type with OBS, failing if its id is taken or OBS
+    /// rejects it. Failures are also written to the OBS log.
+    pub fn register_encoder(&mut self, encoder: EncoderInfo) -> Result<(), RegistrationError> {
+        unsafe {
+            let pointer = encoder.into_raw();
+            let info = &*pointer;
+            let result = register(
+                obs_enum_encoder_types,
+                info.id,
+                0,
+                (info.type_data, info.free_type_data),
+                || {
+                    obs_register_encoder_s(
+                        pointer,
+                        std::mem::size_of::<obs_encoder_info>() as size_t,
+                    )
+                },
+            );
+            match result {
+                Ok(()) => self.encoders.push(pointer),
+                Err(ref err) => {
+                    crate::error!("Failed to register encoder: {}", err);
+                    drop(Box::from_raw(pointer));
+                }
+            }
+            result
+        }
+    }
+
+    pub fn create_service_builder<T: Serviceable, D>(&self) -> ServiceInfoBuilder<T, D> {
+        ServiceInfoBuilder::new()
+    }
+
+    /// Registers a service type with OBS, failing if its id is taken or OBS
+    /// rejects it. Failures are also written to the OBS log.
+    pub fn register_service(&mut self, service: ServiceInfo) -> Result<(), RegistrationError> {
+        unsafe {
+            let pointer = service.into_raw();
+            let info = &(*pointer).base;
+            let result = register(
+                obs_enum_service_types,
+                info.id,
+                0,
+                (info.type_data, info.free_type_data),
+                || obs_register_service_s(info, RawServiceInfo::size() as size_t),
+            );
+            match result {
+                Ok(()) => self.services.push(pointer),
+                Err(ref err) => {
+                    crate::error!("Failed to register service: {}", err);
+                    drop(Box::from_raw(pointer));
+                }
+            }
+            result
+        }
+    }
+}
+
+/// Why a source, output, encoder or service type could not be registered.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RegistrationError {
+    /// A type with the same id is already registered, by this or another
+    /// module.
+    DuplicateId(String),
+    /// OBS refused the type, usually because a callback it requires for the
+    /// type and flags is missing. OBS logs the exact reason.
+    Rejected(String),
+}
+
+impl fmt::Display for RegistrationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RegistrationError::DuplicateId(id) => {
+                write!(f, "a type with the id {} is already registered", id)
+            }
+            RegistrationError::Rejected(id) => {
+                write!(f, "OBS rejected {}, see the log above for why", id)
+            }
+        }
+    }
+}
+
+impl std::error::Error for RegistrationError {}
+
+type EnumTypesFn = unsafe extern "C" fn(size_t, *mut *const c_char) -> bool;
+
+unsafe fn type_registered(enum_types: EnumTypesFn, id: &CStr) -> bool {
+    let mut index = 0;
+    let mut current = std::ptr::null();
+    while enum_types(index, &mut current) {
+        if !current.is_null() && CStr::from_ptr(current) == id {
+            return true;
+        }
+        index += 1;
+    }
+    false
+}
+
+type FreeTypeDataFn = Option<unsafe extern "C" fn(*mut c_void)>;
+
+/// OBS only logs registration failures, so they are detected by checking
+/// whether the type exists before and after registering it, by the
+/// `<id>_v<version>` id OBS lists versioned types with.
+unsafe fn register(
+    enum_types: EnumTypesFn,
+    id: *const c_char,
+    version: u32,
+    (type_data, free_type_data): (*mut c_void, FreeTypeDataFn),
+    register: impl FnOnce(),
+) -> Result<(), RegistrationError> {
+    // OBS frees the type data of a type it rejects, but not of one that never
+    // reaches it.
+    let free = || {
+        if let Some(free_type_data) = free_type_data {
+            if !type_data.is_null() {
+                free_type_data(type_data);
+            }
+        }
+    };
+
+    if id.is_null() {
+        free();
+        return Err(RegistrationError::Rejected(String::new()));
+    }
+    let id = CStr::from_ptr(id);
+    let registered_id = if version == 0 {
+        id.to_owned()
+    } else {
+        let mut versioned = id.to_bytes().to_vec();
+        versioned.extend_from_slice(format!("_v{}", version).as_bytes());
+        CString::new(versioned).unwrap()
+    };
+
+    if type_registered(enum_types, &registered_id) {
+        free();
+        return Err(RegistrationError::DuplicateId(
+            registered_id.to_string_lossy().into_owned(),
+        ));
+    }
+    register();
+    if type_registered(enum_types, &registered_id) {
+        Ok(())
+    } else {
+        Err(RegistrationError::Rejected(
+            registered_id.to_string_lossy().into_owned(),
+        ))
     }
 }
 
@@ -39,6 +266,15 @@ impl Drop for LoadContext {
             for pointer in self.sources.drain(..) {
                 drop(Box::from_raw(pointer))
             }
+            for pointer in self.outputs.drain(..) {
+                drop(Box::from_raw(pointer))
+            }
+            for pointer in self.encoders.drain(..) {
+                drop(Box::from_raw(pointer))
+            }
+            for pointer in self.services.drain(..) {
+                drop(Box::from_raw(pointer))
+            }
         }
     }
 }
@@ -49,6 +285,19 @@ pub trait Module {
     fn load(&mut self, _load_context: &mut LoadContext) -> bool {
         true
     }
+    /// Called when OBS unloads the module, after every source has been
+    /// destroyed.
+    ///
+    /// OBS may unmap the library right after, so nothing of the module may
+    /// run afterwards: threads it started must be joined and callbacks it
+    /// registered with OBS removed. Unloading happens in this order:
+    ///
+    /// 1. `unload` is called.
+    /// 2. The module value is dropped.
+    /// 3. Hooks registered with [`on_unload`] run, latest first.
+    /// 4. State of the wrapper itself is torn down, such as the
+    ///    [`runtime`](crate::runtime) and the hook installed with
+    ///    [`set_panic_hook`](crate::unwind::set_panic_hook).
     fn unload(&mut self) {}
     fn post_load(&mut self) {}
     fn description() -> ObsString;
@@ -56,6 +305,44 @@ pub trait Module {
     fn author() -> ObsString;
 }
 
+/// Exports the module `$t` to OBS, like [`obs_register_module`], and loads
+/// the translations of the module for [`text`] when given a default locale:
+///
+/// ```ignore
+/// obs_module!(ScrollFocusFilter, default_locale = "en-US");
+///
+/// // data/locale/en-US.ini:
+/// // FocusFilter="Scroll Focus Filter"
+///
+/// fn get_name() -> ObsString {
+///     ObsString::from(module::text("FocusFilter"))
+/// }
+/// ```
+///
+/// Translations are read from `data/locale/<locale>.ini` next to the
+/// library, with missing keys taken from the default locale.
+#[macro_export]
+macro_rules! obs_module {
+    ($t:ty) => {
+        $crate::obs_register_module!($t);
+    };
+    ($t:ty, default_locale = $locale:literal) => {
+        $crate::obs_register_module!($t);
+
+        #[allow(missing_safety_doc)]
+        #[no_mangle]
+        pub unsafe extern "C" fn obs_module_set_locale(locale: *const std::os::raw::c_char) {
+            $crate::module::set_locale(obs_current_module(), $crate::obs_string!($locale), locale);
+        }
+
+        #[allow(missing_safety_doc)]
+        #[no_mangle]
+        pub unsafe extern "C" fn obs_module_free_locale() {
+            $crate::module::free_locale();
+        }
+    };
+}
+
 #[macro_export]
 macro_rules! obs_register_module {
     ($t:ty) => {
@@ -88,8 +375,10 @@ macro_rules! obs_register_module {
         #[no_mangle]
         pub unsafe extern "C" fn obs_module_load() -> bool {
             let mut module = OBS_MODULE.as_mut().expect("Could not get current module!");
+            $crate::module::load_module();
             let mut context = unsafe { $crate::module::LoadContext::new() };
-            let ret = module.load(&mut context);
+            let ret = $crate::unwind::handle_unwind::<$t, _>("load", || module.load(&mut context))
+                .unwrap_or(false);
             LOAD_CONTEXT = Some(context);
 
             ret
@@ -99,32 +388,36 @@ macro_rules! obs_register_module {
         #[no_mangle]
         pub unsafe extern "C" fn obs_module_unload() {
             let mut module = OBS_MODULE.as_mut().expect("Could not get current module!");
-            module.unload();
+            $crate::unwind::handle_unwind::<$t, _>("unload", || module.unload());
+            let module = OBS_MODULE.take();
+            $crate::unwind::handle_unwind::<$t, _>("drop", || drop(module));
+            LOAD_CONTEXT = None;
+            $crate::module::unload_module();
         }
 
         #[allow(missing_safety_doc)]
         #[no_mangle]
         pub unsafe extern "C" fn obs_module_post_load() {
             let mut module = OBS_MODULE.as_mut().expect("Could not get current module!");
-            module.post_load();
+            $crate::unwind::handle_unwind::<$t, _>("post_load", || module.post_load());
         }
 
         #[allow(missing_safety_doc)]
         #[no_mangle]
         pub unsafe extern "C" fn obs_module_name() -> *const std::os::raw::c_char {
-            <$t>::name().as_ptr()
+            <$t>::name().as_cached_ptr()
         }
 
         #[allow(missing_safety_doc)]
         #[no_mangle]
         pub unsafe extern "C" fn obs_module_description() -> *const std::os::raw::c_char {
-            <$t>::description().as_ptr()
+            <$t>::description().as_cached_ptr()
         }
 
         #[allow(missing_safety_doc)]
         #[no_mangle]
         pub unsafe extern "C" fn obs_module_author() -> *const std::os::raw::c_char {
-            <$t>::author().as_ptr()
+            <$t>::author().as_cached_ptr()
         }
     };
 }
@@ -148,3 +441,185 @@ impl ModuleContext {
         self.raw
     }
 }
+
+/// Registers `hook` to run when OBS unloads the module, to tear down state
+/// kept in statics, such as background threads, caches or connections, while
+/// the library is still loaded.
+///
+/// Rust never runs destructors of statics, and OBS unmapping the library
+/// under a thread that is still running crashes OBS, so such state must be
+/// torn down explicitly. See [`Module::unload`] for when hooks run.
+///
+/// ```ignore
+/// static WORKER: Mutex<Option<Worker>> = Mutex::new(None);
+///
+/// fn load(&mut self, load_context: &mut LoadContext) -> bool {
+///     *WORKER.lock().unwrap() = Some(Worker::spawn());
+///     module::on_unload(|| {
+///         if let Some(worker) = WORKER.lock().unwrap().take() {
+///             worker.stop_and_join();
+///         }
+///     });
+///     true
+/// }
+/// ```
+pub fn on_unload<F: FnOnce() + Send + 'static>(hook: F) {
+    UNLOAD_HOOKS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .push(Box::new(hook));
+}
+
+/// State shared by every source of a module, set in [`Module::load`] and
+/// dropped when the module is unloaded:
+///
+/// ```ignore
+/// static DEVICES: ModuleState<Mutex<DeviceList>> = ModuleState::new();
+///
+/// fn load(&mut self, load_context: &mut LoadContext) -> bool {
+///     DEVICES.init(Mutex::new(DeviceList::scan()));
+///     // ...
+/// }
+///
+/// // In any callback:
+/// let count = DEVICES.with(|devices| devices.lock().unwrap().len());
+/// ```
+///
+/// Callbacks run on many threads at once, so `T` must be `Sync`, i.e. carry
+/// its own locking for any state that changes after load.
+pub struct ModuleState<T> {
+    value: RwLock<Option<Arc<T>>>,
+}
+
+impl<T: Send + Sync + 'static> ModuleState<T> {
+    pub const fn new() -> Self {
+        Self {
+            value: RwLock::new(None),
+        }
+    }
+
+    /// Sets the state, to be dropped when the module is unloaded. Returns
+    /// `false` and drops `value` if the state was already set.
+    pub fn init(&'static self, value: T) -> bool {
+        let mut current = self.value.write().unwrap_or_else(|e| e.into_inner());
+        if current.is_some() {
+            return false;
+        }
+        *current = Some(Arc::new(value));
+        on_unload(move || {
+            let value = self.value.write().unwrap_or_else(|e| e.into_inner()).take();
+            drop(value);
+        });
+        true
+    }
+
+    /// Runs `func` with the state, returning `None` if it is not set.
+    pub fn with<R>(&self, func: impl FnOnce(&T) -> R) -> Option<R> {
+        let current = self.value.read().unwrap_or_else(|e| e.into_inner());
+        current.as_deref().map(func)
+    }
+
+    /// Returns a handle to the state, e.g. to keep in the data of a source.
+    ///
+    /// Handles must be dropped by the time the module is unloaded, as they
+    /// otherwise keep the state alive past it. Sources are always destroyed
+    /// before that.
+    pub fn get(&self) -> Option<Arc<T>> {
+        self.value.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    pub fn is_initialized(&self) -> bool {
+        self.value
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .is_some()
+    }
+}
+
+impl<T: Send + Sync + 'static> Default for ModuleState<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Returns the translation of `key` in the current locale, or `key` itself if
+/// it has none, like `obs_module_text` in C plugins.
+///
+/// Translations are only loaded by modules exported with [`obs_module`] and a
+/// default locale.
+pub fn text(key: &str) -> String {
+    let c_key = match CString::new(key) {
+        Ok(c_key) => c_key,
+        Err(_) => return key.to_owned(),
+    };
+    let locale = LOCALE.read().unwrap_or_else(|e| e.into_inner());
+    if let Some(lookup) = locale.as_ref() {
+        let mut value = std::ptr::null();
+        unsafe {
+            if text_lookup_getstr(lookup.0, c_key.as_ptr(), &mut value) && !value.is_null() {
+                return CStr::from_ptr(value).to_string_lossy().into_owned();
+            }
+        }
+    }
+    key.to_owned()
+}
+
+/// Loads the translations of `locale`, called by [`obs_module`] whenever OBS
+/// sets the locale.
+#[doc(hidden)]
+pub unsafe fn set_locale(
+    module: *mut obs_module_t,
+    default_locale: ObsString,
+    locale: *const c_char,
+) {
+    let lookup = obs_module_load_locale(module, default_locale.as_ptr(), locale);
+    let lookup = if lookup.is_null() {
+        None
+    } else {
+        Some(Lookup(lookup))
+    };
+    let previous = std::mem::replace(
+        &mut *LOCALE.write().unwrap_or_else(|e| e.into_inner()),
+        lookup,
+    );
+    if let Some(previous) = previous {
+        text_lookup_destroy(previous.0);
+    }
+}
+
+/// Frees the translations, called by [`obs_module`] before the module is
+/// unloaded.
+#[doc(hidden)]
+pub fn free_locale() {
+    let lookup = LOCALE.write().unwrap_or_else(|e| e.into_inner()).take();
+    if let Some(lookup) = lookup {
+        unsafe { text_lookup_destroy(lookup.0) };
+    }
+}
+
+/// Sets up module-level state, called by [`obs_register_module`] before
+/// [`Module::load`].
+#[doc(hidden)]
+pub fn load_module() {
+    crate::unwind::install_hook();
+
+    #[cfg(feature = "tokio")]
+    if let Err(err) = crate::runtime::start() {
+        crate::error!("Failed to start the module runtime: {}", err);
+    }
+}
+
+/// Tears down module-level state, called by [`obs_register_module`] after
+/// [`Module::unload`].
+#[doc(hidden)]
+pub fn unload_module() {
+    let hooks = std::mem::take(&mut *UNLOAD_HOOKS.lock().unwrap_or_else(|e| e.into_inner()));
+    for hook in hooks.into_iter().rev() {
+        handle_unwind::<UnloadHook, _>("unload_hook", hook);
+    }
+
+    #[cfg(feature = "tokio")]
+    crate::runtime::shutdown();
+    crate::unwind::reset_panic_hook();
+    crate::string::clear_cache();
+}
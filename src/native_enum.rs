@@ -32,7 +32,7 @@ macro_rules! native_enum {
             }
 
             impl Into<$native_name> for $name {
-                fn into(self) -> obs_text_type {
+                fn into(self) -> $native_name {
                     match self {
                         $(Self::$rust => [<$native_name _ $native>]),*
                     }
@@ -41,7 +41,7 @@ macro_rules! native_enum {
 
             impl std::convert::TryFrom<$native_name> for $name {
                 type Error = crate::native_enum::NativeParsingError;
-                fn try_from(value: $native_name) -> Result<Self, Self::Error> {
+                fn try_from(value: $native_name) -> Result<Self, crate::native_enum::NativeParsingError> {
                     match value {
                         $([<$native_name _ $native>] => Ok(Self::$rust)),*,
                         _ => Err(crate::native_enum::NativeParsingError::new(stringify!($name), value as i64))
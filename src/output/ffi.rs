@@ -0,0 +1,295 @@
+use super::context::CreatableOutputContext;
+use super::traits::*;
+use super::OutputContext;
+use crate::data::DataObj;
+use crate::encoder::EncoderPacket;
+use crate::source::audio::AudioFrames;
+use crate::source::context::GlobalContext;
+use crate::source::properties::Properties;
+use crate::unwind::handle_unwind;
+use crate::video::{VideoDataContext, VideoFormat, VideoInfo};
+use crate::wrapper::PtrWrapper;
+use std::convert::TryFrom;
+use std::ffi::c_void;
+use std::mem::forget;
+use std::os::raw::c_char;
+
+use obs_sys::{
+    audio_data, audio_output_get_channels, calldata_get_data, calldata_t, encoder_packet,
+    obs_data_t, obs_output_audio, obs_output_get_height, obs_output_get_signal_handler,
+    obs_output_get_type_data, obs_output_get_width, obs_output_t, obs_output_video, obs_properties,
+    signal_handler_connect, signal_handler_disconnect, video_data, video_output_get_info,
+};
+
+/// A signal the wrapper of every output of a type is connected to.
+pub(super) struct SignalCallback {
+    /// Nul-terminated signal name.
+    pub(super) signal: &'static str,
+    pub(super) callback: unsafe extern "C" fn(*mut c_void, *mut calldata_t),
+}
+
+pub(super) struct OutputTypeData {
+    pub(super) signals: Vec<SignalCallback>,
+}
+
+pub unsafe extern "C" fn free_type_data(type_data: *mut c_void) {
+    drop(Box::from_raw(type_data as *mut OutputTypeData));
+}
+
+/// Connects or disconnects the signals of the output type to `data`.
+unsafe fn connect_signals(output: *mut obs_output_t, data: *mut c_void, connect: bool) {
+    let type_data = obs_output_get_type_data(output) as *const OutputTypeData;
+    let type_data = match type_data.as_ref() {
+        Some(type_data) => type_data,
+        None => return,
+    };
+
+    let handler = obs_output_get_signal_handler(output);
+    for signal in &type_data.signals {
+        let name = signal.signal.as_ptr() as *const c_char;
+        if connect {
+            signal_handler_connect(handler, name, Some(signal.callback), data);
+        } else {
+            signal_handler_disconnect(handler, name, Some(signal.callback), data);
+        }
+    }
+}
+
+/// Reads the integer parameter `name`, the same as `calldata_int`.
+pub(crate) unsafe fn calldata_int(calldata: *mut calldata_t, name: &str) -> Option<i64> {
+    let name = std::ffi::CString::new(name).ok()?;
+    let mut value: i64 = 0;
+    let found = calldata_get_data(
+        calldata,
+        name.as_ptr(),
+        &mut value as *mut i64 as *mut c_void,
+        std::mem::size_of::<i64>() as _,
+    );
+    if found {
+        Some(value)
+    } else {
+        None
+    }
+}
+
+struct DataWrapper<D> {
+    data: Option<D>,
+    output: *mut obs_output_t,
+    format: Option<VideoFormat>,
+}
+
+impl<D> DataWrapper<D> {
+    fn new(data: Option<D>, output: *mut obs_output_t) -> Self {
+        Self {
+            data,
+            output,
+            format: None,
+        }
+    }
+
+    fn context(&self) -> OutputContext {
+        OutputContext {
+            output: self.output,
+        }
+    }
+
+    unsafe fn video_frame(&self, frame: *mut video_data) -> VideoDataContext {
+        let format = self.format.unwrap_or_else(|| {
+            let info = video_output_get_info(obs_output_video(self.output));
+            info.as_ref()
+                .and_then(|info| VideoFormat::try_from(info.format).ok())
+                .unwrap_or(VideoFormat::None)
+        });
+        VideoDataContext::from_raw(
+            frame,
+            format,
+            obs_output_get_width(self.output),
+            obs_output_get_height(self.output),
+        )
+    }
+}
+
+pub unsafe extern "C" fn get_name<D, F: GetNameOutput<D>>(
+    _type_data: *mut c_void,
+) -> *const c_char {
+    handle_unwind::<F, _>("get_name", || F::get_name().as_cached_ptr()).unwrap_or(std::ptr::null())
+}
+
+pub unsafe extern "C" fn create_default_data<D>(
+    _settings: *mut obs_data_t,
+    output: *mut obs_output_t,
+) -> *mut c_void {
+    let data = Box::new(DataWrapper::<D>::new(None, output));
+    let data = Box::into_raw(data) as *mut c_void;
+    connect_signals(output, data, true);
+    data
+}
+
+pub unsafe extern "C" fn create<D, F: CreatableOutput<D>>(
+    settings: *mut obs_data_t,
+    output: *mut obs_output_t,
+) -> *mut c_void {
+    let mut global = GlobalContext::new();
+    let settings = DataObj::from_raw(settings);
+    let mut create = CreatableOutputContext::from_raw(settings, &mut global);
+
+    let data = handle_unwind::<F, _>("create", || {
+        F::create(&mut create, OutputContext { output })
+    });
+    forget(create.settings);
+
+    let data = match data {
+        Some(data) => data,
+        None => return std::ptr::null_mut(),
+    };
+
+    let wrapper = Box::new(DataWrapper::new(Some(data), output));
+    let data = Box::into_raw(wrapper) as *mut c_void;
+    connect_signals(output, data, true);
+    data
+}
+
+pub unsafe extern "C" fn destroy<D>(data: *mut c_void) {
+    let wrapper: Box<DataWrapper<D>> = Box::from_raw(data as *mut DataWrapper<D>);
+    connect_signals(wrapper.output, data, false);
+    handle_unwind::<D, _>("drop", || drop(wrapper));
+}
+
+pub unsafe extern "C" fn start<D, F: StartOutput<D>>(data: *mut c_void) -> bool {
+    let wrapper: &mut DataWrapper<D> = &mut *(data as *mut DataWrapper<D>);
+    let mut context = wrapper.context();
+    handle_unwind::<F, _>("start", || F::start(&mut wrapper.data, &mut context)).unwrap_or(false)
+}
+
+pub unsafe extern "C" fn stop<D, F: StopOutput<D>>(data: *mut c_void, ts: u64) {
+    let wrapper: &mut DataWrapper<D> = &mut *(data as *mut DataWrapper<D>);
+    let mut context = wrapper.context();
+    handle_unwind::<F, _>("stop", || F::stop(&mut wrapper.data, &mut context, ts));
+}
+
+pub unsafe extern "C" fn raw_video<D, F: RawVideoOutput<D>>(
+    data: *mut c_void,
+    frame: *mut video_data,
+) {
+    let wrapper: &mut DataWrapper<D> = &mut *(data as *mut DataWrapper<D>);
+    let mut frame = wrapper.video_frame(frame);
+    handle_unwind::<F, _>("raw_video", || F::raw_video(&mut wrapper.data, &mut frame));
+}
+
+pub unsafe extern "C" fn raw_audio<D, F: RawAudioOutput<D>>(
+    data: *mut c_void,
+    frames: *mut audio_data,
+) {
+    let wrapper: &mut DataWrapper<D> = &mut *(data as *mut DataWrapper<D>);
+    let channels = audio_output_get_channels(obs_output_audio(wrapper.output)) as usize;
+    let frames = AudioFrames::from_raw(frames, channels);
+    handle_unwind::<F, _>("raw_audio", || F::raw_audio(&mut wrapper.data, &frames));
+}
+
+pub unsafe extern "C" fn encoded_packet<D, F: EncodedPacketOutput<D>>(
+    data: *mut c_void,
+    packet: *mut encoder_packet,
+) {
+    let wrapper: &mut DataWrapper<D> = &mut *(data as *mut DataWrapper<D>);
+    let packet = EncoderPacket::from_raw(packet);
+    handle_unwind::<F, _>("encoded_packet", || {
+        F::encoded_packet(&mut wrapper.data, packet.as_ref())
+    });
+}
+
+pub unsafe extern "C" fn update<D, F: UpdateOutput<D>>(
+    data: *mut c_void,
+    settings: *mut obs_data_t,
+) {
+    let wrapper: &mut DataWrapper<D> = &mut *(data as *mut DataWrapper<D>);
+    let mut settings = DataObj::from_raw(settings);
+    handle_unwind::<F, _>("update", || F::update(&mut wrapper.data, &mut settings));
+    forget(settings);
+}
+
+pub unsafe extern "C" fn get_properties<D, F: GetPropertiesOutput<D>>(
+    data: *mut c_void,
+) -> *mut obs_properties {
+    let wrapper: &mut DataWrapper<D> = &mut *(data as *mut DataWrapper<D>);
+
+    handle_unwind::<F, _>("get_properties", || {
+        let mut properties = Properties::new();
+        F::get_properties(&mut wrapper.data, &mut properties);
+        properties.into_raw()
+    })
+    .unwrap_or(std::ptr::null_mut())
+}
+
+pub unsafe extern "C" fn get_defaults<D, F: GetDefaultsOutput<D>>(settings: *mut obs_data_t) {
+    let mut settings = DataObj::from_raw(settings);
+    handle_unwind::<F, _>("get_defaults", || F::get_defaults(&mut settings));
+    forget(settings);
+}
+
+pub unsafe extern "C" fn video_sink_start<D, F: VideoSinkOutput<D>>(data: *mut c_void) -> bool {
+    let wrapper: &mut DataWrapper<D> = &mut *(data as *mut DataWrapper<D>);
+    let mut context = wrapper.context();
+
+    let (width, height) = match VideoInfo::get() {
+        Some(info) => (info.output_width, info.output_height),
+        None => return false,
+    };
+
+    let format = F::format();
+    context.set_video_conversion(format, width, height);
+    wrapper.format = Some(format);
+
+    if !context.can_begin_data_capture() {
+        return false;
+    }
+
+    // Frames arrive as soon as capture begins, so `started` runs first and is
+    // undone if capture then fails.
+    if handle_unwind::<F, _>("started", || F::started(&mut wrapper.data, width, height)).is_none() {
+        return false;
+    }
+    if !context.begin_data_capture() {
+        handle_unwind::<F, _>("stopped", || F::stopped(&mut wrapper.data));
+        return false;
+    }
+    true
+}
+
+pub unsafe extern "C" fn video_sink_stop<D, F: VideoSinkOutput<D>>(data: *mut c_void, _ts: u64) {
+    let wrapper: &mut DataWrapper<D> = &mut *(data as *mut DataWrapper<D>);
+    let mut context = wrapper.context();
+    context.end_data_capture();
+    handle_unwind::<F, _>("stopped", || F::stopped(&mut wrapper.data));
+}
+
+pub unsafe extern "C" fn video_sink_raw_video<D, F: VideoSinkOutput<D>>(
+    data: *mut c_void,
+    frame: *mut video_data,
+) {
+    let wrapper: &mut DataWrapper<D> = &mut *(data as *mut DataWrapper<D>);
+    let frame = wrapper.video_frame(frame);
+    handle_unwind::<F, _>("frame", || F::frame(&mut wrapper.data, &frame));
+}
+
+pub unsafe extern "C" fn reconnect<D, F: ReconnectOutput<D>>(
+    data: *mut c_void,
+    calldata: *mut calldata_t,
+) {
+    let wrapper: &mut DataWrapper<D> = &mut *(data as *mut DataWrapper<D>);
+    let mut context = wrapper.context();
+    let timeout_sec = calldata_int(calldata, "timeout_sec").unwrap_or(0) as i32;
+    handle_unwind::<F, _>("reconnect", || {
+        F::reconnect(&mut wrapper.data, &mut context, timeout_sec)
+    });
+}
+
+pub unsafe extern "C" fn reconnect_success<D, F: ReconnectOutput<D>>(
+    data: *mut c_void,
+    _calldata: *mut calldata_t,
+) {
+    let wrapper: &mut DataWrapper<D> = &mut *(data as *mut DataWrapper<D>);
+    let mut context = wrapper.context();
+    handle_unwind::<F, _>("reconnect_success", || {
+        F::reconnect_success(&mut wrapper.data, &mut context)
+    });
+}
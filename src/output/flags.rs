@@ -0,0 +1,33 @@
+use bitflags::bitflags;
+use obs_sys::{
+    OBS_OUTPUT_AUDIO, OBS_OUTPUT_CAN_PAUSE, OBS_OUTPUT_ENCODED, OBS_OUTPUT_MULTI_TRACK,
+    OBS_OUTPUT_SERVICE, OBS_OUTPUT_VIDEO,
+};
+
+bitflags! {
+    /// Capabilities an output declares through `obs_output_info.flags`.
+    ///
+    /// `VIDEO`, `AUDIO` and `ENCODED` are also set automatically by
+    /// [`OutputInfoBuilder::build`](super::OutputInfoBuilder::build) when the
+    /// matching callbacks are enabled.
+    ///
+    /// See [OBS documentation](https://obsproject.com/docs/reference-outputs.html#c.obs_output_info.flags)
+    pub struct OutputInfoFlags: u32 {
+        /// Output takes video.
+        const VIDEO = OBS_OUTPUT_VIDEO;
+        /// Output takes audio.
+        const AUDIO = OBS_OUTPUT_AUDIO;
+        /// Output takes video and audio, the same as `VIDEO | AUDIO`.
+        const AV = OBS_OUTPUT_VIDEO | OBS_OUTPUT_AUDIO;
+        /// Output takes encoded packets instead of raw data.
+        const ENCODED = OBS_OUTPUT_ENCODED;
+        /// Output sends data to a service.
+        const SERVICE = OBS_OUTPUT_SERVICE;
+        /// Output supports multiple audio tracks.
+        const MULTI_TRACK = OBS_OUTPUT_MULTI_TRACK;
+        /// Output can be paused with
+        /// [`OutputContext::pause`](super::OutputContext::pause). OBS does
+        /// the pausing itself, by holding back data from the output.
+        const CAN_PAUSE = OBS_OUTPUT_CAN_PAUSE;
+    }
+}
@@ -0,0 +1,512 @@
+use paste::item;
+
+pub mod context;
+mod ffi;
+pub mod flags;
+pub mod signal;
+pub mod traits;
+
+pub use context::*;
+pub use flags::*;
+pub use signal::*;
+pub use traits::*;
+
+use obs_sys::{
+    obs_get_output_by_name, obs_output_active, obs_output_addref, obs_output_audio,
+    obs_output_begin_data_capture, obs_output_can_begin_data_capture, obs_output_can_pause,
+    obs_output_end_data_capture, obs_output_get_audio_encoder, obs_output_get_congestion,
+    obs_output_get_connect_time_ms, obs_output_get_frames_dropped, obs_output_get_height,
+    obs_output_get_id, obs_output_get_name, obs_output_get_pause_offset,
+    obs_output_get_total_bytes, obs_output_get_total_frames, obs_output_get_video_encoder,
+    obs_output_get_width, obs_output_info, obs_output_pause, obs_output_paused,
+    obs_output_reconnecting, obs_output_release, obs_output_set_audio_encoder,
+    obs_output_set_last_error, obs_output_set_media, obs_output_set_mixer, obs_output_set_mixers,
+    obs_output_set_reconnect_settings, obs_output_set_video_conversion,
+    obs_output_set_video_encoder, obs_output_signal_stop, obs_output_t, obs_output_video, size_t,
+    OBS_OUTPUT_AUDIO, OBS_OUTPUT_BAD_PATH, OBS_OUTPUT_CONNECT_FAILED, OBS_OUTPUT_DISCONNECTED,
+    OBS_OUTPUT_ENCODED, OBS_OUTPUT_ENCODE_ERROR, OBS_OUTPUT_ERROR, OBS_OUTPUT_INVALID_STREAM,
+    OBS_OUTPUT_NO_SPACE, OBS_OUTPUT_SUCCESS, OBS_OUTPUT_UNSUPPORTED, OBS_OUTPUT_VIDEO,
+};
+
+use crate::encoder::EncoderContext;
+use crate::source::audio::AudioRef;
+use crate::video::{self, VideoFormat, VideoRef};
+
+use std::{
+    ffi::{CStr, CString},
+    marker::PhantomData,
+    os::raw::c_int,
+};
+
+/// Context wrapping an OBS output - the final destination of audio and video data.
+///
+/// See [OBS documentation](https://obsproject.com/docs/reference-outputs.html#c.obs_output_t)
+pub struct OutputContext {
+    output: *mut obs_output_t,
+}
+
+impl OutputContext {
+    /// Return a unique id for the output
+    pub fn id(&self) -> usize {
+        self.output as usize
+    }
+
+    pub fn output_id(&self) -> Option<&str> {
+        unsafe {
+            let ptr = obs_output_get_id(self.output);
+            if ptr.is_null() {
+                None
+            } else {
+                Some(CStr::from_ptr(ptr).to_str().unwrap())
+            }
+        }
+    }
+
+    pub fn name(&self) -> Option<&str> {
+        unsafe {
+            let ptr = obs_output_get_name(self.output);
+            if ptr.is_null() {
+                None
+            } else {
+                Some(CStr::from_ptr(ptr).to_str().unwrap())
+            }
+        }
+    }
+
+    pub fn active(&self) -> bool {
+        unsafe { obs_output_active(self.output) }
+    }
+
+    /// Width of the frames delivered to this output, after any scaling.
+    pub fn width(&self) -> u32 {
+        unsafe { obs_output_get_width(self.output) }
+    }
+
+    /// Height of the frames delivered to this output, after any scaling.
+    pub fn height(&self) -> u32 {
+        unsafe { obs_output_get_height(self.output) }
+    }
+
+    /// Total number of frames sent by the output since it started.
+    pub fn total_frames(&self) -> i32 {
+        unsafe { obs_output_get_total_frames(self.output) }
+    }
+
+    /// Number of frames dropped by the output since it started, e.g. because
+    /// of network congestion.
+    pub fn frames_dropped(&self) -> i32 {
+        unsafe { obs_output_get_frames_dropped(self.output) }
+    }
+
+    /// Total number of bytes sent by the output since it started.
+    pub fn total_bytes(&self) -> u64 {
+        unsafe { obs_output_get_total_bytes(self.output) }
+    }
+
+    /// Congestion of the connection, from `0.0` (none) to `1.0` (congested).
+    /// Always `0.0` for outputs that do not report it.
+    pub fn congestion(&self) -> f32 {
+        unsafe { obs_output_get_congestion(self.output) }
+    }
+
+    /// How long connecting took in milliseconds, or `-1` if not connected.
+    pub fn connect_time_ms(&self) -> i32 {
+        unsafe { obs_output_get_connect_time_ms(self.output) }
+    }
+
+    /// Returns whether the output was registered with
+    /// [`OutputInfoFlags::CAN_PAUSE`] and is able to pause right now, which
+    /// encoded outputs are only when their encoders are not shared with
+    /// another output.
+    pub fn can_pause(&self) -> bool {
+        unsafe { obs_output_can_pause(self.output) }
+    }
+
+    /// Pauses or resumes the output, returning `false` if it cannot be paused
+    /// or was already in the requested state.
+    ///
+    /// While paused, OBS stops delivering raw frames and drops encoded
+    /// packets, then shifts later timestamps by the paused duration, see
+    /// [`pause_offset`](Self::pause_offset).
+    ///
+    /// See [OBS documentation](https://obsproject.com/docs/reference-outputs.html#c.obs_output_pause)
+    pub fn pause(&mut self, pause: bool) -> bool {
+        unsafe { obs_output_pause(self.output, pause) }
+    }
+
+    pub fn paused(&self) -> bool {
+        unsafe { obs_output_paused(self.output) }
+    }
+
+    /// Total time the output has spent paused, in nanoseconds. Raw outputs
+    /// subtract this from frame timestamps to keep them continuous.
+    pub fn pause_offset(&self) -> u64 {
+        unsafe { obs_output_get_pause_offset(self.output) }
+    }
+
+    /// Makes OBS restart the output when it stops with
+    /// [`OutputStopCode::Disconnected`], up to `retry_count` times. The delay
+    /// starts at `retry_sec` seconds and doubles with every attempt. A
+    /// `retry_count` of 0 disables reconnecting.
+    ///
+    /// See [OBS documentation](https://obsproject.com/docs/reference-outputs.html#c.obs_output_set_reconnect_settings)
+    pub fn set_reconnect_settings(&mut self, retry_count: i32, retry_sec: i32) {
+        unsafe { obs_output_set_reconnect_settings(self.output, retry_count, retry_sec) }
+    }
+
+    /// Returns whether OBS is waiting to restart the output after it was
+    /// disconnected.
+    pub fn reconnecting(&self) -> bool {
+        unsafe { obs_output_reconnecting(self.output) }
+    }
+
+    /// Returns whether data capture can begin using the flags the output was registered with.
+    pub fn can_begin_data_capture(&self) -> bool {
+        unsafe { obs_output_can_begin_data_capture(self.output, 0) }
+    }
+
+    /// Begins receiving data from the media or encoders attached to this output.
+    ///
+    /// See [OBS documentation](https://obsproject.com/docs/reference-outputs.html#c.obs_output_begin_data_capture)
+    pub fn begin_data_capture(&mut self) -> bool {
+        unsafe { obs_output_begin_data_capture(self.output, 0) }
+    }
+
+    /// Stops receiving data from the media or encoders attached to this output.
+    pub fn end_data_capture(&mut self) {
+        unsafe { obs_output_end_data_capture(self.output) }
+    }
+
+    /// Attaches the output to a video and audio pipeline, usually the main
+    /// ones from [`GlobalContext`](crate::source::context::GlobalContext). Raw
+    /// outputs receive their frames from these.
+    ///
+    /// See [OBS documentation](https://obsproject.com/docs/reference-outputs.html#c.obs_output_set_media)
+    pub fn set_media(&mut self, video: &VideoRef, audio: &AudioRef) {
+        unsafe { obs_output_set_media(self.output, video.as_ptr(), audio.as_ptr()) }
+    }
+
+    /// The video pipeline the output is attached to.
+    pub fn video(&self) -> Option<VideoRef> {
+        unsafe { VideoRef::from_raw(obs_output_video(self.output)) }
+    }
+
+    /// The audio pipeline the output is attached to.
+    pub fn audio(&self) -> Option<AudioRef> {
+        let audio = unsafe { obs_output_audio(self.output) };
+        if audio.is_null() {
+            None
+        } else {
+            Some(unsafe { AudioRef::from_raw(audio) })
+        }
+    }
+
+    /// Selects the audio mixer (track) a single-track output receives.
+    pub fn set_mixer(&mut self, mixer_idx: usize) {
+        unsafe { obs_output_set_mixer(self.output, mixer_idx as size_t) }
+    }
+
+    /// Sets the audio mixers a multi-track output receives, as a bitmask.
+    pub fn set_mixers(&mut self, mixers: usize) {
+        unsafe { obs_output_set_mixers(self.output, mixers as size_t) }
+    }
+
+    /// Sets the encoder producing the video packets of an encoded output.
+    ///
+    /// See [OBS documentation](https://obsproject.com/docs/reference-outputs.html#c.obs_output_set_video_encoder)
+    pub fn set_video_encoder(&mut self, encoder: &EncoderContext) {
+        unsafe { obs_output_set_video_encoder(self.output, encoder.as_ptr()) }
+    }
+
+    /// Sets the encoder producing the audio packets of track `idx`. Only
+    /// outputs with [`OutputInfoFlags::MULTI_TRACK`] use tracks other than 0.
+    pub fn set_audio_encoder(&mut self, encoder: &EncoderContext, idx: usize) {
+        unsafe { obs_output_set_audio_encoder(self.output, encoder.as_ptr(), idx as size_t) }
+    }
+
+    pub fn video_encoder(&self) -> Option<EncoderContext> {
+        unsafe { EncoderContext::from_raw(obs_output_get_video_encoder(self.output)) }
+    }
+
+    pub fn audio_encoder(&self, idx: usize) -> Option<EncoderContext> {
+        unsafe {
+            EncoderContext::from_raw(obs_output_get_audio_encoder(self.output, idx as size_t))
+        }
+    }
+
+    /// Stops the output from within, e.g. when the connection drops, reporting
+    /// `code` to the user.
+    ///
+    /// See [OBS documentation](https://obsproject.com/docs/reference-outputs.html#c.obs_output_signal_stop)
+    pub fn signal_stop(&mut self, code: OutputStopCode) {
+        unsafe { obs_output_signal_stop(self.output, code.as_raw()) }
+    }
+
+    /// Sets the message shown to the user when the output stops with an
+    /// error.
+    pub fn set_last_error(&mut self, message: &str) {
+        if let Ok(message) = CString::new(message) {
+            unsafe { obs_output_set_last_error(self.output, message.as_ptr()) }
+        }
+    }
+
+    /// Requests raw frames in `format`, scaled to `width` x `height`.
+    pub(crate) fn set_video_conversion(&mut self, format: VideoFormat, width: u32, height: u32) {
+        let conversion = video::scale_info(format, width, height);
+        unsafe {
+            obs_output_set_video_conversion(self.output, &conversion);
+        }
+    }
+}
+
+/// Reason an output stopped, passed to [`OutputContext::signal_stop`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum OutputStopCode {
+    Success,
+    BadPath,
+    ConnectFailed,
+    InvalidStream,
+    Error,
+    Disconnected,
+    Unsupported,
+    NoSpace,
+    EncodeError,
+}
+
+impl OutputStopCode {
+    /// Converts an `OBS_OUTPUT_*` code, returning `None` for unknown ones.
+    pub fn from_raw(code: c_int) -> Option<Self> {
+        Some(match code {
+            c if c == OBS_OUTPUT_SUCCESS as c_int => OutputStopCode::Success,
+            OBS_OUTPUT_BAD_PATH => OutputStopCode::BadPath,
+            OBS_OUTPUT_CONNECT_FAILED => OutputStopCode::ConnectFailed,
+            OBS_OUTPUT_INVALID_STREAM => OutputStopCode::InvalidStream,
+            OBS_OUTPUT_ERROR => OutputStopCode::Error,
+            OBS_OUTPUT_DISCONNECTED => OutputStopCode::Disconnected,
+            OBS_OUTPUT_UNSUPPORTED => OutputStopCode::Unsupported,
+            OBS_OUTPUT_NO_SPACE => OutputStopCode::NoSpace,
+            OBS_OUTPUT_ENCODE_ERROR => OutputStopCode::EncodeError,
+            _ => return None,
+        })
+    }
+
+    pub fn as_raw(&self) -> c_int {
+        match self {
+            OutputStopCode::Success => OBS_OUTPUT_SUCCESS as c_int,
+            OutputStopCode::BadPath => OBS_OUTPUT_BAD_PATH,
+            OutputStopCode::ConnectFailed => OBS_OUTPUT_CONNECT_FAILED,
+            OutputStopCode::InvalidStream => OBS_OUTPUT_INVALID_STREAM,
+            OutputStopCode::Error => OBS_OUTPUT_ERROR,
+            OutputStopCode::Disconnected => OBS_OUTPUT_DISCONNECTED,
+            OutputStopCode::Unsupported => OBS_OUTPUT_UNSUPPORTED,
+            OutputStopCode::NoSpace => OBS_OUTPUT_NO_SPACE,
+            OutputStopCode::EncodeError => OBS_OUTPUT_ENCODE_ERROR,
+        }
+    }
+}
+
+/// An owned reference to an OBS output, released when dropped.
+///
+/// Dereferences to [`OutputContext`], which makes it possible to inspect
+/// outputs created by OBS itself, such as the streaming output.
+pub struct OutputRef {
+    context: OutputContext,
+}
+
+impl OutputRef {
+    /// Takes ownership of a reference to `output`, returning `None` if it is
+    /// null.
+    ///
+    /// # Safety
+    /// `output` must be null or an output pointer whose reference is owned by
+    /// the caller.
+    pub unsafe fn from_raw(output: *mut obs_output_t) -> Option<Self> {
+        if output.is_null() {
+            None
+        } else {
+            Some(Self {
+                context: OutputContext { output },
+            })
+        }
+    }
+
+    /// Looks up an output by its name.
+    pub(crate) fn by_name(name: &str) -> Option<Self> {
+        let name = CString::new(name).ok()?;
+        unsafe { Self::from_raw(obs_get_output_by_name(name.as_ptr())) }
+    }
+}
+
+impl Clone for OutputRef {
+    fn clone(&self) -> Self {
+        unsafe { obs_output_addref(self.context.output) }
+        Self {
+            context: OutputContext {
+                output: self.context.output,
+            },
+        }
+    }
+}
+
+impl std::ops::Deref for OutputRef {
+    type Target = OutputContext;
+
+    fn deref(&self) -> &OutputContext {
+        &self.context
+    }
+}
+
+impl std::ops::DerefMut for OutputRef {
+    fn deref_mut(&mut self) -> &mut OutputContext {
+        &mut self.context
+    }
+}
+
+impl Drop for OutputRef {
+    fn drop(&mut self) {
+        unsafe { obs_output_release(self.context.output) }
+    }
+}
+
+pub struct OutputInfo {
+    info: Box<obs_output_info>,
+}
+
+impl OutputInfo {
+    /// # Safety
+    /// Creates a raw pointer from a box and could cause UB is misused.
+    pub unsafe fn into_raw(self) -> *mut obs_output_info {
+        Box::into_raw(self.info)
+    }
+}
+
+/// The OutputInfoBuilder that handles creating the [OutputInfo](https://obsproject.com/docs/reference-outputs.html#c.obs_output_info) object.
+///
+/// Works the same way as [`SourceInfoBuilder`](crate::source::SourceInfoBuilder): each trait that
+/// is implemented for the output needs to be enabled using this builder.
+///
+/// ```rs
+/// let output = load_context
+///  .create_output_builder::<VirtualCamera, Data>()
+///  .enable_get_name()
+///  .enable_create()
+///  .enable_video_sink()
+///  .build();
+/// ```
+pub struct OutputInfoBuilder<T: Outputable, D> {
+    __output: PhantomData<T>,
+    __data: PhantomData<D>,
+    info: obs_output_info,
+    signals: Vec<ffi::SignalCallback>,
+}
+
+impl<T: Outputable, D> OutputInfoBuilder<T, D> {
+    pub(crate) fn new() -> Self {
+        Self {
+            __output: PhantomData,
+            __data: PhantomData,
+            info: obs_output_info {
+                id: T::get_id().as_cached_ptr(),
+                create: Some(ffi::create_default_data::<D>),
+                destroy: Some(ffi::destroy::<D>),
+                type_data: std::ptr::null_mut(),
+                ..Default::default()
+            },
+            signals: Vec::new(),
+        }
+    }
+
+    /// Declares additional [`OutputInfoFlags`] for the output, on top of
+    /// those inferred from the enabled callbacks. Encoded outputs need to
+    /// declare whether they take video, audio or both:
+    ///
+    /// ```rs
+    /// let output = load_context
+    ///  .create_output_builder::<Muxer, Data>()
+    ///  .enable_encoded_packet()
+    ///  .with_flags(OutputInfoFlags::AV)
+    ///  .build();
+    /// ```
+    pub fn with_flags(mut self, flags: OutputInfoFlags) -> Self {
+        self.info.flags |= flags.bits();
+        self
+    }
+
+    pub fn build(mut self) -> OutputInfo {
+        if self.info.raw_video.is_some() {
+            self.info.flags |= OBS_OUTPUT_VIDEO;
+        }
+
+        if self.info.raw_audio.is_some() || self.info.raw_audio2.is_some() {
+            self.info.flags |= OBS_OUTPUT_AUDIO;
+        }
+
+        if self.info.encoded_packet.is_some() {
+            self.info.flags |= OBS_OUTPUT_ENCODED;
+        }
+
+        if !self.signals.is_empty() {
+            let type_data = Box::new(ffi::OutputTypeData {
+                signals: self.signals,
+            });
+            self.info.type_data = Box::into_raw(type_data) as *mut _;
+            self.info.free_type_data = Some(ffi::free_type_data);
+        }
+
+        OutputInfo {
+            info: Box::new(self.info),
+        }
+    }
+}
+
+impl<D, T: Outputable + VideoSinkOutput<D>> OutputInfoBuilder<T, D> {
+    /// Enables [`VideoSinkOutput`], filling in `start`, `stop` and `raw_video`.
+    pub fn enable_video_sink(mut self) -> Self {
+        self.info.start = Some(ffi::video_sink_start::<D, T>);
+        self.info.stop = Some(ffi::video_sink_stop::<D, T>);
+        self.info.raw_video = Some(ffi::video_sink_raw_video::<D, T>);
+        self
+    }
+}
+
+impl<D, T: Outputable + ReconnectOutput<D>> OutputInfoBuilder<T, D> {
+    /// Enables [`ReconnectOutput`], connected to the `reconnect` and
+    /// `reconnect_success` signals of every output created from this type.
+    pub fn enable_reconnect(mut self) -> Self {
+        self.signals.push(ffi::SignalCallback {
+            signal: "reconnect\0",
+            callback: ffi::reconnect::<D, T>,
+        });
+        self.signals.push(ffi::SignalCallback {
+            signal: "reconnect_success\0",
+            callback: ffi::reconnect_success::<D, T>,
+        });
+        self
+    }
+}
+
+macro_rules! impl_output_builder {
+    ($($f:ident => $t:ident)*) => ($(
+        item! {
+            impl<D, T: Outputable + [<$t>]<D>> OutputInfoBuilder<T, D> {
+                pub fn [<enable_$f>](mut self) -> Self {
+                    self.info.[<$f>] = Some(ffi::[<$f>]::<D, T>);
+                    self
+                }
+            }
+        }
+    )*)
+}
+
+impl_output_builder! {
+    get_name => GetNameOutput
+    create => CreatableOutput
+    start => StartOutput
+    stop => StopOutput
+    raw_video => RawVideoOutput
+    raw_audio => RawAudioOutput
+    encoded_packet => EncodedPacketOutput
+    update => UpdateOutput
+    get_properties => GetPropertiesOutput
+    get_defaults => GetDefaultsOutput
+}
@@ -0,0 +1,182 @@
+use super::ffi::calldata_int;
+use super::{OutputContext, OutputRef, OutputStopCode};
+use crate::unwind::handle_unwind;
+use obs_sys::{
+    calldata_t, obs_output_get_ref, obs_output_get_signal_handler, signal_handler_connect,
+    signal_handler_disconnect,
+};
+
+use std::collections::VecDeque;
+use std::ffi::c_void;
+use std::os::raw::c_char;
+use std::sync::Mutex;
+
+/// A signal sent by an output, with its parameters decoded.
+///
+/// See [OBS documentation](https://obsproject.com/docs/reference-outputs.html#output-signals)
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum OutputSignal {
+    /// The output started.
+    Start,
+    /// The output stopped, with `None` for stop codes this crate does not
+    /// know about.
+    Stop(Option<OutputStopCode>),
+    /// The output is about to start.
+    Starting,
+    /// The output is about to stop.
+    Stopping,
+    /// The output started receiving data.
+    Activate,
+    /// The output stopped receiving data, which happens after `Stop` once
+    /// everything buffered has been sent.
+    Deactivate,
+    /// The output was disconnected and is restarted in `timeout_sec` seconds,
+    /// see [`OutputContext::set_reconnect_settings`].
+    Reconnect { timeout_sec: i32 },
+    /// The output started again after reconnecting.
+    ReconnectSuccess,
+}
+
+struct SignalState {
+    /// `None` while a thread is calling it.
+    callback: Option<Box<dyn FnMut(OutputSignal) + Send>>,
+    /// Signals sent while the callback was running, which the thread calling
+    /// it delivers before giving it back.
+    pending: VecDeque<OutputSignal>,
+}
+
+type SignalCallback = Mutex<SignalState>;
+
+/// A closure connected to the signals of an output with
+/// [`OutputContext::connect_signals`], disconnected when dropped.
+///
+/// Holds a reference to the output, so it must be dropped for the output to
+/// be destroyed.
+pub struct OutputSignalConnection {
+    output: OutputRef,
+    callback: *mut SignalCallback,
+}
+
+// The callback is `Send` and only called by one thread at a time.
+unsafe impl Send for OutputSignalConnection {}
+unsafe impl Sync for OutputSignalConnection {}
+
+impl OutputSignalConnection {
+    pub fn output(&self) -> &OutputRef {
+        &self.output
+    }
+
+    unsafe fn connect_all(&self, connect: bool) {
+        let handler = obs_output_get_signal_handler(self.output.output);
+        for (signal, callback) in SIGNALS {
+            let signal = signal.as_ptr() as *const c_char;
+            let data = self.callback as *mut c_void;
+            if connect {
+                signal_handler_connect(handler, signal, Some(*callback), data);
+            } else {
+                signal_handler_disconnect(handler, signal, Some(*callback), data);
+            }
+        }
+    }
+}
+
+impl Drop for OutputSignalConnection {
+    fn drop(&mut self) {
+        unsafe {
+            self.connect_all(false);
+            drop(Box::from_raw(self.callback));
+        }
+    }
+}
+
+impl OutputContext {
+    /// Calls `callback` with every signal the output sends from now on, until
+    /// the returned connection is dropped. Returns `None` if the output is
+    /// being destroyed.
+    ///
+    /// Signals are sent from whichever thread started, stopped or
+    /// reconnected the output, so `callback` should return quickly. The lock
+    /// on `callback` is not held while it runs, so it may start or stop the
+    /// output itself: signals sent meanwhile, from any thread, are queued and
+    /// passed to it in order once it returns.
+    ///
+    /// ```ignore
+    /// let connection = output.connect_signals(|signal| match signal {
+    ///     OutputSignal::Stop(Some(OutputStopCode::Disconnected)) => warn!("Stream dropped"),
+    ///     OutputSignal::ReconnectSuccess => info!("Stream is back"),
+    ///     _ => {}
+    /// });
+    /// ```
+    pub fn connect_signals(
+        &self,
+        callback: impl FnMut(OutputSignal) + Send + 'static,
+    ) -> Option<OutputSignalConnection> {
+        let callback: Box<dyn FnMut(OutputSignal) + Send> = Box::new(callback);
+        let connection = OutputSignalConnection {
+            output: unsafe { OutputRef::from_raw(obs_output_get_ref(self.output))? },
+            callback: Box::into_raw(Box::new(Mutex::new(SignalState {
+                callback: Some(callback),
+                pending: VecDeque::new(),
+            }))),
+        };
+        unsafe { connection.connect_all(true) };
+        Some(connection)
+    }
+}
+
+unsafe fn dispatch(data: *mut c_void, signal: OutputSignal) {
+    let state = &*(data as *const SignalCallback);
+    let lock = || state.lock().unwrap_or_else(|e| e.into_inner());
+
+    let mut callback = {
+        let mut state = lock();
+        state.pending.push_back(signal);
+        match state.callback.take() {
+            Some(callback) => callback,
+            // Whoever is running the callback delivers the signal next.
+            None => return,
+        }
+    };
+    loop {
+        let signal = {
+            let mut state = lock();
+            match state.pending.pop_front() {
+                Some(signal) => signal,
+                None => {
+                    state.callback = Some(callback);
+                    return;
+                }
+            }
+        };
+        handle_unwind::<OutputSignalConnection, _>("output_signal", || callback(signal));
+    }
+}
+
+macro_rules! signal_callbacks {
+    ($($name:ident => $signal:literal |$calldata:ident| $value:expr),* $(,)?) => {
+        $(
+            unsafe extern "C" fn $name(data: *mut c_void, $calldata: *mut calldata_t) {
+                dispatch(data, $value);
+            }
+        )*
+
+        const SIGNALS: &[(&str, unsafe extern "C" fn(*mut c_void, *mut calldata_t))] = &[
+            $((concat!($signal, "\0"), $name)),*
+        ];
+    };
+}
+
+signal_callbacks! {
+    start => "start" |_calldata| OutputSignal::Start,
+    stop => "stop" |calldata| OutputSignal::Stop(
+        calldata_int(calldata, "code").and_then(|code| OutputStopCode::from_raw(code as _))
+    ),
+    starting => "starting" |_calldata| OutputSignal::Starting,
+    stopping => "stopping" |_calldata| OutputSignal::Stopping,
+    activate => "activate" |_calldata| OutputSignal::Activate,
+    deactivate => "deactivate" |_calldata| OutputSignal::Deactivate,
+    reconnect => "reconnect" |calldata| OutputSignal::Reconnect {
+        timeout_sec: calldata_int(calldata, "timeout_sec").unwrap_or(0) as i32,
+    },
+    reconnect_success => "reconnect_success" |_calldata| OutputSignal::ReconnectSuccess,
+}
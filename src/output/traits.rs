@@ -0,0 +1,93 @@
+use super::context::CreatableOutputContext;
+use super::OutputContext;
+use crate::data::DataObj;
+use crate::encoder::EncoderPacket;
+use crate::source::audio::AudioFrames;
+use crate::source::properties::Properties;
+use crate::string::ObsString;
+use crate::video::{VideoDataContext, VideoFormat};
+
+pub trait Outputable {
+    fn get_id() -> ObsString;
+}
+
+pub trait GetNameOutput<D> {
+    fn get_name() -> ObsString;
+}
+
+pub trait CreatableOutput<D> {
+    fn create(create: &mut CreatableOutputContext, output: OutputContext) -> D;
+}
+
+pub trait StartOutput<D> {
+    fn start(data: &mut Option<D>, output: &mut OutputContext) -> bool;
+}
+
+pub trait StopOutput<D> {
+    fn stop(data: &mut Option<D>, output: &mut OutputContext, ts: u64);
+}
+
+pub trait RawVideoOutput<D> {
+    fn raw_video(data: &mut Option<D>, frame: &mut VideoDataContext);
+}
+
+/// Receives the raw audio mix as planar float, for outputs that do not use
+/// an encoder.
+pub trait RawAudioOutput<D> {
+    fn raw_audio(data: &mut Option<D>, frames: &AudioFrames);
+}
+
+/// Receives the packets of the encoders attached to the output, for muxers
+/// and streaming protocols. `packet` is `None` when an encoder failed, in
+/// which case the output should stop with [`OutputStopCode::EncodeError`](super::OutputStopCode::EncodeError).
+pub trait EncodedPacketOutput<D> {
+    fn encoded_packet(data: &mut Option<D>, packet: Option<&EncoderPacket>);
+}
+
+/// Notified while OBS restarts an output that stopped with
+/// [`OutputStopCode::Disconnected`](super::OutputStopCode::Disconnected), see
+/// [`OutputContext::set_reconnect_settings`].
+pub trait ReconnectOutput<D> {
+    /// Called when an attempt is scheduled, `timeout_sec` seconds before the
+    /// output is started again.
+    fn reconnect(data: &mut Option<D>, output: &mut OutputContext, timeout_sec: i32);
+
+    /// Called once the output has started again.
+    fn reconnect_success(_data: &mut Option<D>, _output: &mut OutputContext) {}
+}
+
+pub trait UpdateOutput<D> {
+    fn update(data: &mut Option<D>, settings: &mut DataObj);
+}
+
+pub trait GetPropertiesOutput<D> {
+    fn get_properties(data: &mut Option<D>, properties: &mut Properties);
+}
+
+pub trait GetDefaultsOutput<D> {
+    fn get_defaults(settings: &mut DataObj);
+}
+
+/// A raw video output that receives the composited canvas at canvas FPS,
+/// converted to [`format`](VideoSinkOutput::format) and scaled to the output
+/// resolution.
+///
+/// Enabling this trait provides `start`, `stop` and `raw_video`, so it
+/// cannot be combined with [`StartOutput`], [`StopOutput`] or [`RawVideoOutput`].
+pub trait VideoSinkOutput<D> {
+    /// The pixel format frames are delivered in, usually [`VideoFormat::BGRA`]
+    /// or [`VideoFormat::NV12`].
+    fn format() -> VideoFormat;
+
+    /// Called for every frame. Rows should be read through the frame's
+    /// stride-aware accessors.
+    fn frame(data: &mut Option<D>, frame: &VideoDataContext);
+
+    /// Called right before capture starts, with the resolution frames will
+    /// have. If capture then fails to start, [`stopped`](Self::stopped) is
+    /// called.
+    fn started(_data: &mut Option<D>, _width: u32, _height: u32) {}
+
+    /// Called once capture has stopped, or failed to start.
+    fn stopped(_data: &mut Option<D>) {}
+}
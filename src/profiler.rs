@@ -0,0 +1,111 @@
+//! Timing plugin code with the OBS profiler, whose results are written to the
+//! log when OBS exits and shown in the frontend's performance view:
+//!
+//! ```ignore
+//! fn video_tick(data: &mut Option<Data>, seconds: f32) {
+//!     let _scope = ProfileScope::new(obs_string!("scroll_focus::update_zoom"));
+//!     // ...
+//! }
+//! ```
+//!
+//! With [`set_profile_callbacks`] enabled, every callback the wrapper hands to
+//! OBS is additionally profiled under the name it is reported with on panic,
+//! e.g. `my_plugin::MySource::video_render`.
+
+use crate::string::ObsString;
+use obs_sys::{profile_end, profile_start};
+
+use std::collections::HashMap;
+use std::ffi::CString;
+use std::os::raw::c_char;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+static PROFILE_CALLBACKS: AtomicBool = AtomicBool::new(false);
+
+// The profiler identifies entries by the address of their name, so every name
+// is kept alive, at a stable address, until the plugin is unloaded.
+static NAMES: Mutex<Option<HashMap<String, CString>>> = Mutex::new(None);
+
+// Names of wrapped callbacks by type and callback, so that the name of a
+// callback is only built the first time it is profiled.
+type CallbackNames = HashMap<&'static str, HashMap<String, CString>>;
+static CALLBACK_NAMES: Mutex<Option<CallbackNames>> = Mutex::new(None);
+
+fn intern(name: &str) -> *const c_char {
+    let mut names = NAMES.lock().unwrap_or_else(|e| e.into_inner());
+    names
+        .get_or_insert_with(HashMap::new)
+        .entry(name.to_owned())
+        .or_insert_with(|| CString::new(name).unwrap_or_default())
+        .as_ptr()
+}
+
+/// Profiles the code running until the scope is dropped, with
+/// `profile_start` and `profile_end`.
+///
+/// Scopes must be dropped in reverse order of creation on the thread they
+/// were created on, which holding them in local variables ensures.
+///
+/// See [OBS documentation](https://obsproject.com/docs/reference-libobs-util-profiler.html)
+pub struct ProfileScope {
+    name: *const c_char,
+}
+
+impl ProfileScope {
+    /// Starts a scope called `name`. Static names made with `obs_string!` are
+    /// passed straight to OBS, dynamic ones are copied once and reused.
+    pub fn new(name: ObsString) -> Self {
+        let name = match name {
+            ObsString::Static(_) => name.as_ptr(),
+            ObsString::Dynamic(_) => intern(name.as_str()),
+        };
+        unsafe { profile_start(name) };
+        Self { name }
+    }
+}
+
+impl Drop for ProfileScope {
+    fn drop(&mut self) {
+        unsafe { profile_end(self.name) }
+    }
+}
+
+/// Runs `func` inside a [`ProfileScope`] called `name`.
+pub fn profile<R>(name: ObsString, func: impl FnOnce() -> R) -> R {
+    let _scope = ProfileScope::new(name);
+    func()
+}
+
+/// Sets whether every callback the wrapper hands to OBS is profiled. Disabled
+/// by default, since it adds a lookup of the callback name to every call.
+pub fn set_profile_callbacks(enabled: bool) {
+    PROFILE_CALLBACKS.store(enabled, Ordering::Relaxed);
+}
+
+/// Starts a scope called `<type_name>::<callback>` for a wrapped callback if
+/// [`set_profile_callbacks`] is enabled.
+pub(crate) fn callback_scope(type_name: &'static str, callback: &str) -> Option<ProfileScope> {
+    if !PROFILE_CALLBACKS.load(Ordering::Relaxed) {
+        return None;
+    }
+
+    let name = {
+        let mut names = CALLBACK_NAMES.lock().unwrap_or_else(|e| e.into_inner());
+        let callbacks = names
+            .get_or_insert_with(HashMap::new)
+            .entry(type_name)
+            .or_default();
+        match callbacks.get(callback) {
+            Some(name) => name.as_ptr(),
+            None => callbacks
+                .entry(callback.to_owned())
+                .or_insert_with(|| {
+                    CString::new(format!("{}::{}", type_name, callback)).unwrap_or_default()
+                })
+                .as_ptr(),
+        }
+    };
+    unsafe { profile_start(name) };
+    Some(ProfileScope { name })
+}
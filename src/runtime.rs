@@ -0,0 +1,108 @@
+//! A background [tokio](https://tokio.rs) runtime shared by the whole module,
+//! available with the `tokio` feature.
+//!
+//! The runtime is started when OBS loads the module, before
+//! [`Module::load`](crate::module::Module::load), and shut down when OBS
+//! unloads it. Sources grab a [`RuntimeHandle`] with [`handle`] to spawn
+//! network or other IO tasks:
+//!
+//! ```ignore
+//! // From any callback or thread:
+//! if let Some(handle) = runtime::handle() {
+//!     handle.spawn(async move { poll_chat().await });
+//! }
+//! ```
+
+use std::future::Future;
+use std::io;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use tokio::runtime::{Builder, Handle, Runtime};
+use tokio::task::JoinHandle;
+
+/// How long unloading waits for running tasks before abandoning them.
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(1);
+
+static RUNTIME: Mutex<Option<Runtime>> = Mutex::new(None);
+
+/// A cloneable handle to the module runtime.
+#[derive(Clone, Debug)]
+pub struct RuntimeHandle {
+    handle: Handle,
+}
+
+impl RuntimeHandle {
+    /// Spawns `future` on the runtime.
+    pub fn spawn<F>(&self, future: F) -> JoinHandle<F::Output>
+    where
+        F: Future + Send + 'static,
+        F::Output: Send + 'static,
+    {
+        self.handle.spawn(future)
+    }
+
+    /// Runs a blocking function on the runtime's blocking thread pool.
+    pub fn spawn_blocking<F, R>(&self, func: F) -> JoinHandle<R>
+    where
+        F: FnOnce() -> R + Send + 'static,
+        R: Send + 'static,
+    {
+        self.handle.spawn_blocking(func)
+    }
+
+    /// Blocks the current thread until `future` completes.
+    ///
+    /// Must not be called from OBS's graphics or audio threads, or from within
+    /// the runtime itself.
+    pub fn block_on<F: Future>(&self, future: F) -> F::Output {
+        self.handle.block_on(future)
+    }
+
+    /// The underlying tokio handle.
+    pub fn inner(&self) -> &Handle {
+        &self.handle
+    }
+}
+
+/// Starts the module runtime if it is not already running, e.g. again after
+/// [`shutdown`]. Failing to start it when the module loads is only logged.
+pub fn start() -> io::Result<RuntimeHandle> {
+    let mut runtime = RUNTIME.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(runtime) = runtime.as_ref() {
+        return Ok(RuntimeHandle {
+            handle: runtime.handle().clone(),
+        });
+    }
+
+    let started = Builder::new_multi_thread()
+        .thread_name("obs-wrapper-runtime")
+        .enable_all()
+        .build()?;
+    let handle = started.handle().clone();
+    *runtime = Some(started);
+
+    Ok(RuntimeHandle { handle })
+}
+
+/// Returns a handle to the module runtime, or `None` if it is not running
+/// because it failed to start or was shut down.
+pub fn handle() -> Option<RuntimeHandle> {
+    RUNTIME
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .as_ref()
+        .map(|runtime| RuntimeHandle {
+            handle: runtime.handle().clone(),
+        })
+}
+
+/// Shuts down the module runtime, waiting briefly for running tasks.
+///
+/// Called automatically when the module is unloaded.
+pub fn shutdown() {
+    let runtime = RUNTIME.lock().unwrap_or_else(|e| e.into_inner()).take();
+    if let Some(runtime) = runtime {
+        runtime.shutdown_timeout(SHUTDOWN_TIMEOUT);
+    }
+}
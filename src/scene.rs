@@ -0,0 +1,426 @@
+//! Scenes are sources arranging other sources as scene items. Plugins can
+//! build and rearrange scenes programmatically:
+//!
+//! ```ignore
+//! let mut scene = global.scene_by_name("Scene").unwrap();
+//! let source = SourceRef::create("color_source", "Background", None).unwrap();
+//! let mut item = scene.add(&source).unwrap();
+//! item.set_order(OrderMovement::MoveBottom);
+//! ```
+
+#![allow(non_upper_case_globals)]
+
+use crate::graphics::Vec2;
+use crate::native_enum;
+use crate::source::{SourceContext, SourceRef};
+use crate::string::ObsString;
+use crate::symbol::{Symbol, LIBOBS};
+use bitflags::bitflags;
+use obs_sys::{
+    obs_bounds_type, obs_bounds_type_OBS_BOUNDS_MAX_ONLY, obs_bounds_type_OBS_BOUNDS_NONE,
+    obs_bounds_type_OBS_BOUNDS_SCALE_INNER, obs_bounds_type_OBS_BOUNDS_SCALE_OUTER,
+    obs_bounds_type_OBS_BOUNDS_SCALE_TO_HEIGHT, obs_bounds_type_OBS_BOUNDS_SCALE_TO_WIDTH,
+    obs_bounds_type_OBS_BOUNDS_STRETCH, obs_order_movement,
+    obs_order_movement_OBS_ORDER_MOVE_BOTTOM, obs_order_movement_OBS_ORDER_MOVE_DOWN,
+    obs_order_movement_OBS_ORDER_MOVE_TOP, obs_order_movement_OBS_ORDER_MOVE_UP, obs_scale_type,
+    obs_scale_type_OBS_SCALE_AREA, obs_scale_type_OBS_SCALE_BICUBIC,
+    obs_scale_type_OBS_SCALE_BILINEAR, obs_scale_type_OBS_SCALE_DISABLE,
+    obs_scale_type_OBS_SCALE_LANCZOS, obs_scale_type_OBS_SCALE_POINT, obs_scene_add,
+    obs_scene_addref, obs_scene_create, obs_scene_create_private, obs_scene_find_source,
+    obs_scene_from_source, obs_scene_get_source, obs_scene_release, obs_scene_t,
+    obs_sceneitem_addref, obs_sceneitem_crop, obs_sceneitem_get_crop, obs_sceneitem_get_id,
+    obs_sceneitem_get_info, obs_sceneitem_get_scale_filter, obs_sceneitem_get_scene,
+    obs_sceneitem_get_source, obs_sceneitem_locked, obs_sceneitem_release, obs_sceneitem_remove,
+    obs_sceneitem_set_crop, obs_sceneitem_set_info, obs_sceneitem_set_locked,
+    obs_sceneitem_set_order, obs_sceneitem_set_order_position, obs_sceneitem_set_scale_filter,
+    obs_sceneitem_set_visible, obs_sceneitem_t, obs_sceneitem_visible, obs_source_get_ref,
+    obs_transform_info, OBS_ALIGN_BOTTOM, OBS_ALIGN_LEFT, OBS_ALIGN_RIGHT, OBS_ALIGN_TOP,
+};
+
+use std::{convert::TryFrom, os::raw::c_int};
+
+// Added in OBS 27, after the version the generated bindings cover. The
+// constants mirror the names bindgen would generate for `enum obs_blending_type`.
+#[allow(non_camel_case_types)]
+type obs_blending_type = u32;
+const obs_blending_type_OBS_BLEND_NORMAL: obs_blending_type = 0;
+const obs_blending_type_OBS_BLEND_ADDITIVE: obs_blending_type = 1;
+const obs_blending_type_OBS_BLEND_SUBTRACT: obs_blending_type = 2;
+const obs_blending_type_OBS_BLEND_SCREEN: obs_blending_type = 3;
+const obs_blending_type_OBS_BLEND_MULTIPLY: obs_blending_type = 4;
+const obs_blending_type_OBS_BLEND_LIGHTEN: obs_blending_type = 5;
+const obs_blending_type_OBS_BLEND_DARKEN: obs_blending_type = 6;
+
+pub(crate) static obs_sceneitem_set_blending_mode: Symbol<
+    unsafe extern "C" fn(*mut obs_sceneitem_t, obs_blending_type),
+> = Symbol::new(&LIBOBS, b"obs_sceneitem_set_blending_mode\0");
+pub(crate) static obs_sceneitem_get_blending_mode: Symbol<
+    unsafe extern "C" fn(*mut obs_sceneitem_t) -> obs_blending_type,
+> = Symbol::new(&LIBOBS, b"obs_sceneitem_get_blending_mode\0");
+
+native_enum!(OrderMovement, obs_order_movement {
+    MoveUp => OBS_ORDER_MOVE_UP,
+    MoveDown => OBS_ORDER_MOVE_DOWN,
+    MoveTop => OBS_ORDER_MOVE_TOP,
+    MoveBottom => OBS_ORDER_MOVE_BOTTOM
+});
+
+native_enum!(BoundsType, obs_bounds_type {
+    None => OBS_BOUNDS_NONE,
+    Stretch => OBS_BOUNDS_STRETCH,
+    ScaleInner => OBS_BOUNDS_SCALE_INNER,
+    ScaleOuter => OBS_BOUNDS_SCALE_OUTER,
+    ScaleToWidth => OBS_BOUNDS_SCALE_TO_WIDTH,
+    ScaleToHeight => OBS_BOUNDS_SCALE_TO_HEIGHT,
+    MaxOnly => OBS_BOUNDS_MAX_ONLY
+});
+
+bitflags! {
+    /// The edges a position is anchored to, centered on an axis when neither
+    /// of its edges is set.
+    pub struct Alignment: u32 {
+        const LEFT = OBS_ALIGN_LEFT;
+        const RIGHT = OBS_ALIGN_RIGHT;
+        const TOP = OBS_ALIGN_TOP;
+        const BOTTOM = OBS_ALIGN_BOTTOM;
+    }
+}
+
+impl Alignment {
+    pub const CENTER: Alignment = Alignment::empty();
+}
+
+impl Default for Alignment {
+    fn default() -> Self {
+        Self::CENTER
+    }
+}
+
+native_enum!(ScaleFilter, obs_scale_type {
+    Disable => OBS_SCALE_DISABLE,
+    Point => OBS_SCALE_POINT,
+    Bicubic => OBS_SCALE_BICUBIC,
+    Bilinear => OBS_SCALE_BILINEAR,
+    Lanczos => OBS_SCALE_LANCZOS,
+    Area => OBS_SCALE_AREA
+});
+
+native_enum!(BlendingMode, obs_blending_type {
+    Normal => OBS_BLEND_NORMAL,
+    Additive => OBS_BLEND_ADDITIVE,
+    Subtract => OBS_BLEND_SUBTRACT,
+    Screen => OBS_BLEND_SCREEN,
+    Multiply => OBS_BLEND_MULTIPLY,
+    Lighten => OBS_BLEND_LIGHTEN,
+    Darken => OBS_BLEND_DARKEN
+});
+
+/// Pixels cut from each edge of a scene item's source.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub struct Crop {
+    pub left: i32,
+    pub top: i32,
+    pub right: i32,
+    pub bottom: i32,
+}
+
+impl From<obs_sceneitem_crop> for Crop {
+    fn from(crop: obs_sceneitem_crop) -> Self {
+        Self {
+            left: crop.left,
+            top: crop.top,
+            right: crop.right,
+            bottom: crop.bottom,
+        }
+    }
+}
+
+impl From<Crop> for obs_sceneitem_crop {
+    fn from(crop: Crop) -> Self {
+        Self {
+            left: crop.left,
+            top: crop.top,
+            right: crop.right,
+            bottom: crop.bottom,
+        }
+    }
+}
+
+/// The complete transform of a scene item, which can be captured and restored
+/// in one step.
+///
+/// See [OBS documentation](https://obsproject.com/docs/reference-scenes.html#c.obs_transform_info)
+#[derive(Clone)]
+pub struct TransformInfo {
+    pub pos: Vec2,
+    /// Rotation in degrees.
+    pub rot: f32,
+    pub scale: Vec2,
+    pub alignment: Alignment,
+    pub bounds_type: BoundsType,
+    pub bounds_alignment: Alignment,
+    pub bounds: Vec2,
+}
+
+impl From<obs_transform_info> for TransformInfo {
+    fn from(info: obs_transform_info) -> Self {
+        Self {
+            pos: info.pos.into(),
+            rot: info.rot,
+            scale: info.scale.into(),
+            alignment: Alignment::from_bits_truncate(info.alignment),
+            bounds_type: BoundsType::try_from(info.bounds_type).unwrap_or(BoundsType::None),
+            bounds_alignment: Alignment::from_bits_truncate(info.bounds_alignment),
+            bounds: info.bounds.into(),
+        }
+    }
+}
+
+impl From<TransformInfo> for obs_transform_info {
+    fn from(info: TransformInfo) -> Self {
+        Self {
+            pos: info.pos.into(),
+            rot: info.rot,
+            scale: info.scale.into(),
+            alignment: info.alignment.bits(),
+            bounds_type: info.bounds_type.into(),
+            bounds_alignment: info.bounds_alignment.bits(),
+            bounds: info.bounds.into(),
+        }
+    }
+}
+
+/// An owned reference to an OBS scene, released when dropped.
+///
+/// See [OBS documentation](https://obsproject.com/docs/reference-scenes.html)
+pub struct SceneRef {
+    scene: *mut obs_scene_t,
+}
+
+impl SceneRef {
+    /// Takes ownership of a reference to `scene`, returning `None` if it is
+    /// null.
+    ///
+    /// # Safety
+    /// `scene` must be null or a scene pointer whose reference is owned by the
+    /// caller.
+    pub unsafe fn from_raw(scene: *mut obs_scene_t) -> Option<Self> {
+        if scene.is_null() {
+            None
+        } else {
+            Some(Self { scene })
+        }
+    }
+
+    /// Creates a scene, which is listed in the frontend.
+    pub fn create(name: impl Into<ObsString>) -> Option<Self> {
+        let name = name.into();
+        unsafe { Self::from_raw(obs_scene_create(name.as_ptr())) }
+    }
+
+    /// Creates a private scene, which is neither listed in the frontend nor
+    /// saved.
+    pub fn create_private(name: impl Into<ObsString>) -> Option<Self> {
+        let name = name.into();
+        unsafe { Self::from_raw(obs_scene_create_private(name.as_ptr())) }
+    }
+
+    /// Returns the scene of `source`, or `None` if it is not a scene.
+    pub fn from_source(source: &SourceContext) -> Option<Self> {
+        unsafe {
+            let scene = obs_scene_from_source(source.as_ptr());
+            if scene.is_null() {
+                None
+            } else {
+                obs_scene_addref(scene);
+                Some(Self { scene })
+            }
+        }
+    }
+
+    /// Looks up a scene by the name of its source.
+    pub(crate) fn by_name(name: &str) -> Option<Self> {
+        let source = SourceRef::by_name(name)?;
+        Self::from_source(&source)
+    }
+
+    /// Returns the source of the scene, which is what gets added to other
+    /// scenes or set as the output source.
+    pub fn source(&self) -> Option<SourceRef> {
+        unsafe { SourceRef::from_raw(obs_source_get_ref(obs_scene_get_source(self.scene))) }
+    }
+
+    /// Adds `source` to the top of the scene, returning the new item.
+    ///
+    /// See [OBS documentation](https://obsproject.com/docs/reference-scenes.html#c.obs_scene_add)
+    pub fn add(&mut self, source: &SourceContext) -> Option<SceneItem> {
+        unsafe { SceneItem::from_borrowed(obs_scene_add(self.scene, source.as_ptr())) }
+    }
+
+    /// Finds the item of the source called `name`.
+    pub fn find_source(&self, name: impl Into<ObsString>) -> Option<SceneItem> {
+        let name = name.into();
+        unsafe { SceneItem::from_borrowed(obs_scene_find_source(self.scene, name.as_ptr())) }
+    }
+
+    pub fn as_ptr(&self) -> *mut obs_scene_t {
+        self.scene
+    }
+}
+
+impl Clone for SceneRef {
+    fn clone(&self) -> Self {
+        unsafe { obs_scene_addref(self.scene) }
+        Self { scene: self.scene }
+    }
+}
+
+impl Drop for SceneRef {
+    fn drop(&mut self) {
+        unsafe { obs_scene_release(self.scene) }
+    }
+}
+
+/// An owned reference to a scene item, the placement of a source in a scene.
+///
+/// See [OBS documentation](https://obsproject.com/docs/reference-scenes.html#c.obs_sceneitem_t)
+pub struct SceneItem {
+    item: *mut obs_sceneitem_t,
+}
+
+impl SceneItem {
+    /// Takes a new reference to `item`, returning `None` if it is null.
+    unsafe fn from_borrowed(item: *mut obs_sceneitem_t) -> Option<Self> {
+        if item.is_null() {
+            None
+        } else {
+            obs_sceneitem_addref(item);
+            Some(Self { item })
+        }
+    }
+
+    /// The unique id of the item within its scene.
+    pub fn id(&self) -> i64 {
+        unsafe { obs_sceneitem_get_id(self.item) }
+    }
+
+    pub fn scene(&self) -> Option<SceneRef> {
+        unsafe {
+            let scene = obs_sceneitem_get_scene(self.item);
+            if scene.is_null() {
+                None
+            } else {
+                obs_scene_addref(scene);
+                Some(SceneRef { scene })
+            }
+        }
+    }
+
+    pub fn source(&self) -> Option<SourceRef> {
+        unsafe { SourceRef::from_raw(obs_source_get_ref(obs_sceneitem_get_source(self.item))) }
+    }
+
+    /// Moves the item relative to the other items of the scene.
+    pub fn set_order(&mut self, movement: OrderMovement) {
+        unsafe { obs_sceneitem_set_order(self.item, movement.into()) }
+    }
+
+    /// Moves the item to `position`, `0` being the bottom of the scene.
+    pub fn set_order_position(&mut self, position: i32) {
+        unsafe { obs_sceneitem_set_order_position(self.item, position as c_int) }
+    }
+
+    pub fn visible(&self) -> bool {
+        unsafe { obs_sceneitem_visible(self.item) }
+    }
+
+    /// Shows or hides the item, returning `false` if it was already in that
+    /// state.
+    pub fn set_visible(&mut self, visible: bool) -> bool {
+        unsafe { obs_sceneitem_set_visible(self.item, visible) }
+    }
+
+    pub fn locked(&self) -> bool {
+        unsafe { obs_sceneitem_locked(self.item) }
+    }
+
+    /// Locks the item, preventing it from being moved in the preview, or
+    /// unlocks it. Returns `false` if it was already in that state.
+    pub fn set_locked(&mut self, locked: bool) -> bool {
+        unsafe { obs_sceneitem_set_locked(self.item, locked) }
+    }
+
+    /// Sets how the item is blended with the items below it.
+    ///
+    /// Does nothing before OBS 27, see
+    /// [`supports_blending_modes`](crate::version::supports_blending_modes).
+    pub fn set_blending_mode(&mut self, mode: BlendingMode) {
+        if let Some(set_blending_mode) = obs_sceneitem_set_blending_mode.get() {
+            unsafe { set_blending_mode(self.item, mode.into()) }
+        }
+    }
+
+    /// Returns `None` before OBS 27.
+    pub fn blending_mode(&self) -> Option<BlendingMode> {
+        let get_blending_mode = obs_sceneitem_get_blending_mode.get()?;
+        BlendingMode::try_from(unsafe { get_blending_mode(self.item) }).ok()
+    }
+
+    pub fn info(&self) -> TransformInfo {
+        let mut info = std::mem::MaybeUninit::<obs_transform_info>::uninit();
+        unsafe {
+            obs_sceneitem_get_info(self.item, info.as_mut_ptr());
+            info.assume_init().into()
+        }
+    }
+
+    /// Sets the whole transform of the item at once.
+    pub fn set_info(&mut self, info: TransformInfo) {
+        let info = info.into();
+        unsafe { obs_sceneitem_set_info(self.item, &info) }
+    }
+
+    pub fn set_crop(&mut self, crop: Crop) {
+        let crop = crop.into();
+        unsafe { obs_sceneitem_set_crop(self.item, &crop) }
+    }
+
+    pub fn crop(&self) -> Crop {
+        let mut crop = obs_sceneitem_crop::default();
+        unsafe { obs_sceneitem_get_crop(self.item, &mut crop) };
+        crop.into()
+    }
+
+    /// Sets the filter used when the item is drawn at a size other than the
+    /// size of its source, `Disable` using the default of the video settings.
+    pub fn set_scale_filter(&mut self, filter: ScaleFilter) {
+        unsafe { obs_sceneitem_set_scale_filter(self.item, filter.into()) }
+    }
+
+    pub fn scale_filter(&self) -> Option<ScaleFilter> {
+        ScaleFilter::try_from(unsafe { obs_sceneitem_get_scale_filter(self.item) }).ok()
+    }
+
+    /// Removes the item from its scene. The item stays valid until dropped,
+    /// but no longer belongs to any scene.
+    pub fn remove(&mut self) {
+        unsafe { obs_sceneitem_remove(self.item) }
+    }
+
+    pub fn as_ptr(&self) -> *mut obs_sceneitem_t {
+        self.item
+    }
+}
+
+impl Clone for SceneItem {
+    fn clone(&self) -> Self {
+        unsafe { obs_sceneitem_addref(self.item) }
+        Self { item: self.item }
+    }
+}
+
+impl Drop for SceneItem {
+    fn drop(&mut self) {
+        unsafe { obs_sceneitem_release(self.item) }
+    }
+}
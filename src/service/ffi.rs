@@ -0,0 +1,223 @@
+use super::context::CreatableServiceContext;
+use super::traits::*;
+use super::ServiceContext;
+use crate::data::DataObj;
+use crate::source::context::GlobalContext;
+use crate::source::properties::Properties;
+use crate::string::ObsString;
+use crate::unwind::handle_unwind;
+use crate::wrapper::PtrWrapper;
+use std::ffi::c_void;
+use std::mem::forget;
+use std::os::raw::c_char;
+
+use obs_sys::{obs_data_t, obs_properties, obs_service_t};
+
+struct DataWrapper<D> {
+    data: Option<D>,
+    /// The strings OBS was last given, which it reads without copying.
+    url: Option<ObsString>,
+    key: Option<ObsString>,
+    username: Option<ObsString>,
+    password: Option<ObsString>,
+    output_type: Option<ObsString>,
+    video_codecs: Vec<ObsString>,
+    /// Nul-terminated array of pointers into `video_codecs`.
+    video_codec_ptrs: Vec<*const c_char>,
+}
+
+impl<D> DataWrapper<D> {
+    fn new(data: Option<D>) -> Self {
+        Self {
+            data,
+            url: None,
+            key: None,
+            username: None,
+            password: None,
+            output_type: None,
+            video_codecs: Vec::new(),
+            video_codec_ptrs: Vec::new(),
+        }
+    }
+}
+
+/// Keeps `string` in `slot` and returns a pointer to it.
+fn keep_string(string: Option<Option<ObsString>>, slot: &mut Option<ObsString>) -> *const c_char {
+    *slot = string.flatten();
+    ObsString::ptr_or_null(slot)
+}
+
+pub unsafe extern "C" fn get_name<D, F: GetNameService<D>>(
+    _type_data: *mut c_void,
+) -> *const c_char {
+    handle_unwind::<F, _>("get_name", || F::get_name().as_cached_ptr()).unwrap_or(std::ptr::null())
+}
+
+pub unsafe extern "C" fn create_default_data<D>(
+    _settings: *mut obs_data_t,
+    _service: *mut obs_service_t,
+) -> *mut c_void {
+    let data = Box::new(DataWrapper::<D>::new(None));
+    Box::into_raw(data) as *mut c_void
+}
+
+pub unsafe extern "C" fn create<D, F: CreatableService<D>>(
+    settings: *mut obs_data_t,
+    service: *mut obs_service_t,
+) -> *mut c_void {
+    let mut global = GlobalContext::new();
+    let settings = DataObj::from_raw(settings);
+    let mut create = CreatableServiceContext::from_raw(settings, &mut global);
+
+    let data = handle_unwind::<F, _>("create", || {
+        F::create(&mut create, ServiceContext { service })
+    });
+    forget(create.settings);
+
+    match data {
+        Some(data) => Box::into_raw(Box::new(DataWrapper::new(Some(data)))) as *mut c_void,
+        None => std::ptr::null_mut(),
+    }
+}
+
+pub unsafe extern "C" fn destroy<D>(data: *mut c_void) {
+    let wrapper: Box<DataWrapper<D>> = Box::from_raw(data as *mut DataWrapper<D>);
+    handle_unwind::<D, _>("drop", || drop(wrapper));
+}
+
+pub unsafe extern "C" fn update<D, F: UpdateService<D>>(
+    data: *mut c_void,
+    settings: *mut obs_data_t,
+) {
+    let wrapper: &mut DataWrapper<D> = &mut *(data as *mut DataWrapper<D>);
+    let mut settings = DataObj::from_raw(settings);
+    handle_unwind::<F, _>("update", || F::update(&mut wrapper.data, &mut settings));
+    forget(settings);
+}
+
+pub unsafe extern "C" fn get_properties<D, F: GetPropertiesService<D>>(
+    data: *mut c_void,
+) -> *mut obs_properties {
+    // OBS passes no data when asking for the properties of the type.
+    let mut none = None;
+    let data = match (data as *mut DataWrapper<D>).as_mut() {
+        Some(wrapper) => &mut wrapper.data,
+        None => &mut none,
+    };
+
+    handle_unwind::<F, _>("get_properties", || {
+        let mut properties = Properties::new();
+        F::get_properties(data, &mut properties);
+        properties.into_raw()
+    })
+    .unwrap_or(std::ptr::null_mut())
+}
+
+pub unsafe extern "C" fn get_defaults<D, F: GetDefaultsService<D>>(settings: *mut obs_data_t) {
+    let mut settings = DataObj::from_raw(settings);
+    handle_unwind::<F, _>("get_defaults", || F::get_defaults(&mut settings));
+    forget(settings);
+}
+
+pub unsafe extern "C" fn get_url<D, F: GetUrlService<D>>(data: *mut c_void) -> *const c_char {
+    let wrapper: &mut DataWrapper<D> = &mut *(data as *mut DataWrapper<D>);
+    let url = handle_unwind::<F, _>("get_url", || F::get_url(&mut wrapper.data));
+    keep_string(url, &mut wrapper.url)
+}
+
+pub unsafe extern "C" fn get_key<D, F: GetKeyService<D>>(data: *mut c_void) -> *const c_char {
+    let wrapper: &mut DataWrapper<D> = &mut *(data as *mut DataWrapper<D>);
+    let key = handle_unwind::<F, _>("get_key", || F::get_key(&mut wrapper.data));
+    keep_string(key, &mut wrapper.key)
+}
+
+pub unsafe extern "C" fn get_username<D, F: GetUsernameService<D>>(
+    data: *mut c_void,
+) -> *const c_char {
+    let wrapper: &mut DataWrapper<D> = &mut *(data as *mut DataWrapper<D>);
+    let username = handle_unwind::<F, _>("get_username", || F::get_username(&mut wrapper.data));
+    keep_string(username, &mut wrapper.username)
+}
+
+pub unsafe extern "C" fn get_password<D, F: GetPasswordService<D>>(
+    data: *mut c_void,
+) -> *const c_char {
+    let wrapper: &mut DataWrapper<D> = &mut *(data as *mut DataWrapper<D>);
+    let password = handle_unwind::<F, _>("get_password", || F::get_password(&mut wrapper.data));
+    keep_string(password, &mut wrapper.password)
+}
+
+pub unsafe extern "C" fn apply_encoder_settings<D, F: ApplyEncoderSettingsService<D>>(
+    data: *mut c_void,
+    video_settings: *mut obs_data_t,
+    audio_settings: *mut obs_data_t,
+) {
+    let wrapper: &mut DataWrapper<D> = &mut *(data as *mut DataWrapper<D>);
+    let mut video = if video_settings.is_null() {
+        None
+    } else {
+        Some(DataObj::from_raw(video_settings))
+    };
+    let mut audio = if audio_settings.is_null() {
+        None
+    } else {
+        Some(DataObj::from_raw(audio_settings))
+    };
+    handle_unwind::<F, _>("apply_encoder_settings", || {
+        F::apply_encoder_settings(&mut wrapper.data, video.as_mut(), audio.as_mut())
+    });
+    forget(video);
+    forget(audio);
+}
+
+pub unsafe extern "C" fn get_output_type<D, F: GetOutputTypeService<D>>(
+    data: *mut c_void,
+) -> *const c_char {
+    let wrapper: &mut DataWrapper<D> = &mut *(data as *mut DataWrapper<D>);
+    let output_type =
+        handle_unwind::<F, _>("get_output_type", || F::get_output_type(&mut wrapper.data));
+    keep_string(output_type, &mut wrapper.output_type)
+}
+
+pub unsafe extern "C" fn get_protocol<D, F: GetProtocolService<D>>(
+    data: *mut c_void,
+) -> *const c_char {
+    let wrapper: &mut DataWrapper<D> = &mut *(data as *mut DataWrapper<D>);
+    handle_unwind::<F, _>("get_protocol", || {
+        F::get_protocol(&mut wrapper.data).as_cached_ptr()
+    })
+    .unwrap_or(std::ptr::null())
+}
+
+pub unsafe extern "C" fn can_try_to_connect<D, F: CanTryToConnectService<D>>(
+    data: *mut c_void,
+) -> bool {
+    let wrapper: &mut DataWrapper<D> = &mut *(data as *mut DataWrapper<D>);
+    handle_unwind::<F, _>("can_try_to_connect", || {
+        F::can_try_to_connect(&mut wrapper.data)
+    })
+    .unwrap_or(false)
+}
+
+pub unsafe extern "C" fn get_supported_video_codecs<D, F: GetSupportedVideoCodecsService<D>>(
+    data: *mut c_void,
+) -> *mut *const c_char {
+    let wrapper: &mut DataWrapper<D> = &mut *(data as *mut DataWrapper<D>);
+    let codecs = handle_unwind::<F, _>("get_supported_video_codecs", || {
+        F::get_supported_video_codecs(&mut wrapper.data)
+    });
+
+    match codecs.flatten() {
+        Some(codecs) => {
+            wrapper.video_codecs = codecs;
+            wrapper.video_codec_ptrs = wrapper
+                .video_codecs
+                .iter()
+                .map(ObsString::as_ptr)
+                .chain(std::iter::once(std::ptr::null()))
+                .collect();
+            wrapper.video_codec_ptrs.as_mut_ptr()
+        }
+        None => std::ptr::null_mut(),
+    }
+}
@@ -0,0 +1,204 @@
+//! Services describe where streams go, such as the server and key of a
+//! platform and the protocol and codecs it takes. Outputs with
+//! [`OutputInfoFlags::SERVICE`](crate::output::OutputInfoFlags::SERVICE) read
+//! them to connect, so a platform with its own ingest can be integrated
+//! without writing an output:
+//!
+//! ```ignore
+//! let service = load_context
+//!     .create_service_builder::<WhipService, Data>()
+//!     .enable_get_name()
+//!     .enable_create()
+//!     .enable_get_url()
+//!     .enable_get_protocol()
+//!     .enable_can_try_to_connect()
+//!     .enable_get_supported_video_codecs()
+//!     .build();
+//! load_context.register_service(service).is_ok()
+//! ```
+//!
+//! The protocol, codec and connection callbacks are newer than the generated
+//! bindings and only used by OBS 29.1 and newer, which also requires
+//! [`GetProtocolService`], see
+//! [`version::supports_service_protocols`](crate::version::supports_service_protocols).
+
+use paste::item;
+
+pub mod context;
+mod ffi;
+pub mod traits;
+
+pub use context::*;
+pub use traits::*;
+
+use obs_sys::{obs_service_get_id, obs_service_get_name, obs_service_info, obs_service_t, size_t};
+
+use std::{
+    ffi::CStr,
+    marker::PhantomData,
+    os::raw::{c_char, c_int, c_void},
+};
+
+/// Context wrapping an OBS service.
+///
+/// See [OBS documentation](https://obsproject.com/docs/reference-services.html#c.obs_service_t)
+pub struct ServiceContext {
+    service: *mut obs_service_t,
+}
+
+impl ServiceContext {
+    pub fn service_id(&self) -> Option<&str> {
+        unsafe { ptr_to_str(obs_service_get_id(self.service)) }
+    }
+
+    pub fn name(&self) -> Option<&str> {
+        unsafe { ptr_to_str(obs_service_get_name(self.service)) }
+    }
+
+    pub fn as_ptr(&self) -> *mut obs_service_t {
+        self.service
+    }
+}
+
+unsafe fn ptr_to_str<'a>(ptr: *const c_char) -> Option<&'a str> {
+    if ptr.is_null() {
+        None
+    } else {
+        CStr::from_ptr(ptr).to_str().ok()
+    }
+}
+
+/// `obs_service_info` as of OBS 29.1. The bindings end at `get_output_type`,
+/// and the callbacks after it are only read by versions that know them.
+#[repr(C)]
+#[allow(dead_code)]
+pub(crate) struct RawServiceInfo {
+    pub(crate) base: obs_service_info,
+    get_supported_resolutions:
+        Option<unsafe extern "C" fn(*mut c_void, *mut *mut c_void, *mut size_t)>,
+    get_max_fps: Option<unsafe extern "C" fn(*mut c_void, *mut c_int)>,
+    get_max_bitrate: Option<unsafe extern "C" fn(*mut c_void, *mut c_int, *mut c_int)>,
+    get_supported_video_codecs: Option<unsafe extern "C" fn(*mut c_void) -> *mut *const c_char>,
+    get_protocol: Option<unsafe extern "C" fn(*mut c_void) -> *const c_char>,
+    get_supported_audio_codecs: Option<unsafe extern "C" fn(*mut c_void) -> *mut *const c_char>,
+    get_connect_info: Option<unsafe extern "C" fn(*mut c_void, u32) -> *const c_char>,
+    can_try_to_connect: Option<unsafe extern "C" fn(*mut c_void) -> bool>,
+}
+
+impl RawServiceInfo {
+    /// The size to register the type with. OBS refuses types larger than its
+    /// own `obs_service_info`, so older versions only see the part the
+    /// bindings know.
+    pub(crate) fn size() -> usize {
+        if crate::version::supports_service_protocols() {
+            std::mem::size_of::<RawServiceInfo>()
+        } else {
+            std::mem::size_of::<obs_service_info>()
+        }
+    }
+}
+
+pub struct ServiceInfo {
+    info: Box<RawServiceInfo>,
+}
+
+impl ServiceInfo {
+    /// # Safety
+    /// Creates a raw pointer from a box and could cause UB is misused.
+    pub(crate) unsafe fn into_raw(self) -> *mut RawServiceInfo {
+        Box::into_raw(self.info)
+    }
+}
+
+/// The ServiceInfoBuilder that handles creating the [ServiceInfo](https://obsproject.com/docs/reference-services.html#c.obs_service_info) object.
+///
+/// Works the same way as [`SourceInfoBuilder`](crate::source::SourceInfoBuilder): each trait that
+/// is implemented for the service needs to be enabled using this builder.
+pub struct ServiceInfoBuilder<T: Serviceable, D> {
+    __service: PhantomData<T>,
+    __data: PhantomData<D>,
+    info: RawServiceInfo,
+}
+
+impl<T: Serviceable, D> ServiceInfoBuilder<T, D> {
+    pub(crate) fn new() -> Self {
+        Self {
+            __service: PhantomData,
+            __data: PhantomData,
+            info: RawServiceInfo {
+                base: obs_service_info {
+                    id: T::get_id().as_cached_ptr(),
+                    create: Some(ffi::create_default_data::<D>),
+                    destroy: Some(ffi::destroy::<D>),
+                    type_data: std::ptr::null_mut(),
+                    ..Default::default()
+                },
+                get_supported_resolutions: None,
+                get_max_fps: None,
+                get_max_bitrate: None,
+                get_supported_video_codecs: None,
+                get_protocol: None,
+                get_supported_audio_codecs: None,
+                get_connect_info: None,
+                can_try_to_connect: None,
+            },
+        }
+    }
+
+    pub fn build(self) -> ServiceInfo {
+        ServiceInfo {
+            info: Box::new(self.info),
+        }
+    }
+}
+
+impl<D, T: Serviceable + GetProtocolService<D>> ServiceInfoBuilder<T, D> {
+    /// Enables [`GetProtocolService`], used by OBS 29.1 and newer.
+    pub fn enable_get_protocol(mut self) -> Self {
+        self.info.get_protocol = Some(ffi::get_protocol::<D, T>);
+        self
+    }
+}
+
+impl<D, T: Serviceable + CanTryToConnectService<D>> ServiceInfoBuilder<T, D> {
+    /// Enables [`CanTryToConnectService`], used by OBS 29.1 and newer.
+    pub fn enable_can_try_to_connect(mut self) -> Self {
+        self.info.can_try_to_connect = Some(ffi::can_try_to_connect::<D, T>);
+        self
+    }
+}
+
+impl<D, T: Serviceable + GetSupportedVideoCodecsService<D>> ServiceInfoBuilder<T, D> {
+    /// Enables [`GetSupportedVideoCodecsService`], used by OBS 29.1 and newer.
+    pub fn enable_get_supported_video_codecs(mut self) -> Self {
+        self.info.get_supported_video_codecs = Some(ffi::get_supported_video_codecs::<D, T>);
+        self
+    }
+}
+
+macro_rules! impl_service_builder {
+    ($($f:ident => $t:ident)*) => ($(
+        item! {
+            impl<D, T: Serviceable + [<$t>]<D>> ServiceInfoBuilder<T, D> {
+                pub fn [<enable_$f>](mut self) -> Self {
+                    self.info.base.[<$f>] = Some(ffi::[<$f>]::<D, T>);
+                    self
+                }
+            }
+        }
+    )*)
+}
+
+impl_service_builder! {
+    get_name => GetNameService
+    create => CreatableService
+    update => UpdateService
+    get_properties => GetPropertiesService
+    get_defaults => GetDefaultsService
+    get_url => GetUrlService
+    get_key => GetKeyService
+    get_username => GetUsernameService
+    get_password => GetPasswordService
+    apply_encoder_settings => ApplyEncoderSettingsService
+    get_output_type => GetOutputTypeService
+}
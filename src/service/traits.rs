@@ -0,0 +1,85 @@
+use super::context::CreatableServiceContext;
+use super::ServiceContext;
+use crate::data::DataObj;
+use crate::source::properties::Properties;
+use crate::string::ObsString;
+
+pub trait Serviceable {
+    fn get_id() -> ObsString;
+}
+
+pub trait GetNameService<D> {
+    fn get_name() -> ObsString;
+}
+
+pub trait CreatableService<D> {
+    fn create(create: &mut CreatableServiceContext, service: ServiceContext) -> D;
+}
+
+pub trait UpdateService<D> {
+    fn update(data: &mut Option<D>, settings: &mut DataObj);
+}
+
+pub trait GetPropertiesService<D> {
+    /// `data` is `None` when OBS asks for the properties of the type rather
+    /// than of a service.
+    fn get_properties(data: &mut Option<D>, properties: &mut Properties);
+}
+
+pub trait GetDefaultsService<D> {
+    fn get_defaults(settings: &mut DataObj);
+}
+
+/// The server outputs connect to.
+pub trait GetUrlService<D> {
+    fn get_url(data: &mut Option<D>) -> Option<ObsString>;
+}
+
+/// The stream key, or `None` if the platform uses none.
+pub trait GetKeyService<D> {
+    fn get_key(data: &mut Option<D>) -> Option<ObsString>;
+}
+
+pub trait GetUsernameService<D> {
+    fn get_username(data: &mut Option<D>) -> Option<ObsString>;
+}
+
+pub trait GetPasswordService<D> {
+    fn get_password(data: &mut Option<D>) -> Option<ObsString>;
+}
+
+/// Maps the limits of the platform onto the settings of the encoders before
+/// a stream starts, e.g. capping the bitrate or the keyframe interval.
+pub trait ApplyEncoderSettingsService<D> {
+    /// Either settings are `None` when the stream has no such encoder.
+    fn apply_encoder_settings(
+        data: &mut Option<D>,
+        video: Option<&mut DataObj>,
+        audio: Option<&mut DataObj>,
+    );
+}
+
+/// The id of the output type streams to the service use, e.g.
+/// `"rtmp_output"`, or `None` to let OBS pick one for the protocol.
+pub trait GetOutputTypeService<D> {
+    fn get_output_type(data: &mut Option<D>) -> Option<ObsString>;
+}
+
+/// The protocol of the service, e.g. `"RTMP"`, `"SRT"` or `"WHIP"`, which OBS
+/// matches against the protocols of outputs. Required by OBS 29.1 and newer.
+pub trait GetProtocolService<D> {
+    fn get_protocol(data: &mut Option<D>) -> ObsString;
+}
+
+/// Called before a stream starts, to refuse it while the service is missing
+/// something it needs, such as a server or a key.
+pub trait CanTryToConnectService<D> {
+    fn can_try_to_connect(data: &mut Option<D>) -> bool;
+}
+
+/// The video codecs the platform accepts, e.g. `"h264"` or `"hevc"`, which
+/// OBS uses to offer only matching encoders.
+pub trait GetSupportedVideoCodecsService<D> {
+    /// Returns `None` if any codec the output supports is accepted.
+    fn get_supported_video_codecs(data: &mut Option<D>) -> Option<Vec<ObsString>>;
+}
@@ -1,38 +1,332 @@
-use obs_sys::{audio_output_get_channels, audio_output_get_sample_rate, audio_t, obs_audio_data};
+use crate::native_enum;
+use obs_sys::{
+    audio_data, audio_output_active, audio_output_get_channels, audio_output_get_sample_rate,
+    audio_t, obs_audio_data, obs_get_audio, obs_monitoring_type,
+    obs_monitoring_type_OBS_MONITORING_TYPE_MONITOR_AND_OUTPUT,
+    obs_monitoring_type_OBS_MONITORING_TYPE_MONITOR_ONLY,
+    obs_monitoring_type_OBS_MONITORING_TYPE_NONE, speaker_layout, speaker_layout_SPEAKERS_2POINT1,
+    speaker_layout_SPEAKERS_4POINT0, speaker_layout_SPEAKERS_4POINT1,
+    speaker_layout_SPEAKERS_5POINT1, speaker_layout_SPEAKERS_7POINT1, speaker_layout_SPEAKERS_MONO,
+    speaker_layout_SPEAKERS_STEREO, speaker_layout_SPEAKERS_UNKNOWN,
+};
 
-pub struct AudioDataContext {
+use std::cell::Cell;
+
+native_enum!(MonitoringType, obs_monitoring_type {
+    None => OBS_MONITORING_TYPE_NONE,
+    MonitorOnly => OBS_MONITORING_TYPE_MONITOR_ONLY,
+    MonitorAndOutput => OBS_MONITORING_TYPE_MONITOR_AND_OUTPUT
+});
+
+native_enum!(SpeakerLayout, speaker_layout {
+    Unknown => SPEAKERS_UNKNOWN,
+    Mono => SPEAKERS_MONO,
+    Stereo => SPEAKERS_STEREO,
+    TwoPointOne => SPEAKERS_2POINT1,
+    FourPointZero => SPEAKERS_4POINT0,
+    FourPointOne => SPEAKERS_4POINT1,
+    FivePointOne => SPEAKERS_5POINT1,
+    SevenPointOne => SPEAKERS_7POINT1
+});
+
+/// -3 dB, the usual gain of a centre or surround channel folded into a front
+/// channel.
+const MINUS_3DB: f32 = std::f32::consts::FRAC_1_SQRT_2;
+
+impl SpeakerLayout {
+    /// The number of channels of the layout, `0` for `Unknown`.
+    pub fn channels(self) -> usize {
+        match self {
+            Self::Unknown => 0,
+            Self::Mono => 1,
+            Self::Stereo => 2,
+            Self::TwoPointOne => 3,
+            Self::FourPointZero => 4,
+            Self::FourPointOne => 5,
+            Self::FivePointOne => 6,
+            Self::SevenPointOne => 8,
+        }
+    }
+
+    /// The layout OBS uses for `channels` channels.
+    pub fn from_channels(channels: usize) -> Self {
+        match channels {
+            1 => Self::Mono,
+            2 => Self::Stereo,
+            3 => Self::TwoPointOne,
+            4 => Self::FourPointZero,
+            5 => Self::FourPointOne,
+            6 => Self::FivePointOne,
+            8 => Self::SevenPointOne,
+            _ => Self::Unknown,
+        }
+    }
+
+    /// The gains of each channel, in OBS channel order, into the left and
+    /// right channel of a stereo downmix. The LFE channel is dropped.
+    ///
+    /// Every other channel keeps its energy, so `l² + r² = 1`: centred
+    /// channels are spread over both sides at -3 dB and side and rear
+    /// channels folded into their side at full gain.
+    pub fn stereo_coefficients(self) -> &'static [(f32, f32)] {
+        const L: (f32, f32) = (1., 0.);
+        const R: (f32, f32) = (0., 1.);
+        const C: (f32, f32) = (MINUS_3DB, MINUS_3DB);
+        const LFE: (f32, f32) = (0., 0.);
+        const SL: (f32, f32) = (1., 0.);
+        const SR: (f32, f32) = (0., 1.);
+        match self {
+            Self::Unknown => &[],
+            Self::Mono => &[C],
+            Self::Stereo => &[L, R],
+            Self::TwoPointOne => &[L, R, LFE],
+            Self::FourPointZero => &[L, R, C, C],
+            Self::FourPointOne => &[L, R, C, LFE, C],
+            Self::FivePointOne => &[L, R, C, LFE, SL, SR],
+            Self::SevenPointOne => &[L, R, C, LFE, SL, SR, SL, SR],
+        }
+    }
+}
+
+/// Downmixes planar audio in `layout` into `left` and `right`, using
+/// [`SpeakerLayout::stereo_coefficients`]. The result is not normalised, so
+/// loud multichannel input may exceed `[-1, 1]`.
+///
+/// Only as many frames as the shortest buffer are written.
+pub fn downmix_to_stereo(
+    layout: SpeakerLayout,
+    input: &[&[f32]],
+    left: &mut [f32],
+    right: &mut [f32],
+) {
+    let frames = input
+        .iter()
+        .map(|channel| channel.len())
+        .chain([left.len(), right.len()].iter().copied())
+        .min()
+        .unwrap_or(0);
+    left[..frames].iter_mut().for_each(|s| *s = 0.);
+    right[..frames].iter_mut().for_each(|s| *s = 0.);
+    for (channel, &(l, r)) in input.iter().zip(layout.stereo_coefficients()) {
+        for i in 0..frames {
+            left[i] += channel[i] * l;
+            right[i] += channel[i] * r;
+        }
+    }
+}
+
+/// Downmixes planar audio in `layout` into `output`, summing the channels of
+/// its stereo downmix at -3 dB. This keeps the energy of audio that is the
+/// same on both sides, and undoes [`upmix_mono`].
+///
+/// Only as many frames as the shortest buffer are written.
+pub fn downmix_to_mono(layout: SpeakerLayout, input: &[&[f32]], output: &mut [f32]) {
+    let frames = input
+        .iter()
+        .map(|channel| channel.len())
+        .chain(std::iter::once(output.len()))
+        .min()
+        .unwrap_or(0);
+    output[..frames].iter_mut().for_each(|s| *s = 0.);
+    for (channel, &(l, r)) in input.iter().zip(layout.stereo_coefficients()) {
+        let gain = (l + r) * MINUS_3DB;
+        for i in 0..frames {
+            output[i] += channel[i] * gain;
+        }
+    }
+}
+
+/// Upmixes mono audio into planar `output` channels by copying it into the
+/// front left and right channels at -3 dB, which keeps its energy, or into
+/// the only channel of mono output, and silencing the others.
+pub fn upmix_mono(input: &[f32], output: &mut [&mut [f32]]) {
+    let fronts = output.len().min(2);
+    let gain = if fronts == 2 { MINUS_3DB } else { 1. };
+    for (index, channel) in output.iter_mut().enumerate() {
+        let frames = channel.len().min(input.len());
+        if index < fronts {
+            for (o, i) in channel[..frames].iter_mut().zip(input) {
+                *o = i * gain;
+            }
+        } else {
+            channel[..frames].iter_mut().for_each(|s| *s = 0.);
+        }
+    }
+}
+
+/// What happens to the audio given to
+/// [`FilterAudioSource::filter_audio`](crate::source::traits::FilterAudioSource::filter_audio).
+pub enum FilterAudioResult {
+    /// Passes the audio on, including changes made to it in place.
+    Pass,
+    /// Drops the audio, so nothing is output for it.
+    Drop,
+    /// Outputs other planar audio, with one buffer per channel, instead. The
+    /// number of frames is the length of the shortest buffer.
+    Replace {
+        channels: Vec<Vec<f32>>,
+        timestamp: u64,
+    },
+}
+
+/// A block of planar float audio passing through an audio filter.
+///
+/// The number of frames and the timestamp can be changed, e.g. by filters
+/// compensating for their latency. Shrinking works in place, while growing
+/// moves the audio into a buffer kept by the filter until its next call.
+///
+/// Filters receive audio in the format of the main audio pipeline, given by
+/// [`sample_rate`](Self::sample_rate) and [`channels`](Self::channels), which
+/// changes when the user changes the audio settings of OBS.
+pub struct AudioDataContext<'a> {
     pointer: *mut obs_audio_data,
+    // The audio given by OBS belongs to the source, so changes to the frame
+    // count, timestamp or planes are made to a copy kept by the filter.
+    output: &'a mut Option<obs_audio_data>,
+    scratch: &'a mut Vec<Vec<f32>>,
+    owned: bool,
+    grown: bool,
+    // Looked up when first asked for, so that filters which never need it do
+    // not query the pipeline on every call.
+    format: Cell<Option<(usize, usize)>>,
 }
 
-impl AudioDataContext {
-    pub(crate) unsafe fn from_raw(pointer: *mut obs_audio_data) -> Self {
-        Self { pointer }
+impl<'a> AudioDataContext<'a> {
+    pub(crate) unsafe fn from_raw(
+        pointer: *mut obs_audio_data,
+        output: &'a mut Option<obs_audio_data>,
+        scratch: &'a mut Vec<Vec<f32>>,
+    ) -> Self {
+        Self {
+            pointer,
+            output,
+            scratch,
+            owned: false,
+            grown: false,
+            format: Cell::new(None),
+        }
     }
 
-    pub fn frames(&self) -> usize {
+    /// The sample rate and channel count of the audio.
+    fn format(&self) -> (usize, usize) {
+        if let Some(format) = self.format.get() {
+            return format;
+        }
+        let format = match AudioRef::main() {
+            Some(audio) => (audio.output_sample_rate(), audio.output_channels()),
+            // Without a pipeline, every plane holding audio is a channel.
+            None => (
+                0,
+                unsafe { self.pointer.as_ref() }.map_or(0, |audio| {
+                    audio
+                        .data
+                        .iter()
+                        .take_while(|plane| !plane.is_null())
+                        .count()
+                }),
+            ),
+        };
+        self.format.set(Some(format));
+        format
+    }
+
+    /// The audio to return to OBS, either the one passed in or the copy
+    /// holding the changes.
+    pub(crate) fn as_ptr(&self) -> *mut obs_audio_data {
+        self.pointer
+    }
+
+    fn audio_mut(&mut self) -> &mut obs_audio_data {
+        if !self.owned {
+            let audio = unsafe { *self.pointer.as_ref().expect("Audio pointer was null!") };
+            self.pointer = self.output.insert(audio);
+            self.owned = true;
+        }
+        unsafe { &mut *self.pointer }
+    }
+
+    /// The sample rate of the audio, in Hz.
+    pub fn sample_rate(&self) -> usize {
+        self.format().0
+    }
+
+    /// The speaker layout of the audio, giving the order of the channels.
+    pub fn speaker_layout(&self) -> SpeakerLayout {
+        SpeakerLayout::from_channels(self.format().1)
+    }
+
+    /// The timestamp of the first frame, in nanoseconds.
+    pub fn timestamp(&self) -> u64 {
         unsafe {
             self.pointer
                 .as_ref()
                 .expect("Audio pointer was null!")
-                .frames as usize
+                .timestamp
         }
     }
 
-    pub fn channels(&self) -> usize {
+    pub fn set_timestamp(&mut self, timestamp: u64) {
+        self.audio_mut().timestamp = timestamp;
+    }
+
+    /// Drops the frames after the first `frames`. Does nothing if there are
+    /// fewer frames than that.
+    pub fn truncate(&mut self, frames: usize) {
+        if frames < self.frames() {
+            self.audio_mut().frames = frames as u32;
+        }
+    }
+
+    /// Changes the number of frames to `frames`, dropping frames from the end
+    /// or padding it with silence.
+    pub fn resize(&mut self, frames: usize) {
+        let current = self.frames();
+        if frames <= current {
+            self.truncate(frames);
+            return;
+        }
+
+        self.audio_mut();
+        let audio = unsafe { &mut *self.pointer };
+        let grown = std::mem::replace(&mut self.grown, true);
+        self.scratch.resize_with(audio.data.len(), Vec::new);
+        for (plane, buffer) in audio.data.iter_mut().zip(self.scratch.iter_mut()) {
+            if plane.is_null() {
+                continue;
+            }
+            if !grown {
+                // Still the planes of OBS, which are only read from.
+                let samples = unsafe { core::slice::from_raw_parts(*plane as *const f32, current) };
+                buffer.clear();
+                buffer.extend_from_slice(samples);
+            } else {
+                buffer.truncate(current);
+            }
+            buffer.resize(frames, 0.);
+            *plane = buffer.as_mut_ptr() as *mut u8;
+        }
+        audio.frames = frames as u32;
+    }
+
+    pub fn frames(&self) -> usize {
         unsafe {
             self.pointer
                 .as_ref()
                 .expect("Audio pointer was null!")
-                .data
-                .len()
+                .frames as usize
         }
     }
 
+    /// The number of channels of the audio.
+    pub fn channels(&self) -> usize {
+        self.format().1
+    }
+
     pub fn get_channel_as_mut_slice(&self, channel: usize) -> Option<&'_ mut [f32]> {
         unsafe {
             let data = self.pointer.as_ref()?.data;
 
-            if channel >= data.len() {
+            if channel >= self.channels().min(data.len()) || data[channel].is_null() {
                 return None;
             }
 
@@ -46,6 +340,62 @@ impl AudioDataContext {
     }
 }
 
+/// Wrapper around [`audio_data`], a block of planar float audio delivered to
+/// a raw audio output.
+pub struct AudioFrames {
+    pointer: *mut audio_data,
+    channels: usize,
+}
+
+impl AudioFrames {
+    pub(crate) unsafe fn from_raw(pointer: *mut audio_data, channels: usize) -> Self {
+        Self { pointer, channels }
+    }
+
+    pub fn frames(&self) -> usize {
+        unsafe {
+            self.pointer
+                .as_ref()
+                .expect("Audio pointer was null!")
+                .frames as usize
+        }
+    }
+
+    pub fn timestamp(&self) -> u64 {
+        unsafe {
+            self.pointer
+                .as_ref()
+                .expect("Audio pointer was null!")
+                .timestamp
+        }
+    }
+
+    pub fn channels(&self) -> usize {
+        self.channels
+    }
+
+    /// Returns the samples of `channel`.
+    pub fn channel(&self, channel: usize) -> Option<&'_ [f32]> {
+        if channel >= self.channels {
+            return None;
+        }
+        unsafe {
+            let raw = self.pointer.as_ref()?;
+            let ptr = raw.data[channel];
+            if ptr.is_null() {
+                return None;
+            }
+            Some(core::slice::from_raw_parts(
+                ptr as *const f32,
+                raw.frames as usize,
+            ))
+        }
+    }
+}
+
+/// Reference to an audio pipeline, either the main one from
+/// [`GlobalContext::audio`](super::context::GlobalContext::audio) or the one an
+/// output is attached to.
 pub struct AudioRef {
     pointer: *mut audio_t,
 }
@@ -55,6 +405,25 @@ impl AudioRef {
         Self { pointer }
     }
 
+    /// The main audio pipeline, or `None` if audio has not been initialised.
+    pub(crate) fn main() -> Option<Self> {
+        let pointer = unsafe { obs_get_audio() };
+        if pointer.is_null() {
+            None
+        } else {
+            Some(Self { pointer })
+        }
+    }
+
+    /// Whether any output is currently receiving audio from the pipeline.
+    pub fn active(&self) -> bool {
+        unsafe { audio_output_active(self.pointer) }
+    }
+
+    pub fn as_ptr(&self) -> *mut audio_t {
+        self.pointer
+    }
+
     pub fn output_sample_rate(&self) -> usize {
         unsafe { audio_output_get_sample_rate(self.pointer) as usize }
     }
@@ -63,3 +432,166 @@ impl AudioRef {
         unsafe { audio_output_get_channels(self.pointer) as usize }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LAYOUTS: [SpeakerLayout; 7] = [
+        SpeakerLayout::Mono,
+        SpeakerLayout::Stereo,
+        SpeakerLayout::TwoPointOne,
+        SpeakerLayout::FourPointZero,
+        SpeakerLayout::FourPointOne,
+        SpeakerLayout::FivePointOne,
+        SpeakerLayout::SevenPointOne,
+    ];
+
+    /// The index of the LFE channel of each layout that has one.
+    fn lfe(layout: SpeakerLayout) -> Option<usize> {
+        match layout {
+            SpeakerLayout::TwoPointOne => Some(2),
+            SpeakerLayout::FourPointOne
+            | SpeakerLayout::FivePointOne
+            | SpeakerLayout::SevenPointOne => Some(3),
+            _ => None,
+        }
+    }
+
+    fn energy(samples: &[f32]) -> f32 {
+        samples.iter().map(|s| s * s).sum()
+    }
+
+    fn signal(frames: usize, seed: f32) -> Vec<f32> {
+        (0..frames)
+            .map(|i| (i as f32 * 0.37 + seed).sin() * 0.5)
+            .collect()
+    }
+
+    fn assert_close(actual: f32, expected: f32) {
+        assert!(
+            (actual - expected).abs() <= 1e-4 * expected.abs().max(1.),
+            "{} is not close to {}",
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn channel_counts() {
+        assert_eq!(SpeakerLayout::Unknown.channels(), 0);
+        assert!(SpeakerLayout::Unknown.stereo_coefficients().is_empty());
+        for &layout in &LAYOUTS {
+            assert_eq!(SpeakerLayout::from_channels(layout.channels()), layout);
+            assert_eq!(layout.stereo_coefficients().len(), layout.channels());
+        }
+        assert_eq!(SpeakerLayout::from_channels(7), SpeakerLayout::Unknown);
+    }
+
+    #[test]
+    fn stereo_coefficients_keep_the_energy_of_each_channel() {
+        for &layout in &LAYOUTS {
+            for (channel, &(l, r)) in layout.stereo_coefficients().iter().enumerate() {
+                let expected = if lfe(layout) == Some(channel) { 0. } else { 1. };
+                assert_close(l * l + r * r, expected);
+            }
+        }
+    }
+
+    #[test]
+    fn mono_to_stereo_keeps_the_energy() {
+        let input = signal(256, 0.);
+        let (mut left, mut right) = (vec![1.; 256], vec![1.; 256]);
+        upmix_mono(&input, &mut [&mut left, &mut right]);
+        assert_eq!(left, right);
+        assert_close(energy(&left) + energy(&right), energy(&input));
+
+        // The stereo downmix of mono audio matches its upmix.
+        let (mut down_left, mut down_right) = (vec![0.; 256], vec![0.; 256]);
+        downmix_to_stereo(
+            SpeakerLayout::Mono,
+            &[&input],
+            &mut down_left,
+            &mut down_right,
+        );
+        assert_eq!(down_left, left);
+        assert_eq!(down_right, right);
+    }
+
+    #[test]
+    fn upmix_into_mono_and_surround() {
+        let input = signal(64, 1.);
+        let mut mono = vec![0.; 64];
+        upmix_mono(&input, &mut [&mut mono]);
+        assert_eq!(mono, input);
+
+        let mut channels = vec![vec![1.; 64]; 6];
+        let mut outputs: Vec<&mut [f32]> = channels.iter_mut().map(|c| &mut c[..]).collect();
+        upmix_mono(&input, &mut outputs);
+        assert_close(energy(&channels[0]) + energy(&channels[1]), energy(&input));
+        for channel in &channels[2..] {
+            assert!(channel.iter().all(|&s| s == 0.));
+        }
+    }
+
+    #[test]
+    fn stereo_to_mono_keeps_the_energy() {
+        let centred = signal(256, 2.);
+        let mut mono = vec![1.; 256];
+        downmix_to_mono(SpeakerLayout::Stereo, &[&centred, &centred], &mut mono);
+        assert_close(energy(&mono), 2. * energy(&centred));
+
+        // Downmixing undoes the upmix.
+        let input = signal(256, 3.);
+        let (mut left, mut right) = (vec![0.; 256], vec![0.; 256]);
+        upmix_mono(&input, &mut [&mut left, &mut right]);
+        downmix_to_mono(SpeakerLayout::Stereo, &[&left, &right], &mut mono);
+        for (&m, &i) in mono.iter().zip(&input) {
+            assert_close(m, i);
+        }
+
+        downmix_to_mono(SpeakerLayout::Mono, &[&input], &mut mono);
+        for (&m, &i) in mono.iter().zip(&input) {
+            assert_close(m, i);
+        }
+    }
+
+    #[test]
+    fn surround_to_stereo_keeps_the_energy_of_each_channel() {
+        let layout = SpeakerLayout::FivePointOne;
+        let silence = vec![0.; 128];
+        let input = signal(128, 4.);
+        for channel in 0..layout.channels() {
+            let mut channels = vec![&silence[..]; layout.channels()];
+            channels[channel] = &input;
+            let (mut left, mut right) = (vec![1.; 128], vec![1.; 128]);
+            downmix_to_stereo(layout, &channels, &mut left, &mut right);
+
+            let expected = if lfe(layout) == Some(channel) {
+                0.
+            } else {
+                energy(&input)
+            };
+            assert_close(energy(&left) + energy(&right), expected);
+        }
+    }
+
+    #[test]
+    fn only_the_shortest_buffer_is_mixed() {
+        let (long, short) = (vec![1.; 8], vec![1.; 4]);
+        let (mut left, mut right) = (vec![9.; 8], vec![9.; 8]);
+        downmix_to_stereo(
+            SpeakerLayout::Stereo,
+            &[&long, &short],
+            &mut left,
+            &mut right,
+        );
+        assert_eq!(left, [1., 1., 1., 1., 9., 9., 9., 9.]);
+        assert_eq!(right, [1., 1., 1., 1., 9., 9., 9., 9.]);
+
+        let mut mono = vec![9.; 8];
+        downmix_to_mono(SpeakerLayout::Stereo, &[&long, &short], &mut mono);
+        assert!(mono[..4].iter().all(|&s| (s - 2. * MINUS_3DB).abs() < 1e-6));
+        assert_eq!(mono[4..], [9.; 4]);
+    }
+}
@@ -0,0 +1,233 @@
+//! Source types written inline with closures, for prototypes and small
+//! plugins that do not need a type implementing every `*Source` trait:
+//!
+//! ```ignore
+//! let source = ClosureSourceBuilder::new(
+//!     "solid_color",
+//!     "Solid Color",
+//!     SourceType::INPUT,
+//!     |settings, _source| settings.get::<i64, _>("color").unwrap_or(0) as u32,
+//! )
+//! .on_update(|color, settings| *color = settings.get::<i64, _>("color").unwrap_or(0) as u32)
+//! .on_get_size(|_| (1920, 1080))
+//! .on_video_render(|color, _render| draw_color(*color))
+//! .build();
+//! load_context.register_source(source).is_ok()
+//! ```
+//!
+//! The types are registered through [`SourceInfo::from_dyn`].
+
+use super::context::{GlobalContext, VideoRenderContext};
+use super::dynamic::{DynSource, DynSourceType};
+use super::properties::Properties;
+use super::{OutputFlags, SourceContext, SourceInfo, SourceType};
+use crate::data::DataObj;
+use crate::string::ObsString;
+
+use std::sync::Arc;
+
+type CreateFn<D> = dyn Fn(&mut DataObj, SourceContext) -> D + Send + Sync;
+type UpdateFn<D> = dyn Fn(&mut D, &mut DataObj) + Send + Sync;
+type VideoRenderFn<D> = dyn Fn(&mut D, &mut VideoRenderContext) + Send + Sync;
+type VideoTickFn<D> = dyn Fn(&mut D, f32) + Send + Sync;
+type GetSizeFn<D> = dyn Fn(&D) -> (u32, u32) + Send + Sync;
+type GetPropertiesFn<D> = dyn Fn(&mut D, &mut Properties) + Send + Sync;
+type GetDefaultsFn = dyn Fn(&mut DataObj) + Send + Sync;
+
+struct Callbacks<D> {
+    create: Box<CreateFn<D>>,
+    update: Option<Box<UpdateFn<D>>>,
+    video_render: Option<Box<VideoRenderFn<D>>>,
+    video_tick: Option<Box<VideoTickFn<D>>>,
+    get_size: Option<Box<GetSizeFn<D>>>,
+    get_properties: Option<Box<GetPropertiesFn<D>>>,
+    get_defaults: Option<Box<GetDefaultsFn>>,
+}
+
+/// Builds a source type from closures, each receiving the data returned by
+/// the create closure.
+///
+/// Closures are shared by every source of the type and may be called from
+/// several threads, so they cannot capture mutable state. Per-source state
+/// belongs in the data.
+pub struct ClosureSourceBuilder<D> {
+    id: ObsString,
+    name: ObsString,
+    source_type: SourceType,
+    flags: OutputFlags,
+    callbacks: Callbacks<D>,
+}
+
+impl<D: Send + 'static> ClosureSourceBuilder<D> {
+    pub fn new(
+        id: impl Into<ObsString>,
+        name: impl Into<ObsString>,
+        source_type: SourceType,
+        create: impl Fn(&mut DataObj, SourceContext) -> D + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            id: id.into(),
+            name: name.into(),
+            source_type,
+            flags: OutputFlags::empty(),
+            callbacks: Callbacks {
+                create: Box::new(create),
+                update: None,
+                video_render: None,
+                video_tick: None,
+                get_size: None,
+                get_properties: None,
+                get_defaults: None,
+            },
+        }
+    }
+
+    /// Declares additional [`OutputFlags`] for the source. `VIDEO` is set
+    /// automatically when [`on_video_render`](Self::on_video_render) is used.
+    pub fn with_output_flags(mut self, flags: OutputFlags) -> Self {
+        self.flags |= flags;
+        self
+    }
+
+    pub fn on_update(
+        mut self,
+        func: impl Fn(&mut D, &mut DataObj) + Send + Sync + 'static,
+    ) -> Self {
+        self.callbacks.update = Some(Box::new(func));
+        self
+    }
+
+    pub fn on_video_render(
+        mut self,
+        func: impl Fn(&mut D, &mut VideoRenderContext) + Send + Sync + 'static,
+    ) -> Self {
+        self.callbacks.video_render = Some(Box::new(func));
+        self.flags |= OutputFlags::VIDEO;
+        self
+    }
+
+    pub fn on_video_tick(mut self, func: impl Fn(&mut D, f32) + Send + Sync + 'static) -> Self {
+        self.callbacks.video_tick = Some(Box::new(func));
+        self
+    }
+
+    /// Sets the closure returning the width and height of the source. Without
+    /// it OBS is not asked for a size, so filters keep the size of their
+    /// target.
+    pub fn on_get_size(mut self, func: impl Fn(&D) -> (u32, u32) + Send + Sync + 'static) -> Self {
+        self.callbacks.get_size = Some(Box::new(func));
+        self
+    }
+
+    pub fn on_get_properties(
+        mut self,
+        func: impl Fn(&mut D, &mut Properties) + Send + Sync + 'static,
+    ) -> Self {
+        self.callbacks.get_properties = Some(Box::new(func));
+        self
+    }
+
+    pub fn on_get_defaults(mut self, func: impl Fn(&mut DataObj) + Send + Sync + 'static) -> Self {
+        self.callbacks.get_defaults = Some(Box::new(func));
+        self
+    }
+
+    pub fn build(self) -> SourceInfo {
+        SourceInfo::from_dyn(ClosureSourceType {
+            id: self.id,
+            name: self.name,
+            source_type: self.source_type,
+            flags: self.flags,
+            callbacks: Arc::new(self.callbacks),
+        })
+    }
+}
+
+struct ClosureSourceType<D> {
+    id: ObsString,
+    name: ObsString,
+    source_type: SourceType,
+    flags: OutputFlags,
+    callbacks: Arc<Callbacks<D>>,
+}
+
+impl<D: Send + 'static> DynSourceType for ClosureSourceType<D> {
+    fn id(&self) -> ObsString {
+        self.id.clone()
+    }
+
+    fn name(&self) -> ObsString {
+        self.name.clone()
+    }
+
+    fn source_type(&self) -> SourceType {
+        self.source_type
+    }
+
+    fn output_flags(&self) -> OutputFlags {
+        self.flags
+    }
+
+    fn has_size(&self) -> bool {
+        self.callbacks.get_size.is_some()
+    }
+
+    fn create(&self, settings: &mut DataObj, source: SourceContext) -> Option<Box<dyn DynSource>> {
+        let data = (self.callbacks.create)(settings, source);
+        Some(Box::new(ClosureSource {
+            data,
+            callbacks: self.callbacks.clone(),
+        }))
+    }
+
+    fn get_defaults(&self, settings: &mut DataObj) {
+        if let Some(get_defaults) = &self.callbacks.get_defaults {
+            get_defaults(settings);
+        }
+    }
+}
+
+struct ClosureSource<D> {
+    data: D,
+    callbacks: Arc<Callbacks<D>>,
+}
+
+impl<D: Send> DynSource for ClosureSource<D> {
+    fn update(&mut self, settings: &mut DataObj, _context: &mut GlobalContext) {
+        if let Some(update) = &self.callbacks.update {
+            update(&mut self.data, settings);
+        }
+    }
+
+    fn get_properties(&mut self, properties: &mut Properties) {
+        if let Some(get_properties) = &self.callbacks.get_properties {
+            get_properties(&mut self.data, properties);
+        }
+    }
+
+    fn get_width(&mut self) -> u32 {
+        self.callbacks
+            .get_size
+            .as_ref()
+            .map_or(0, |get_size| get_size(&self.data).0)
+    }
+
+    fn get_height(&mut self) -> u32 {
+        self.callbacks
+            .get_size
+            .as_ref()
+            .map_or(0, |get_size| get_size(&self.data).1)
+    }
+
+    fn video_tick(&mut self, seconds: f32) {
+        if let Some(video_tick) = &self.callbacks.video_tick {
+            video_tick(&mut self.data, seconds);
+        }
+    }
+
+    fn video_render(&mut self, _context: &mut GlobalContext, render: &mut VideoRenderContext) {
+        if let Some(video_render) = &self.callbacks.video_render {
+            video_render(&mut self.data, render);
+        }
+    }
+}
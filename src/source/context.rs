@@ -1,38 +1,174 @@
 use super::audio::AudioRef;
-use super::hotkey::Hotkey;
+use super::hotkey::{Hotkey, HotkeyCallback, HotkeyPairRegistration};
+use super::{SourceRef, TypeData};
+use crate::graphics::GraphicsTexture;
+use crate::output::OutputRef;
 use crate::prelude::DataObj;
+use crate::scene::SceneRef;
 use crate::string::ObsString;
-use obs_sys::{obs_get_audio, obs_source_t};
+use crate::video::VideoRef;
+use obs_sys::{
+    gs_effect_get_param_by_name, gs_effect_loop, gs_effect_set_texture, gs_get_effect,
+    obs_base_effect, obs_base_effect_OBS_EFFECT_DEFAULT,
+    obs_base_effect_OBS_EFFECT_PREMULTIPLIED_ALPHA, obs_get_audio, obs_get_base_effect,
+    obs_initialized, obs_render_main_texture, obs_source_draw, obs_source_get_type_data,
+    obs_source_t,
+};
 
-pub struct GlobalContext;
-pub struct VideoRenderContext;
+use std::marker::PhantomData;
+
+/// Handle to the global state of OBS, such as the sources and outputs it
+/// knows about.
+///
+/// It is handed to callbacks by OBS, and can be obtained on other threads with
+/// [`GlobalContext::enter`], so code touching global state needs one in hand.
+pub struct GlobalContext {
+    _private: (),
+}
+
+/// Marker handed to callbacks running on the graphics thread, such as
+/// `video_render`. Functions that are only safe to call while rendering take
+/// it as a parameter, so calling them elsewhere fails to compile.
+pub struct VideoRenderContext {
+    _private: PhantomData<*const ()>,
+}
+
+/// Marker handed to callbacks running on the audio thread, such as
+/// `audio_render`. Functions that are only meaningful while mixing audio take
+/// it as a parameter.
+pub struct AudioThreadContext {
+    _private: PhantomData<*const ()>,
+}
 
 impl GlobalContext {
+    /// # Safety
+    /// Must only be called while OBS is initialised, e.g. from a callback.
+    pub(crate) unsafe fn new() -> Self {
+        Self { _private: () }
+    }
+
+    /// Obtains a handle from a thread OBS did not call into, such as a
+    /// background worker. Returns `None` if OBS is not initialised.
+    pub fn enter() -> Option<Self> {
+        if unsafe { obs_initialized() } {
+            Some(unsafe { Self::new() })
+        } else {
+            None
+        }
+    }
+
+    /// Looks up a source by its name.
+    ///
+    /// See [OBS documentation](https://obsproject.com/docs/reference-core.html#c.obs_get_source_by_name)
+    pub fn source_by_name(&self, name: &str) -> Option<SourceRef> {
+        SourceRef::by_name(name)
+    }
+
+    /// Looks up a scene by the name of its source.
+    pub fn scene_by_name(&self, name: &str) -> Option<SceneRef> {
+        SceneRef::by_name(name)
+    }
+
+    /// Looks up an output by its name.
+    ///
+    /// See [OBS documentation](https://obsproject.com/docs/reference-core.html#c.obs_get_output_by_name)
+    pub fn output_by_name(&self, name: &str) -> Option<OutputRef> {
+        OutputRef::by_name(name)
+    }
+
+    /// The main video pipeline, or `None` if video has not been initialised.
+    pub fn video(&self) -> Option<VideoRef> {
+        VideoRef::main()
+    }
+
+    /// The main audio pipeline, or `None` if audio has not been initialised.
+    pub fn audio(&self) -> Option<AudioRef> {
+        AudioRef::main()
+    }
+
     pub fn with_audio<T, F: FnOnce(&AudioRef) -> T>(&self, func: F) -> T {
         let audio = unsafe { AudioRef::from_raw(obs_get_audio()) };
         func(&audio)
     }
 }
 
-impl Default for VideoRenderContext {
-    fn default() -> Self {
-        Self
+impl VideoRenderContext {
+    /// # Safety
+    /// Must only be called on the graphics thread, while rendering.
+    pub(crate) unsafe fn new() -> Self {
+        Self {
+            _private: PhantomData,
+        }
+    }
+
+    /// Draws the main output, e.g. from a
+    /// [`Display`](crate::display::Display) draw callback.
+    pub fn render_main_texture(&mut self) {
+        unsafe { obs_render_main_texture() }
+    }
+
+    /// Draws `texture` at the origin of the source with one of the stock OBS
+    /// effects, which is all an image-like source needs in `video_render`.
+    ///
+    /// `linear_alpha` should be set when the colour channels of the texture
+    /// are independent of its alpha channel. Otherwise the texture is taken
+    /// to have premultiplied alpha and is drawn with the premultiplied alpha
+    /// effect. `flip` flips the texture vertically.
+    ///
+    /// Choosing the effect requires the source to declare
+    /// [`OutputFlags::CUSTOM_DRAW`](super::OutputFlags::CUSTOM_DRAW).
+    /// Otherwise OBS has already started its default effect around
+    /// `video_render`, and the texture is drawn with that one instead, with
+    /// `linear_alpha` ignored.
+    pub fn draw_source_texture(
+        &mut self,
+        texture: &GraphicsTexture,
+        flip: bool,
+        linear_alpha: bool,
+    ) {
+        let effect: obs_base_effect = if linear_alpha {
+            obs_base_effect_OBS_EFFECT_DEFAULT
+        } else {
+            obs_base_effect_OBS_EFFECT_PREMULTIPLIED_ALPHA
+        };
+
+        unsafe {
+            // Effects cannot be nested, so the one OBS started is reused.
+            let active = gs_get_effect();
+            if !active.is_null() {
+                let image = gs_effect_get_param_by_name(active, b"image\0".as_ptr() as *const _);
+                gs_effect_set_texture(image, texture.as_ptr());
+                obs_source_draw(texture.as_ptr(), 0, 0, 0, 0, flip);
+                return;
+            }
+
+            let effect = obs_get_base_effect(effect);
+            if effect.is_null() {
+                return;
+            }
+            let image = gs_effect_get_param_by_name(effect, b"image\0".as_ptr() as *const _);
+            gs_effect_set_texture(image, texture.as_ptr());
+            while gs_effect_loop(effect, b"Draw\0".as_ptr() as *const _) {
+                obs_source_draw(texture.as_ptr(), 0, 0, 0, 0, flip);
+            }
+        }
     }
 }
 
-impl Default for GlobalContext {
-    fn default() -> Self {
-        Self
+impl AudioThreadContext {
+    /// # Safety
+    /// Must only be called on the audio thread, while mixing.
+    pub(crate) unsafe fn new() -> Self {
+        Self {
+            _private: PhantomData,
+        }
     }
 }
 
 pub struct CreatableSourceContext<'a, D> {
-    _source: *mut obs_source_t,
-    pub(crate) hotkey_callbacks: Vec<(
-        ObsString,
-        ObsString,
-        Box<dyn FnMut(&mut Hotkey, &mut Option<D>)>,
-    )>,
+    source: *mut obs_source_t,
+    pub(crate) hotkey_callbacks: Vec<(ObsString, ObsString, HotkeyCallback<D>)>,
+    pub(crate) hotkey_pair_callbacks: Vec<HotkeyPairRegistration<D>>,
     pub settings: DataObj<'a>,
     pub global: &'a mut GlobalContext,
 }
@@ -44,13 +180,21 @@ impl<'a, D> CreatableSourceContext<'a, D> {
         global: &'a mut GlobalContext,
     ) -> Self {
         Self {
-            _source: source,
+            source,
             hotkey_callbacks: Default::default(),
+            hotkey_pair_callbacks: Default::default(),
             settings,
             global,
         }
     }
 
+    /// Registers a hotkey for the source being created. `func` may capture
+    /// state of its own, and is kept until the source is destroyed.
+    ///
+    /// `func` is called both when the hotkey is pressed and when it is
+    /// released, see [`Hotkey::pressed`].
+    ///
+    /// See [OBS documentation](https://obsproject.com/docs/reference-hotkeys.html#c.obs_hotkey_register_source)
     pub fn register_hotkey<F: FnMut(&mut Hotkey, &mut Option<D>) + 'static>(
         &mut self,
         name: ObsString,
@@ -61,6 +205,39 @@ impl<'a, D> CreatableSourceContext<'a, D> {
             .push((name, description, Box::new(func)));
     }
 
+    /// Registers a pair of hotkeys that toggle between two states, such as
+    /// enable / disable.
+    ///
+    /// Only the hotkey for the current state fires: `func0` while in the first
+    /// state and `func1` while in the second. Each callback returns `true` if the
+    /// press was consumed and the pair should switch to the other state.
+    ///
+    /// See [OBS documentation](https://obsproject.com/docs/reference-hotkeys.html#c.obs_hotkey_pair_register_source)
+    pub fn register_hotkey_pair<F0, F1>(
+        &mut self,
+        name0: ObsString,
+        description0: ObsString,
+        name1: ObsString,
+        description1: ObsString,
+        func0: F0,
+        func1: F1,
+    ) where
+        F0: FnMut(&mut Hotkey, &mut Option<D>) -> bool + 'static,
+        F1: FnMut(&mut Hotkey, &mut Option<D>) -> bool + 'static,
+    {
+        self.hotkey_pair_callbacks.push(HotkeyPairRegistration {
+            names: [name0, name1],
+            descriptions: [description0, description1],
+            callbacks: [Box::new(func0), Box::new(func1)],
+        });
+    }
+
+    /// Returns the value attached to the source type with
+    /// [`SourceInfoBuilder::with_type_data`](super::SourceInfoBuilder::with_type_data).
+    pub fn type_data(&self) -> Option<&TypeData> {
+        unsafe { TypeData::from_raw(obs_source_get_type_data(self.source)) }
+    }
+
     // Inherited from child contexts
 
     pub fn with_audio<T, F: FnOnce(&AudioRef) -> T>(&self, func: F) -> T {
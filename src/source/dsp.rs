@@ -0,0 +1,371 @@
+//! Building blocks for audio filters, working on the planar `f32` channels of
+//! [`AudioDataContext`](crate::source::audio::AudioDataContext):
+//!
+//! ```ignore
+//! fn filter_audio(data: &mut Option<Data>, audio: &mut AudioDataContext) -> FilterAudioResult {
+//!     if let Some(data) = data {
+//!         for (channel, filter) in data.filters.iter_mut().enumerate() {
+//!             if let Some(samples) = audio.get_channel_as_mut_slice(channel) {
+//!                 filter.process(samples);
+//!                 dsp::apply_gain(samples, dsp::db_to_mul(data.gain_db));
+//!             }
+//!         }
+//!     }
+//!     FilterAudioResult::Pass
+//! }
+//! ```
+//!
+//! The loops are written over plain slices without bounds checks in their
+//! bodies, so the compiler can vectorize them.
+
+use std::f32::consts::PI;
+
+/// Converts decibels to a linear gain, with `-inf` dB giving `0.0`.
+pub fn db_to_mul(db: f32) -> f32 {
+    if db == f32::NEG_INFINITY {
+        0.
+    } else {
+        10f32.powf(db / 20.)
+    }
+}
+
+/// Converts a linear gain to decibels, with `0.0` giving `-inf` dB.
+pub fn mul_to_db(mul: f32) -> f32 {
+    if mul <= 0. {
+        f32::NEG_INFINITY
+    } else {
+        20. * mul.log10()
+    }
+}
+
+/// Multiplies every sample by `gain`.
+pub fn apply_gain(samples: &mut [f32], gain: f32) {
+    samples.iter_mut().for_each(|s| *s *= gain);
+}
+
+/// Multiplies the samples by a gain ramping linearly from `from` to `to`
+/// over the buffer, which avoids the clicks of changing the gain at once.
+pub fn apply_gain_ramp(samples: &mut [f32], from: f32, to: f32) {
+    let step = (to - from) / samples.len().max(1) as f32;
+    for (i, s) in samples.iter_mut().enumerate() {
+        *s *= from + step * i as f32;
+    }
+}
+
+/// Adds `input` multiplied by `gain` to `output`.
+///
+/// Only as many frames as the shortest buffer are mixed.
+pub fn mix_into(output: &mut [f32], input: &[f32], gain: f32) {
+    for (o, i) in output.iter_mut().zip(input) {
+        *o += i * gain;
+    }
+}
+
+/// Clamps every sample to `[-limit, limit]`, a hard clipper.
+pub fn clamp(samples: &mut [f32], limit: f32) {
+    samples.iter_mut().for_each(|s| *s = s.clamp(-limit, limit));
+}
+
+/// Returns the largest absolute sample value.
+pub fn peak(samples: &[f32]) -> f32 {
+    samples.iter().fold(0., |peak, s| peak.max(s.abs()))
+}
+
+/// Returns the root mean square of the samples, or `0.0` if there are none.
+pub fn rms(samples: &[f32]) -> f32 {
+    if samples.is_empty() {
+        return 0.;
+    }
+    let sum: f32 = samples.iter().map(|s| s * s).sum();
+    (sum / samples.len() as f32).sqrt()
+}
+
+/// A second order IIR filter, with the coefficients of the
+/// [Audio EQ Cookbook](https://www.w3.org/TR/audio-eq-cookbook/).
+///
+/// A filter keeps the state of a single channel, so every channel needs its
+/// own. Changing the coefficients with [`set_coefficients`](Self::set_coefficients)
+/// keeps the state, so settings can change while audio plays.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Biquad {
+    b0: f32,
+    b1: f32,
+    b2: f32,
+    a1: f32,
+    a2: f32,
+    z1: f32,
+    z2: f32,
+}
+
+impl Biquad {
+    /// A low-pass filter at `cutoff` Hz. A `q` of `FRAC_1_SQRT_2` gives a flat
+    /// passband.
+    pub fn low_pass(sample_rate: f32, cutoff: f32, q: f32) -> Self {
+        let (cos, alpha) = Self::params(sample_rate, cutoff, q);
+        Self::normalized(
+            (1. - cos) / 2.,
+            1. - cos,
+            (1. - cos) / 2.,
+            1. + alpha,
+            -2. * cos,
+            1. - alpha,
+        )
+    }
+
+    /// A high-pass filter at `cutoff` Hz.
+    pub fn high_pass(sample_rate: f32, cutoff: f32, q: f32) -> Self {
+        let (cos, alpha) = Self::params(sample_rate, cutoff, q);
+        Self::normalized(
+            (1. + cos) / 2.,
+            -(1. + cos),
+            (1. + cos) / 2.,
+            1. + alpha,
+            -2. * cos,
+            1. - alpha,
+        )
+    }
+
+    /// Boosts or cuts a band around `center` Hz by `gain_db`.
+    pub fn peaking(sample_rate: f32, center: f32, q: f32, gain_db: f32) -> Self {
+        let (cos, alpha) = Self::params(sample_rate, center, q);
+        let a = 10f32.powf(gain_db / 40.);
+        Self::normalized(
+            1. + alpha * a,
+            -2. * cos,
+            1. - alpha * a,
+            1. + alpha / a,
+            -2. * cos,
+            1. - alpha / a,
+        )
+    }
+
+    /// Boosts or cuts frequencies below `cutoff` Hz by `gain_db`.
+    pub fn low_shelf(sample_rate: f32, cutoff: f32, q: f32, gain_db: f32) -> Self {
+        let (cos, alpha) = Self::params(sample_rate, cutoff, q);
+        let a = 10f32.powf(gain_db / 40.);
+        let sqrt = 2. * a.sqrt() * alpha;
+        Self::normalized(
+            a * ((a + 1.) - (a - 1.) * cos + sqrt),
+            2. * a * ((a - 1.) - (a + 1.) * cos),
+            a * ((a + 1.) - (a - 1.) * cos - sqrt),
+            (a + 1.) + (a - 1.) * cos + sqrt,
+            -2. * ((a - 1.) + (a + 1.) * cos),
+            (a + 1.) + (a - 1.) * cos - sqrt,
+        )
+    }
+
+    /// Boosts or cuts frequencies above `cutoff` Hz by `gain_db`.
+    pub fn high_shelf(sample_rate: f32, cutoff: f32, q: f32, gain_db: f32) -> Self {
+        let (cos, alpha) = Self::params(sample_rate, cutoff, q);
+        let a = 10f32.powf(gain_db / 40.);
+        let sqrt = 2. * a.sqrt() * alpha;
+        Self::normalized(
+            a * ((a + 1.) + (a - 1.) * cos + sqrt),
+            -2. * a * ((a - 1.) + (a + 1.) * cos),
+            a * ((a + 1.) + (a - 1.) * cos - sqrt),
+            (a + 1.) - (a - 1.) * cos + sqrt,
+            2. * ((a - 1.) - (a + 1.) * cos),
+            (a + 1.) - (a - 1.) * cos - sqrt,
+        )
+    }
+
+    /// Takes the coefficients of `other`, keeping the state of this filter.
+    pub fn set_coefficients(&mut self, other: &Biquad) {
+        self.b0 = other.b0;
+        self.b1 = other.b1;
+        self.b2 = other.b2;
+        self.a1 = other.a1;
+        self.a2 = other.a2;
+    }
+
+    /// Clears the state, e.g. after a gap in the audio.
+    pub fn reset(&mut self) {
+        self.z1 = 0.;
+        self.z2 = 0.;
+    }
+
+    /// Filters `samples` in place.
+    pub fn process(&mut self, samples: &mut [f32]) {
+        // Transposed direct form II, which has the least numerical error in
+        // single precision.
+        let (mut z1, mut z2) = (self.z1, self.z2);
+        for s in samples.iter_mut() {
+            let input = *s;
+            let output = self.b0 * input + z1;
+            z1 = self.b1 * input - self.a1 * output + z2;
+            z2 = self.b2 * input - self.a2 * output;
+            *s = output;
+        }
+        self.z1 = z1;
+        self.z2 = z2;
+    }
+
+    fn params(sample_rate: f32, frequency: f32, q: f32) -> (f32, f32) {
+        let w0 = 2. * PI * frequency / sample_rate;
+        (w0.cos(), w0.sin() / (2. * q))
+    }
+
+    fn normalized(b0: f32, b1: f32, b2: f32, a0: f32, a1: f32, a2: f32) -> Self {
+        Self {
+            b0: b0 / a0,
+            b1: b1 / a0,
+            b2: b2 / a0,
+            a1: a1 / a0,
+            a2: a2 / a0,
+            z1: 0.,
+            z2: 0.,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f32::consts::FRAC_1_SQRT_2;
+
+    const SAMPLE_RATE: f32 = 48000.;
+
+    fn assert_close(actual: f32, expected: f32, tolerance: f32) {
+        assert!(
+            (actual - expected).abs() <= tolerance,
+            "{} is not within {} of {}",
+            actual,
+            tolerance,
+            expected
+        );
+    }
+
+    /// The magnitude of the response of `filter` at `frequency` Hz, from its
+    /// transfer function evaluated on the unit circle.
+    fn gain_at(filter: &Biquad, frequency: f32) -> f32 {
+        let w = 2. * PI * frequency / SAMPLE_RATE;
+        let (c1, s1, c2, s2) = (w.cos(), w.sin(), (2. * w).cos(), (2. * w).sin());
+        let num_re = filter.b0 + filter.b1 * c1 + filter.b2 * c2;
+        let num_im = -filter.b1 * s1 - filter.b2 * s2;
+        let den_re = 1. + filter.a1 * c1 + filter.a2 * c2;
+        let den_im = -filter.a1 * s1 - filter.a2 * s2;
+        ((num_re * num_re + num_im * num_im) / (den_re * den_re + den_im * den_im)).sqrt()
+    }
+
+    /// The gain of `filter` on a sine at `frequency` Hz, once it settled.
+    fn measured_gain(mut filter: Biquad, frequency: f32) -> f32 {
+        let mut samples: Vec<f32> = (0..SAMPLE_RATE as usize)
+            .map(|i| (2. * PI * frequency * i as f32 / SAMPLE_RATE).sin())
+            .collect();
+        let input = rms(&samples[samples.len() / 2..]);
+        filter.process(&mut samples);
+        rms(&samples[samples.len() / 2..]) / input
+    }
+
+    #[test]
+    fn db_round_trips() {
+        for &db in &[-60., -6., 0., 3., 12.] {
+            assert_close(mul_to_db(db_to_mul(db)), db, 1e-4);
+        }
+        for &mul in &[0.001, 0.5, 1., 2.] {
+            assert_close(db_to_mul(mul_to_db(mul)), mul, 1e-6);
+        }
+        assert_close(db_to_mul(-6.0206), 0.5, 1e-4);
+    }
+
+    #[test]
+    fn silence_is_negative_infinity() {
+        assert_eq!(db_to_mul(f32::NEG_INFINITY), 0.);
+        assert_eq!(mul_to_db(0.), f32::NEG_INFINITY);
+        assert_eq!(mul_to_db(-1.), f32::NEG_INFINITY);
+        assert_eq!(db_to_mul(mul_to_db(0.)), 0.);
+    }
+
+    #[test]
+    fn low_pass_passes_dc_and_stops_nyquist() {
+        let filter = Biquad::low_pass(SAMPLE_RATE, 1000., FRAC_1_SQRT_2);
+        assert_close(gain_at(&filter, 0.), 1., 1e-4);
+        assert_close(gain_at(&filter, SAMPLE_RATE / 2.), 0., 1e-4);
+        assert_close(gain_at(&filter, 1000.), FRAC_1_SQRT_2, 1e-3);
+    }
+
+    #[test]
+    fn high_pass_stops_dc_and_passes_nyquist() {
+        let filter = Biquad::high_pass(SAMPLE_RATE, 1000., FRAC_1_SQRT_2);
+        assert_close(gain_at(&filter, 0.), 0., 1e-4);
+        assert_close(gain_at(&filter, SAMPLE_RATE / 2.), 1., 1e-4);
+        assert_close(gain_at(&filter, 1000.), FRAC_1_SQRT_2, 1e-3);
+    }
+
+    #[test]
+    fn processing_matches_the_response() {
+        let filter = Biquad::low_pass(SAMPLE_RATE, 1000., FRAC_1_SQRT_2);
+        for &frequency in &[100., 1000., 5000.] {
+            assert_close(
+                measured_gain(filter, frequency),
+                gain_at(&filter, frequency),
+                1e-2,
+            );
+        }
+
+        let mut dc = vec![1.; 4800];
+        Biquad::low_pass(SAMPLE_RATE, 1000., FRAC_1_SQRT_2).process(&mut dc);
+        assert_close(dc[dc.len() - 1], 1., 1e-4);
+    }
+
+    #[test]
+    fn peaking_reaches_its_gain_at_the_center() {
+        for &gain_db in &[-12., -3., 6., 12.] {
+            let filter = Biquad::peaking(SAMPLE_RATE, 2000., 1., gain_db);
+            assert_close(mul_to_db(gain_at(&filter, 2000.)), gain_db, 1e-2);
+            assert_close(mul_to_db(measured_gain(filter, 2000.)), gain_db, 0.1);
+            assert_close(gain_at(&filter, 0.), 1., 1e-4);
+            assert_close(gain_at(&filter, SAMPLE_RATE / 2.), 1., 1e-4);
+        }
+    }
+
+    #[test]
+    fn set_coefficients_keeps_the_state() {
+        let mut filter = Biquad::low_pass(SAMPLE_RATE, 1000., FRAC_1_SQRT_2);
+        filter.process(&mut [1.; 16]);
+        let state = (filter.z1, filter.z2);
+        filter.set_coefficients(&Biquad::high_pass(SAMPLE_RATE, 200., 1.));
+        assert_eq!((filter.z1, filter.z2), state);
+        filter.reset();
+        assert_eq!((filter.z1, filter.z2), (0., 0.));
+    }
+
+    #[test]
+    fn gain_ramp_starts_at_from_and_leads_into_to() {
+        let mut samples = vec![1.; 4];
+        apply_gain_ramp(&mut samples, 0., 1.);
+        assert_eq!(samples, [0., 0.25, 0.5, 0.75]);
+
+        // The next buffer starts at `to`, continuing the same slope.
+        let mut samples = vec![2.; 5];
+        apply_gain_ramp(&mut samples, 1., 0.5);
+        assert_close(samples[0], 2., 1e-6);
+        assert_close(samples[4], 2. * (0.5 + 0.1), 1e-6);
+
+        let mut empty: [f32; 0] = [];
+        apply_gain_ramp(&mut empty, 0., 1.);
+
+        let mut flat = vec![0.5; 3];
+        apply_gain_ramp(&mut flat, 2., 2.);
+        assert_eq!(flat, [1.; 3]);
+    }
+
+    #[test]
+    fn rms_and_peak_of_known_signals() {
+        assert_eq!(rms(&[]), 0.);
+        assert_eq!(peak(&[]), 0.);
+
+        assert_close(rms(&[0.5; 64]), 0.5, 1e-6);
+        assert_eq!(peak(&[0.25, -0.75, 0.5]), 0.75);
+
+        let square: Vec<f32> = (0..64).map(|i| if i % 2 == 0 { 1. } else { -1. }).collect();
+        assert_close(rms(&square), 1., 1e-6);
+        assert_eq!(peak(&square), 1.);
+
+        let sine: Vec<f32> = (0..4800)
+            .map(|i| 0.8 * (2. * PI * 100. * i as f32 / SAMPLE_RATE).sin())
+            .collect();
+        assert_close(rms(&sine), 0.8 * FRAC_1_SQRT_2, 1e-4);
+        assert_close(peak(&sine), 0.8, 1e-4);
+    }
+}
@@ -0,0 +1,288 @@
+//! Source types described by trait objects instead of a generic
+//! [`SourceInfoBuilder`](crate::source::SourceInfoBuilder), so that they can be
+//! chosen at runtime, e.g. one type per entry of a configuration file:
+//!
+//! ```ignore
+//! struct PresetType { id: String, preset: Preset }
+//!
+//! impl DynSourceType for PresetType {
+//!     fn id(&self) -> ObsString { ObsString::from(self.id.as_str()) }
+//!     fn name(&self) -> ObsString { ObsString::from(self.preset.name.as_str()) }
+//!     fn source_type(&self) -> SourceType { SourceType::FILTER }
+//!     fn output_flags(&self) -> OutputFlags { OutputFlags::VIDEO }
+//!
+//!     fn create(&self, _settings: &mut DataObj, source: SourceContext) -> Option<Box<dyn DynSource>> {
+//!         Some(Box::new(PresetFilter::new(&self.preset, source)))
+//!     }
+//! }
+//!
+//! for (id, preset) in presets {
+//!     let info = SourceInfo::from_dyn(PresetType { id, preset });
+//!     let _ = load_context.register_source(info);
+//! }
+//! ```
+//!
+//! Every type shares the same non-generic callbacks, so registering many of
+//! them does not grow the plugin binary.
+
+use super::audio::{AudioDataContext, FilterAudioResult};
+use super::context::{GlobalContext, VideoRenderContext};
+use super::ffi::{filter_audio_output, free_type_data};
+use super::properties::Properties;
+use super::{OutputFlags, SourceContext, SourceInfo, SourceType, TypeData};
+use crate::data::DataObj;
+use crate::string::ObsString;
+use crate::unwind::handle_unwind;
+use crate::wrapper::PtrWrapper;
+use obs_sys::{
+    obs_audio_data, obs_data_t, obs_properties, obs_source_get_type_data, obs_source_info,
+    obs_source_t,
+};
+
+use std::ffi::c_void;
+use std::mem::forget;
+use std::os::raw::c_char;
+
+/// A source type registered with [`SourceInfo::from_dyn`].
+pub trait DynSourceType: Send + Sync {
+    /// The unique id of the type.
+    fn id(&self) -> ObsString;
+
+    /// The name of the type shown to the user.
+    fn name(&self) -> ObsString;
+
+    fn source_type(&self) -> SourceType;
+
+    /// The flags of the type. Unlike with [`SourceInfoBuilder`](super::SourceInfoBuilder),
+    /// none are inferred: the video callbacks of [`DynSource`] are only called
+    /// with [`OutputFlags::VIDEO`], and [`DynSource::filter_audio`] only for
+    /// filters with [`OutputFlags::AUDIO`].
+    fn output_flags(&self) -> OutputFlags;
+
+    /// Whether [`DynSource::get_width`] and [`DynSource::get_height`] report
+    /// the size of the source. Without them OBS uses the size of the filter
+    /// target, so this is only `false` for filters by default.
+    fn has_size(&self) -> bool {
+        self.source_type() != SourceType::FILTER
+    }
+
+    /// Creates a source of this type, or returns `None` if it cannot be
+    /// created.
+    fn create(&self, settings: &mut DataObj, source: SourceContext) -> Option<Box<dyn DynSource>>;
+
+    fn get_defaults(&self, _settings: &mut DataObj) {}
+}
+
+/// A source created by a [`DynSourceType`]. Every callback has a default
+/// implementation doing nothing.
+pub trait DynSource: Send {
+    fn update(&mut self, _settings: &mut DataObj, _context: &mut GlobalContext) {}
+
+    fn get_properties(&mut self, _properties: &mut Properties) {}
+
+    fn get_width(&mut self) -> u32 {
+        0
+    }
+
+    fn get_height(&mut self) -> u32 {
+        0
+    }
+
+    fn video_tick(&mut self, _seconds: f32) {}
+
+    fn video_render(&mut self, _context: &mut GlobalContext, _render: &mut VideoRenderContext) {}
+
+    fn filter_audio(&mut self, _audio: &mut AudioDataContext) -> FilterAudioResult {
+        FilterAudioResult::Pass
+    }
+
+    fn activate(&mut self) {}
+
+    fn deactivate(&mut self) {}
+}
+
+struct DynTypeData {
+    source_type: Box<dyn DynSourceType>,
+    // OBS keeps pointers to these for as long as the type is registered.
+    id: ObsString,
+    name: ObsString,
+}
+
+struct DynWrapper {
+    source: Box<dyn DynSource>,
+    audio_scratch: Vec<Vec<f32>>,
+    audio_replacement: Option<obs_audio_data>,
+}
+
+impl SourceInfo {
+    /// Describes a source type by a trait object, see the
+    /// [`dynamic`](crate::source::dynamic) module.
+    pub fn from_dyn(source_type: impl DynSourceType + 'static) -> SourceInfo {
+        let kind = source_type.source_type();
+        let flags = source_type.output_flags();
+        let has_size = source_type.has_size();
+        let type_data = DynTypeData {
+            id: source_type.id(),
+            name: source_type.name(),
+            source_type: Box::new(source_type),
+        };
+
+        let mut info = obs_source_info {
+            id: type_data.id.as_ptr(),
+            type_: kind.to_native(),
+            output_flags: flags.bits(),
+            get_name: Some(get_name),
+            create: Some(create),
+            destroy: Some(destroy),
+            update: Some(update),
+            get_defaults2: Some(get_defaults),
+            get_properties: Some(get_properties),
+            video_tick: Some(video_tick),
+            activate: Some(activate),
+            deactivate: Some(deactivate),
+            free_type_data: Some(free_type_data),
+            ..Default::default()
+        };
+        if flags.contains(OutputFlags::VIDEO) {
+            if has_size {
+                info.get_width = Some(get_width);
+                info.get_height = Some(get_height);
+            }
+            info.video_render = Some(video_render);
+        }
+        if kind == SourceType::FILTER && flags.contains(OutputFlags::AUDIO) {
+            info.filter_audio = Some(filter_audio);
+        }
+        info.type_data = Box::into_raw(Box::new(TypeData::new(type_data))) as *mut c_void;
+
+        SourceInfo {
+            info: Box::new(info),
+        }
+    }
+}
+
+unsafe fn type_data<'a>(type_data: *mut c_void) -> Option<&'a DynTypeData> {
+    TypeData::from_raw(type_data)?.downcast_ref()
+}
+
+unsafe fn wrapper<'a>(data: *mut c_void) -> &'a mut DynWrapper {
+    &mut *(data as *mut DynWrapper)
+}
+
+unsafe extern "C" fn get_name(data: *mut c_void) -> *const c_char {
+    type_data(data).map_or(std::ptr::null(), |data| data.name.as_ptr())
+}
+
+unsafe extern "C" fn create(settings: *mut obs_data_t, source: *mut obs_source_t) -> *mut c_void {
+    let type_data = match type_data(obs_source_get_type_data(source)) {
+        Some(type_data) => type_data,
+        None => return std::ptr::null_mut(),
+    };
+    let mut settings = DataObj::from_raw(settings);
+    let created = handle_unwind::<dyn DynSourceType, _>("create", || {
+        type_data
+            .source_type
+            .create(&mut settings, SourceContext { source })
+    });
+    forget(settings);
+
+    match created.flatten() {
+        Some(source) => Box::into_raw(Box::new(DynWrapper {
+            source,
+            audio_scratch: Vec::new(),
+            audio_replacement: None,
+        })) as *mut c_void,
+        None => std::ptr::null_mut(),
+    }
+}
+
+unsafe extern "C" fn destroy(data: *mut c_void) {
+    let wrapper = Box::from_raw(data as *mut DynWrapper);
+    handle_unwind::<dyn DynSource, _>("drop", || drop(wrapper));
+}
+
+unsafe extern "C" fn update(data: *mut c_void, settings: *mut obs_data_t) {
+    let wrapper = wrapper(data);
+    let mut global = GlobalContext::new();
+    let mut settings = DataObj::from_raw(settings);
+    handle_unwind::<dyn DynSource, _>("update", || {
+        wrapper.source.update(&mut settings, &mut global)
+    });
+    forget(settings);
+}
+
+unsafe extern "C" fn get_defaults(data: *mut c_void, settings: *mut obs_data_t) {
+    let type_data = match type_data(data) {
+        Some(type_data) => type_data,
+        None => return,
+    };
+    let mut settings = DataObj::from_raw(settings);
+    handle_unwind::<dyn DynSourceType, _>("get_defaults", || {
+        type_data.source_type.get_defaults(&mut settings)
+    });
+    forget(settings);
+}
+
+unsafe extern "C" fn get_properties(data: *mut c_void) -> *mut obs_properties {
+    // OBS asks for the properties of a type without a source with null data.
+    let wrapper = (data as *mut DynWrapper).as_mut();
+    handle_unwind::<dyn DynSource, _>("get_properties", || {
+        let mut properties = Properties::new();
+        if let Some(wrapper) = wrapper {
+            wrapper.source.get_properties(&mut properties);
+        }
+        properties.into_raw()
+    })
+    .unwrap_or(std::ptr::null_mut())
+}
+
+unsafe extern "C" fn get_width(data: *mut c_void) -> u32 {
+    let wrapper = wrapper(data);
+    handle_unwind::<dyn DynSource, _>("get_width", || wrapper.source.get_width()).unwrap_or(0)
+}
+
+unsafe extern "C" fn get_height(data: *mut c_void) -> u32 {
+    let wrapper = wrapper(data);
+    handle_unwind::<dyn DynSource, _>("get_height", || wrapper.source.get_height()).unwrap_or(0)
+}
+
+unsafe extern "C" fn video_tick(data: *mut c_void, seconds: f32) {
+    let wrapper = wrapper(data);
+    handle_unwind::<dyn DynSource, _>("video_tick", || wrapper.source.video_tick(seconds));
+}
+
+unsafe extern "C" fn video_render(data: *mut c_void, _effect: *mut obs_sys::gs_effect_t) {
+    let wrapper = wrapper(data);
+    let mut global = GlobalContext::new();
+    let mut render = VideoRenderContext::new();
+    handle_unwind::<dyn DynSource, _>("video_render", || {
+        wrapper.source.video_render(&mut global, &mut render)
+    });
+}
+
+unsafe extern "C" fn filter_audio(
+    data: *mut c_void,
+    audio: *mut obs_audio_data,
+) -> *mut obs_audio_data {
+    let DynWrapper {
+        source,
+        audio_scratch,
+        audio_replacement,
+    } = wrapper(data);
+    let mut context = AudioDataContext::from_raw(audio, audio_replacement, audio_scratch);
+    let result =
+        handle_unwind::<dyn DynSource, _>("filter_audio", || source.filter_audio(&mut context))
+            .unwrap_or(FilterAudioResult::Pass);
+    let audio = context.as_ptr();
+    filter_audio_output(audio, result, audio_scratch, audio_replacement)
+}
+
+unsafe extern "C" fn activate(data: *mut c_void) {
+    let wrapper = wrapper(data);
+    handle_unwind::<dyn DynSource, _>("activate", || wrapper.source.activate());
+}
+
+unsafe extern "C" fn deactivate(data: *mut c_void) {
+    let wrapper = wrapper(data);
+    handle_unwind::<dyn DynSource, _>("deactivate", || wrapper.source.deactivate());
+}
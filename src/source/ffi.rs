@@ -1,36 +1,57 @@
-use super::audio::AudioDataContext;
-use super::context::{CreatableSourceContext, GlobalContext, VideoRenderContext};
-use super::hotkey::Hotkey;
+use super::audio::{AudioDataContext, FilterAudioResult};
+use super::context::{
+    AudioThreadContext, CreatableSourceContext, GlobalContext, VideoRenderContext,
+};
+use super::frame::AsyncFrame;
+use super::hotkey::{Hotkey, HotkeyCallback, HotkeyPairCallback, HotkeyPairRegistration};
 use super::properties::Properties;
 use super::traits::*;
 use super::ObsString;
-use super::{EnumActiveContext, EnumAllContext, SourceContext};
-use crate::{data::DataObj, wrapper::PtrWrapper};
+use super::{
+    EnumActiveContext, EnumAllContext, KeyEvent, MouseButton, MouseEvent, SourceContext, TypeData,
+};
+use crate::graphics::GraphicsTexture;
+use crate::{
+    data::DataObj,
+    unwind::{handle_result, handle_unwind},
+    wrapper::PtrWrapper,
+};
 use paste::item;
 use std::collections::HashMap;
+use std::convert::TryFrom;
 use std::ffi::c_void;
 use std::mem::forget;
 use std::os::raw::c_char;
+use std::sync::{Mutex, Once};
 
 use obs_sys::{
-    gs_effect_t, obs_audio_data, obs_data_t, obs_hotkey_id, obs_hotkey_register_source,
-    obs_hotkey_t, obs_media_state, obs_properties, obs_source_audio_mix, obs_source_enum_proc_t,
-    obs_source_t, size_t,
+    calldata_set_data, calldata_t, gs_effect_t, gs_texture_t, obs_audio_data, obs_data_t,
+    obs_hotkey_id, obs_hotkey_pair_id, obs_hotkey_pair_register_source, obs_hotkey_pair_unregister,
+    obs_hotkey_register_source, obs_hotkey_t, obs_hotkey_unregister, obs_key_event,
+    obs_media_state, obs_media_state_OBS_MEDIA_STATE_ERROR, obs_mouse_event, obs_properties,
+    obs_properties_destroy, obs_source_audio_mix, obs_source_enum_proc_t, obs_source_frame,
+    obs_source_get_proc_handler, obs_source_properties, obs_source_t, obs_transition_audio_render,
+    obs_transition_video_render, proc_handler_add, size_t,
 };
 
 struct DataWrapper<D> {
     data: Option<D>,
-    hotkey_callbacks: HashMap<obs_hotkey_id, Box<dyn FnMut(&mut Hotkey, &mut Option<D>)>>,
+    source: *mut obs_source_t,
+    hotkey_callbacks: HashMap<obs_hotkey_id, HotkeyCallback<D>>,
+    hotkey_pair_callbacks: HashMap<obs_hotkey_pair_id, [HotkeyPairCallback<D>; 2]>,
+    /// Read by the `get_property_tags` proc, which may run on any thread.
+    property_tags: Mutex<Option<String>>,
+    property_tags_proc: Once,
+    /// Audio of a filter that grew or replaced it, which OBS reads after the
+    /// filter returns.
+    audio_scratch: Vec<Vec<f32>>,
+    audio_replacement: Option<obs_audio_data>,
 }
 
 impl<D> DataWrapper<D> {
     pub(crate) unsafe fn register_callbacks(
         &mut self,
-        callbacks: Vec<(
-            ObsString,
-            ObsString,
-            Box<dyn FnMut(&mut Hotkey, &mut Option<D>)>,
-        )>,
+        callbacks: Vec<(ObsString, ObsString, HotkeyCallback<D>)>,
         source: *mut obs_source_t,
         data: *mut c_void,
     ) {
@@ -46,13 +67,74 @@ impl<D> DataWrapper<D> {
             self.hotkey_callbacks.insert(id, func);
         }
     }
+
+    pub(crate) unsafe fn register_hotkey_pairs(
+        &mut self,
+        pairs: Vec<HotkeyPairRegistration<D>>,
+        source: *mut obs_source_t,
+        data: *mut c_void,
+    ) {
+        for pair in pairs.into_iter() {
+            let id = obs_hotkey_pair_register_source(
+                source,
+                pair.names[0].as_ptr(),
+                pair.descriptions[0].as_ptr(),
+                pair.names[1].as_ptr(),
+                pair.descriptions[1].as_ptr(),
+                Some(hotkey_pair_callback::<D, 0>),
+                Some(hotkey_pair_callback::<D, 1>),
+                data,
+                data,
+            );
+
+            self.hotkey_pair_callbacks.insert(id, pair.callbacks);
+        }
+    }
+
+    /// Unregisters the hotkeys of the source before their callbacks are
+    /// dropped. OBS would only do so after the source has been destroyed,
+    /// leaving a window in which a callback could still fire.
+    unsafe fn unregister_hotkeys(&mut self) {
+        for id in self.hotkey_callbacks.keys() {
+            obs_hotkey_unregister(*id);
+        }
+        for id in self.hotkey_pair_callbacks.keys() {
+            obs_hotkey_pair_unregister(*id);
+        }
+    }
+
+    /// Keeps the tags of the latest properties, adding the
+    /// `get_property_tags` proc the first time there are any, so that only
+    /// sources using [`PropertyTags`](super::properties::PropertyTags) have it.
+    unsafe fn set_property_tags(&mut self, tags: Option<String>, data: *mut c_void) {
+        let has_tags = tags.is_some();
+        *self.property_tags.lock().unwrap_or_else(|e| e.into_inner()) = tags;
+
+        let source = self.source;
+        if has_tags && !source.is_null() {
+            self.property_tags_proc.call_once(|| {
+                proc_handler_add(
+                    obs_source_get_proc_handler(source),
+                    "void get_property_tags(out string tags)\0".as_ptr() as *const c_char,
+                    Some(get_property_tags::<D>),
+                    data,
+                );
+            });
+        }
+    }
 }
 
 impl<D> Default for DataWrapper<D> {
     fn default() -> Self {
         Self {
             data: None,
+            source: std::ptr::null_mut(),
             hotkey_callbacks: HashMap::new(),
+            hotkey_pair_callbacks: HashMap::new(),
+            property_tags: Mutex::new(None),
+            property_tags_proc: Once::new(),
+            audio_scratch: Vec::new(),
+            audio_replacement: None,
         }
     }
 }
@@ -61,7 +143,7 @@ impl<D> From<D> for DataWrapper<D> {
     fn from(data: D) -> Self {
         Self {
             data: Some(data),
-            hotkey_callbacks: HashMap::new(),
+            ..Default::default()
         }
     }
 }
@@ -73,7 +155,8 @@ macro_rules! impl_simple_fn {
                 data: *mut ::std::os::raw::c_void,
             ) $(-> $ret)? {
                 let wrapper = &mut *(data as *mut DataWrapper<D>);
-                F::$name(&mut wrapper.data)
+                handle_unwind::<F, _>(stringify!($name), || F::$name(&mut wrapper.data))
+                    .unwrap_or_default()
             }
         }
     )*)
@@ -82,7 +165,20 @@ macro_rules! impl_simple_fn {
 pub unsafe extern "C" fn get_name<D, F: GetNameSource<D>>(
     _type_data: *mut c_void,
 ) -> *const c_char {
-    F::get_name().as_ptr()
+    handle_unwind::<F, _>("get_name", || F::get_name().as_cached_ptr()).unwrap_or(std::ptr::null())
+}
+
+pub unsafe extern "C" fn get_name_type_data<D, F: GetNameTypeDataSource<D>>(
+    type_data: *mut c_void,
+) -> *const c_char {
+    let type_data = TypeData::from_raw(type_data);
+    handle_unwind::<F, _>("get_name", || F::get_name(type_data).as_cached_ptr())
+        .unwrap_or(std::ptr::null())
+}
+
+pub unsafe extern "C" fn free_type_data(type_data: *mut c_void) {
+    let type_data = Box::from_raw(type_data as *mut TypeData);
+    handle_unwind::<TypeData, _>("drop", || drop(type_data));
 }
 
 impl_simple_fn!(
@@ -95,53 +191,108 @@ impl_simple_fn!(
 
 pub unsafe extern "C" fn create_default_data<D>(
     _settings: *mut obs_data_t,
-    _source: *mut obs_source_t,
+    source: *mut obs_source_t,
 ) -> *mut c_void {
-    let data = Box::new(DataWrapper::<D>::default());
-    Box::into_raw(data) as *mut c_void
+    let wrapper = DataWrapper::<D> {
+        source,
+        ..Default::default()
+    };
+    Box::into_raw(Box::new(wrapper)) as *mut c_void
 }
 
-pub unsafe extern "C" fn create<D, F: CreatableSource<D>>(
+unsafe fn create_with<D, F: ?Sized, C>(
     settings: *mut obs_data_t,
     source: *mut obs_source_t,
-) -> *mut c_void {
+    func: C,
+) -> *mut c_void
+where
+    C: FnOnce(&mut CreatableSourceContext<D>, SourceContext) -> Option<D>,
+{
     let mut wrapper = DataWrapper::default();
 
-    let mut global = GlobalContext::default();
+    let mut global = GlobalContext::new();
     let settings = DataObj::from_raw(settings);
     let mut create = CreatableSourceContext::from_raw(source, settings, &mut global);
 
     let source_context = SourceContext { source };
 
-    let data = F::create(&mut create, source_context);
+    let data = handle_unwind::<F, _>("create", || func(&mut create, source_context));
+    forget(create.settings);
+
+    let data = match data {
+        Some(Some(data)) => data,
+        _ => return std::ptr::null_mut(),
+    };
 
     wrapper.data = Some(data);
-    forget(create.settings);
+    wrapper.source = source;
     let callbacks = create.hotkey_callbacks;
+    let pairs = create.hotkey_pair_callbacks;
 
     let pointer = Box::into_raw(Box::new(wrapper));
 
-    pointer
-        .as_mut()
-        .unwrap()
-        .register_callbacks(callbacks, source, pointer as *mut c_void);
+    let wrapper = pointer.as_mut().unwrap();
+    wrapper.register_callbacks(callbacks, source, pointer as *mut c_void);
+    wrapper.register_hotkey_pairs(pairs, source, pointer as *mut c_void);
+
+    pointer as *mut c_void
+}
+
+pub unsafe extern "C" fn create<D, F: CreatableSource<D>>(
+    settings: *mut obs_data_t,
+    source: *mut obs_source_t,
+) -> *mut c_void {
+    create_with::<_, F, _>(settings, source, |create, source| {
+        Some(F::create(create, source))
+    })
+}
 
-    return pointer as *mut c_void;
+pub unsafe extern "C" fn try_create<D, F: Sourceable + TryCreatableSource<D>>(
+    settings: *mut obs_data_t,
+    source: *mut obs_source_t,
+) -> *mut c_void {
+    create_with::<_, F, _>(settings, source, |create, source| {
+        match F::try_create(create, source) {
+            Ok(data) => Some(data),
+            Err(err) => {
+                crate::error!("Failed to create source {}: {}", F::get_id().as_str(), err);
+                None
+            }
+        }
+    })
 }
 
 pub unsafe extern "C" fn destroy<D>(data: *mut c_void) {
-    let wrapper: Box<DataWrapper<D>> = Box::from_raw(data as *mut DataWrapper<D>);
-    drop(wrapper);
+    let mut wrapper: Box<DataWrapper<D>> = Box::from_raw(data as *mut DataWrapper<D>);
+    wrapper.unregister_hotkeys();
+    handle_unwind::<D, _>("drop", || drop(wrapper));
 }
 
 pub unsafe extern "C" fn update<D, F: UpdateSource<D>>(
     data: *mut c_void,
     settings: *mut obs_data_t,
 ) {
-    let mut global = GlobalContext::default();
+    let mut global = GlobalContext::new();
+    let data: &mut DataWrapper<D> = &mut *(data as *mut DataWrapper<D>);
+    let mut settings = DataObj::from_raw(settings);
+    handle_unwind::<F, _>("update", || {
+        F::update(&mut data.data, &mut settings, &mut global)
+    });
+    forget(settings);
+}
+
+pub unsafe extern "C" fn try_update<D, F: Sourceable + TryUpdateSource<D>>(
+    data: *mut c_void,
+    settings: *mut obs_data_t,
+) {
+    let mut global = GlobalContext::new();
     let data: &mut DataWrapper<D> = &mut *(data as *mut DataWrapper<D>);
     let mut settings = DataObj::from_raw(settings);
-    F::update(&mut data.data, &mut settings, &mut global);
+    handle_unwind::<F, _>("try_update", || {
+        if let Err(err) = F::try_update(&mut data.data, &mut settings, &mut global) {
+            crate::error!("Failed to update source {}: {}", F::get_id().as_str(), err);
+        }
+    });
     forget(settings);
 }
 
@@ -150,9 +301,23 @@ pub unsafe extern "C" fn video_render<D, F: VideoRenderSource<D>>(
     _effect: *mut gs_effect_t,
 ) {
     let wrapper: &mut DataWrapper<D> = &mut *(data as *mut DataWrapper<D>);
-    let mut global = GlobalContext::default();
-    let mut render = VideoRenderContext::default();
-    F::video_render(&mut wrapper.data, &mut global, &mut render);
+    let mut global = GlobalContext::new();
+    let mut render = VideoRenderContext::new();
+    handle_unwind::<F, _>("video_render", || {
+        F::video_render(&mut wrapper.data, &mut global, &mut render)
+    });
+}
+
+pub unsafe extern "C" fn try_video_render<D, F: TryVideoRenderSource<D>>(
+    data: *mut ::std::os::raw::c_void,
+    _effect: *mut gs_effect_t,
+) {
+    let wrapper: &mut DataWrapper<D> = &mut *(data as *mut DataWrapper<D>);
+    let mut global = GlobalContext::new();
+    let mut render = VideoRenderContext::new();
+    handle_result::<F, _, _>("try_video_render", || {
+        F::try_video_render(&mut wrapper.data, &mut global, &mut render)
+    });
 }
 
 pub unsafe extern "C" fn audio_render<D, F: AudioRenderSource<D>>(
@@ -164,10 +329,78 @@ pub unsafe extern "C" fn audio_render<D, F: AudioRenderSource<D>>(
     _sample_rate: size_t,
 ) -> bool {
     let wrapper: &mut DataWrapper<D> = &mut *(data as *mut DataWrapper<D>);
-    let mut global = GlobalContext::default();
-    F::audio_render(&mut wrapper.data, &mut global);
+    let mut global = GlobalContext::new();
+    let mut audio = AudioThreadContext::new();
     // TODO: understand what this bool is
-    true
+    handle_unwind::<F, _>("audio_render", || {
+        F::audio_render(&mut wrapper.data, &mut global, &mut audio);
+        true
+    })
+    .unwrap_or(false)
+}
+
+pub unsafe extern "C" fn transition_video_render<D, F: TransitionVideoRenderSource<D>>(
+    data: *mut ::std::os::raw::c_void,
+    _effect: *mut gs_effect_t,
+) {
+    let wrapper: &mut DataWrapper<D> = &mut *(data as *mut DataWrapper<D>);
+    obs_transition_video_render(wrapper.source, Some(transition_video_callback::<D, F>));
+}
+
+unsafe extern "C" fn transition_video_callback<D, F: TransitionVideoRenderSource<D>>(
+    data: *mut ::std::os::raw::c_void,
+    a: *mut gs_texture_t,
+    b: *mut gs_texture_t,
+    t: f32,
+    cx: u32,
+    cy: u32,
+) {
+    if a.is_null() || b.is_null() {
+        return;
+    }
+    let wrapper: &mut DataWrapper<D> = &mut *(data as *mut DataWrapper<D>);
+    let a = GraphicsTexture::from_raw(a);
+    let b = GraphicsTexture::from_raw(b);
+    handle_unwind::<F, _>("transition_video_render", || {
+        F::transition_video_render(&mut wrapper.data, &a, &b, t, cx, cy)
+    });
+}
+
+pub unsafe extern "C" fn transition_audio_render<D, F: TransitionAudioRenderSource<D>>(
+    data: *mut ::std::os::raw::c_void,
+    ts_out: *mut u64,
+    audio_output: *mut obs_source_audio_mix,
+    mixers: u32,
+    channels: size_t,
+    sample_rate: size_t,
+) -> bool {
+    let wrapper: &mut DataWrapper<D> = &mut *(data as *mut DataWrapper<D>);
+    obs_transition_audio_render(
+        wrapper.source,
+        ts_out,
+        audio_output,
+        mixers,
+        channels,
+        sample_rate,
+        Some(transition_mix_a::<D, F>),
+        Some(transition_mix_b::<D, F>),
+    )
+}
+
+unsafe extern "C" fn transition_mix_a<D, F: TransitionAudioRenderSource<D>>(
+    data: *mut ::std::os::raw::c_void,
+    t: f32,
+) -> f32 {
+    let wrapper: &mut DataWrapper<D> = &mut *(data as *mut DataWrapper<D>);
+    handle_unwind::<F, _>("mix_a", || F::mix_a(&mut wrapper.data, t)).unwrap_or(1.0 - t)
+}
+
+unsafe extern "C" fn transition_mix_b<D, F: TransitionAudioRenderSource<D>>(
+    data: *mut ::std::os::raw::c_void,
+    t: f32,
+) -> f32 {
+    let wrapper: &mut DataWrapper<D> = &mut *(data as *mut DataWrapper<D>);
+    handle_unwind::<F, _>("mix_b", || F::mix_b(&mut wrapper.data, t)).unwrap_or(t)
 }
 
 pub unsafe extern "C" fn get_properties<D, F: GetPropertiesSource<D>>(
@@ -175,29 +408,53 @@ pub unsafe extern "C" fn get_properties<D, F: GetPropertiesSource<D>>(
 ) -> *mut obs_properties {
     let wrapper: &mut DataWrapper<D> = &mut *(data as *mut DataWrapper<D>);
 
-    let mut properties = Properties::new();
-    F::get_properties(&mut wrapper.data, &mut properties);
-    properties.into_raw()
+    handle_unwind::<F, _>("get_properties", || {
+        let mut properties = Properties::new();
+        F::get_properties(&mut wrapper.data, &mut properties);
+        wrapper.set_property_tags(properties.finish_tags(), data);
+        properties.into_raw()
+    })
+    .unwrap_or(std::ptr::null_mut())
+}
+
+pub unsafe extern "C" fn try_get_properties<D, F: TryGetPropertiesSource<D>>(
+    data: *mut ::std::os::raw::c_void,
+) -> *mut obs_properties {
+    let wrapper: &mut DataWrapper<D> = &mut *(data as *mut DataWrapper<D>);
+
+    handle_unwind::<F, _>("try_get_properties", || {
+        let mut properties = Properties::new();
+        handle_result::<F, _, _>("try_get_properties", || {
+            F::try_get_properties(&mut wrapper.data, &mut properties)
+        });
+        wrapper.set_property_tags(properties.finish_tags(), data);
+        properties.into_raw()
+    })
+    .unwrap_or(std::ptr::null_mut())
 }
 
 pub unsafe extern "C" fn enum_active_sources<D, F: EnumActiveSource<D>>(
     data: *mut ::std::os::raw::c_void,
-    _enum_callback: obs_source_enum_proc_t,
-    _param: *mut ::std::os::raw::c_void,
+    enum_callback: obs_source_enum_proc_t,
+    param: *mut ::std::os::raw::c_void,
 ) {
     let wrapper: &mut DataWrapper<D> = &mut *(data as *mut DataWrapper<D>);
-    let context = EnumActiveContext {};
-    F::enum_active_sources(&mut wrapper.data, &context);
+    let context = EnumActiveContext::from_raw(wrapper.source, enum_callback, param);
+    handle_unwind::<F, _>("enum_active_sources", || {
+        F::enum_active_sources(&mut wrapper.data, &context)
+    });
 }
 
 pub unsafe extern "C" fn enum_all_sources<D, F: EnumAllSource<D>>(
     data: *mut ::std::os::raw::c_void,
-    _enum_callback: obs_source_enum_proc_t,
-    _param: *mut ::std::os::raw::c_void,
+    enum_callback: obs_source_enum_proc_t,
+    param: *mut ::std::os::raw::c_void,
 ) {
     let wrapper: &mut DataWrapper<D> = &mut *(data as *mut DataWrapper<D>);
-    let context = EnumAllContext {};
-    F::enum_all_sources(&mut wrapper.data, &context);
+    let context = EnumAllContext::from_raw(wrapper.source, enum_callback, param);
+    handle_unwind::<F, _>("enum_all_sources", || {
+        F::enum_all_sources(&mut wrapper.data, &context)
+    });
 }
 
 impl_simple_fn!(
@@ -210,17 +467,97 @@ pub unsafe extern "C" fn video_tick<D, F: VideoTickSource<D>>(
     seconds: f32,
 ) {
     let wrapper: &mut DataWrapper<D> = &mut *(data as *mut DataWrapper<D>);
-    F::video_tick(&mut wrapper.data, seconds);
+    handle_unwind::<F, _>("video_tick", || F::video_tick(&mut wrapper.data, seconds));
+}
+
+pub unsafe extern "C" fn try_video_tick<D, F: TryVideoTickSource<D>>(
+    data: *mut ::std::os::raw::c_void,
+    seconds: f32,
+) {
+    let wrapper: &mut DataWrapper<D> = &mut *(data as *mut DataWrapper<D>);
+    handle_result::<F, _, _>("try_video_tick", || {
+        F::try_video_tick(&mut wrapper.data, seconds)
+    });
 }
 
 pub unsafe extern "C" fn filter_audio<D, F: FilterAudioSource<D>>(
     data: *mut ::std::os::raw::c_void,
     audio: *mut obs_audio_data,
 ) -> *mut obs_audio_data {
-    let mut context = AudioDataContext::from_raw(audio);
     let wrapper: &mut DataWrapper<D> = &mut *(data as *mut DataWrapper<D>);
-    F::filter_audio(&mut wrapper.data, &mut context);
-    audio
+    let DataWrapper {
+        data,
+        audio_scratch,
+        audio_replacement,
+        ..
+    } = wrapper;
+    let mut context = AudioDataContext::from_raw(audio, audio_replacement, audio_scratch);
+    let result = handle_unwind::<F, _>("filter_audio", || F::filter_audio(data, &mut context))
+        .unwrap_or(FilterAudioResult::Pass);
+    let audio = context.as_ptr();
+    filter_audio_output(audio, result, audio_scratch, audio_replacement)
+}
+
+pub unsafe extern "C" fn try_filter_audio<D, F: TryFilterAudioSource<D>>(
+    data: *mut ::std::os::raw::c_void,
+    audio: *mut obs_audio_data,
+) -> *mut obs_audio_data {
+    let wrapper: &mut DataWrapper<D> = &mut *(data as *mut DataWrapper<D>);
+    let DataWrapper {
+        data,
+        audio_scratch,
+        audio_replacement,
+        ..
+    } = wrapper;
+    let mut context = AudioDataContext::from_raw(audio, audio_replacement, audio_scratch);
+    let result = handle_result::<F, _, _>("try_filter_audio", || {
+        F::try_filter_audio(data, &mut context)
+    })
+    .unwrap_or(FilterAudioResult::Pass);
+    let audio = context.as_ptr();
+    filter_audio_output(audio, result, audio_scratch, audio_replacement)
+}
+
+pub unsafe extern "C" fn filter_video<D, F: FilterVideoSource<D>>(
+    data: *mut ::std::os::raw::c_void,
+    frame: *mut obs_source_frame,
+) -> *mut obs_source_frame {
+    let wrapper: &mut DataWrapper<D> = &mut *(data as *mut DataWrapper<D>);
+    let mut async_frame = AsyncFrame::from_raw(frame);
+    handle_unwind::<F, _>("filter_video", || {
+        F::filter_video(&mut wrapper.data, &mut async_frame)
+    });
+    frame
+}
+
+/// Turns the result of a filter into the audio returned to OBS, keeping
+/// replacement audio in `scratch` and `replacement` until the next call.
+pub(super) fn filter_audio_output(
+    audio: *mut obs_audio_data,
+    result: FilterAudioResult,
+    scratch: &mut Vec<Vec<f32>>,
+    replacement: &mut Option<obs_audio_data>,
+) -> *mut obs_audio_data {
+    match result {
+        FilterAudioResult::Pass => audio,
+        FilterAudioResult::Drop => std::ptr::null_mut(),
+        FilterAudioResult::Replace {
+            channels,
+            timestamp,
+        } => {
+            let frames = channels.iter().map(Vec::len).min().unwrap_or(0);
+            *scratch = channels;
+            let mut output = obs_audio_data {
+                data: [std::ptr::null_mut(); 8],
+                frames: frames as u32,
+                timestamp,
+            };
+            for (plane, channel) in output.data.iter_mut().zip(scratch.iter_mut()) {
+                *plane = channel.as_mut_ptr() as *mut u8;
+            }
+            replacement.insert(output)
+        }
+    }
 }
 
 pub unsafe extern "C" fn media_play_pause<D, F: MediaPlayPauseSource<D>>(
@@ -228,14 +565,15 @@ pub unsafe extern "C" fn media_play_pause<D, F: MediaPlayPauseSource<D>>(
     pause: bool,
 ) {
     let wrapper = &mut *(data as *mut DataWrapper<D>);
-    F::play_pause(&mut wrapper.data, pause);
+    handle_unwind::<F, _>("play_pause", || F::play_pause(&mut wrapper.data, pause));
 }
 
 pub unsafe extern "C" fn media_get_state<D, F: MediaGetStateSource<D>>(
     data: *mut ::std::os::raw::c_void,
 ) -> obs_media_state {
     let wrapper = &mut *(data as *mut DataWrapper<D>);
-    F::get_state(&mut wrapper.data).to_native()
+    handle_unwind::<F, _>("get_state", || F::get_state(&mut wrapper.data).to_native())
+        .unwrap_or(obs_media_state_OBS_MEDIA_STATE_ERROR)
 }
 
 macro_rules! impl_media {
@@ -245,7 +583,8 @@ macro_rules! impl_media {
                 data: *mut ::std::os::raw::c_void,
             ) $(-> $ret)? {
                 let wrapper = &mut *(data as *mut DataWrapper<D>);
-                F::$name(&mut wrapper.data)
+                handle_unwind::<F, _>(stringify!($name), || F::$name(&mut wrapper.data))
+                    .unwrap_or_default()
             }
         }
     )*)
@@ -260,9 +599,70 @@ impl_media!(
     get_time => MediaGetTimeSource -> i64
 );
 
+pub unsafe extern "C" fn mouse_click<D, F: MouseClickSource<D>>(
+    data: *mut ::std::os::raw::c_void,
+    event: *const obs_mouse_event,
+    type_: i32,
+    mouse_up: bool,
+    click_count: u32,
+) {
+    let wrapper = &mut *(data as *mut DataWrapper<D>);
+    let event = MouseEvent::from_native(&*event);
+    if let Ok(button) = MouseButton::try_from(type_ as u32) {
+        handle_unwind::<F, _>("mouse_click", || {
+            F::mouse_click(&mut wrapper.data, &event, button, mouse_up, click_count)
+        });
+    }
+}
+
+pub unsafe extern "C" fn mouse_move<D, F: MouseMoveSource<D>>(
+    data: *mut ::std::os::raw::c_void,
+    event: *const obs_mouse_event,
+    mouse_leave: bool,
+) {
+    let wrapper = &mut *(data as *mut DataWrapper<D>);
+    let event = MouseEvent::from_native(&*event);
+    handle_unwind::<F, _>("mouse_move", || {
+        F::mouse_move(&mut wrapper.data, &event, mouse_leave)
+    });
+}
+
+pub unsafe extern "C" fn mouse_wheel<D, F: MouseWheelSource<D>>(
+    data: *mut ::std::os::raw::c_void,
+    event: *const obs_mouse_event,
+    x_delta: i32,
+    y_delta: i32,
+) {
+    let wrapper = &mut *(data as *mut DataWrapper<D>);
+    let event = MouseEvent::from_native(&*event);
+    handle_unwind::<F, _>("mouse_wheel", || {
+        F::mouse_wheel(&mut wrapper.data, &event, x_delta, y_delta)
+    });
+}
+
+pub unsafe extern "C" fn focus<D, F: FocusSource<D>>(
+    data: *mut ::std::os::raw::c_void,
+    focus: bool,
+) {
+    let wrapper = &mut *(data as *mut DataWrapper<D>);
+    handle_unwind::<F, _>("focus", || F::focus(&mut wrapper.data, focus));
+}
+
+pub unsafe extern "C" fn key_click<D, F: KeyClickSource<D>>(
+    data: *mut ::std::os::raw::c_void,
+    event: *const obs_key_event,
+    key_up: bool,
+) {
+    let wrapper = &mut *(data as *mut DataWrapper<D>);
+    let event = KeyEvent::from_native(&*event);
+    handle_unwind::<F, _>("key_click", || {
+        F::key_click(&mut wrapper.data, &event, key_up)
+    });
+}
+
 pub unsafe extern "C" fn get_defaults<D, F: GetDefaultsSource<D>>(settings: *mut obs_data_t) {
     let mut settings = DataObj::from_raw(settings);
-    F::get_defaults(&mut settings);
+    handle_unwind::<F, _>("get_defaults", || F::get_defaults(&mut settings));
     forget(settings);
 }
 
@@ -279,6 +679,51 @@ pub unsafe extern "C" fn hotkey_callback<D>(
     let mut key = Hotkey::from_raw(hotkey, pressed);
 
     if let Some(callback) = hotkey_callbacks.get_mut(&id) {
-        callback(&mut key, data);
+        handle_unwind::<D, _>("hotkey_callback", || callback(&mut key, data));
+    }
+}
+
+pub unsafe extern "C" fn hotkey_pair_callback<D, const N: usize>(
+    data: *mut c_void,
+    id: obs_hotkey_pair_id,
+    hotkey: *mut obs_hotkey_t,
+    pressed: bool,
+) -> bool {
+    let wrapper: &mut DataWrapper<D> = &mut *(data as *mut DataWrapper<D>);
+
+    let data = &mut wrapper.data;
+    let mut key = Hotkey::from_raw(hotkey, pressed);
+
+    match wrapper.hotkey_pair_callbacks.get_mut(&id) {
+        Some(callbacks) => {
+            handle_unwind::<D, _>("hotkey_pair_callback", || callbacks[N](&mut key, data))
+                .unwrap_or(false)
+        }
+        None => false,
     }
 }
+
+pub unsafe extern "C" fn get_property_tags<D>(data: *mut c_void, calldata: *mut calldata_t) {
+    // Generating the properties goes back through `get_properties`, which
+    // takes the wrapper mutably and refreshes the cached tags, so no
+    // reference to it may be held across the call.
+    let wrapper = data as *mut DataWrapper<D>;
+    let properties = obs_source_properties((*wrapper).source);
+    if !properties.is_null() {
+        obs_properties_destroy(properties);
+    }
+
+    let json = {
+        let tags = (*wrapper)
+            .property_tags
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        format!("{}\0", tags.as_deref().unwrap_or("{}"))
+    };
+    calldata_set_data(
+        calldata,
+        "tags\0".as_ptr() as *const c_char,
+        json.as_ptr() as *const c_void,
+        json.len() as size_t,
+    );
+}
@@ -0,0 +1,63 @@
+use bitflags::bitflags;
+use obs_sys::{
+    OBS_SOURCE_ASYNC, OBS_SOURCE_ASYNC_VIDEO, OBS_SOURCE_AUDIO, OBS_SOURCE_CAP_DISABLED,
+    OBS_SOURCE_COMPOSITE, OBS_SOURCE_CONTROLLABLE_MEDIA, OBS_SOURCE_CUSTOM_DRAW,
+    OBS_SOURCE_DEPRECATED, OBS_SOURCE_DO_NOT_DUPLICATE, OBS_SOURCE_DO_NOT_SELF_MONITOR,
+    OBS_SOURCE_FLAG_FORCE_MONO, OBS_SOURCE_INTERACTION, OBS_SOURCE_MONITOR_BY_DEFAULT,
+    OBS_SOURCE_SUBMIX, OBS_SOURCE_VIDEO,
+};
+
+bitflags! {
+    /// Capabilities a source declares through `obs_source_info.output_flags`.
+    ///
+    /// `VIDEO`, `ASYNC_VIDEO`, `AUDIO`, `INTERACTION` and `CONTROLLABLE_MEDIA`
+    /// are also set automatically by [`SourceInfoBuilder::build`](super::SourceInfoBuilder::build)
+    /// when the matching callbacks are enabled, unless opted out of with
+    /// [`SourceInfoBuilder::without_inferred_flags`](super::SourceInfoBuilder::without_inferred_flags).
+    ///
+    /// See [OBS documentation](https://obsproject.com/docs/reference-sources.html#c.obs_source_info.output_flags)
+    pub struct OutputFlags: u32 {
+        /// Source has video.
+        const VIDEO = OBS_SOURCE_VIDEO;
+        /// Source has audio.
+        const AUDIO = OBS_SOURCE_AUDIO;
+        /// Source outputs asynchronous frames instead of rendering them.
+        const ASYNC = OBS_SOURCE_ASYNC;
+        /// Source outputs asynchronous video frames, the same as `ASYNC | VIDEO`.
+        const ASYNC_VIDEO = OBS_SOURCE_ASYNC_VIDEO;
+        /// Source uses custom drawing and does not need a default effect.
+        const CUSTOM_DRAW = OBS_SOURCE_CUSTOM_DRAW;
+        /// Source supports interaction through mouse and keyboard events.
+        const INTERACTION = OBS_SOURCE_INTERACTION;
+        /// Source composites sub-sources, such as scenes or transitions.
+        const COMPOSITE = OBS_SOURCE_COMPOSITE;
+        /// Source should not be fully duplicated when copied.
+        const DO_NOT_DUPLICATE = OBS_SOURCE_DO_NOT_DUPLICATE;
+        /// Source is deprecated and should not be shown to the user.
+        const DEPRECATED = OBS_SOURCE_DEPRECATED;
+        /// Source audio should not be monitored to avoid feedback.
+        const DO_NOT_SELF_MONITOR = OBS_SOURCE_DO_NOT_SELF_MONITOR;
+        /// Source type is disabled and cannot be created.
+        const CAP_DISABLED = OBS_SOURCE_CAP_DISABLED;
+        /// Source audio is monitored by default.
+        const MONITOR_BY_DEFAULT = OBS_SOURCE_MONITOR_BY_DEFAULT;
+        /// Source is used for submixing audio.
+        const SUBMIX = OBS_SOURCE_SUBMIX;
+        /// Source supports media controls.
+        const CONTROLLABLE_MEDIA = OBS_SOURCE_CONTROLLABLE_MEDIA;
+        /// Source renders in linear sRGB space. Only understood by OBS 27 and
+        /// newer, older versions ignore it.
+        const SRGB = 1 << 15;
+    }
+}
+
+bitflags! {
+    /// Per-source settings a user can change, unlike [`OutputFlags`] which
+    /// are fixed by the source type.
+    ///
+    /// See [OBS documentation](https://obsproject.com/docs/reference-sources.html#c.obs_source_set_flags)
+    pub struct SourceFlags: u32 {
+        /// Downmixes the audio of the source to mono.
+        const FORCE_MONO = OBS_SOURCE_FLAG_FORCE_MONO;
+    }
+}
@@ -0,0 +1,214 @@
+use crate::video::{VideoColorspace, VideoFormat};
+use obs_sys::{
+    obs_source_frame, video_format_get_parameters, video_range_type_VIDEO_RANGE_FULL,
+    video_range_type_VIDEO_RANGE_PARTIAL,
+};
+
+use std::marker::PhantomData;
+use std::slice;
+
+/// A video frame handed to OBS by an async video source.
+///
+/// The frame borrows the pixel data of its planes, OBS copies them when the
+/// frame is output or preloaded.
+///
+/// ```ignore
+/// let mut frame = SourceFrame::new(VideoFormat::BGRA, width, height, timestamp);
+/// frame.set_plane(0, &pixels, width * 4);
+/// source.output_video(&frame);
+/// ```
+pub struct SourceFrame<'a> {
+    frame: obs_source_frame,
+    __data: PhantomData<&'a [u8]>,
+}
+
+impl<'a> SourceFrame<'a> {
+    /// Creates a frame without any planes set, using the default colorspace
+    /// in partial range.
+    pub fn new(format: VideoFormat, width: u32, height: u32, timestamp: u64) -> Self {
+        let mut frame = Self {
+            frame: obs_source_frame {
+                width,
+                height,
+                timestamp,
+                format: format.into(),
+                ..Default::default()
+            },
+            __data: PhantomData,
+        };
+        frame.set_colorspace(VideoColorspace::Default, false);
+        frame
+    }
+
+    /// Sets the data of `plane`, `linesize` being the number of bytes between
+    /// the start of two rows.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `data` is too small for the plane.
+    pub fn set_plane(&mut self, plane: usize, data: &'a [u8], linesize: u32) {
+        let format = self.format();
+        assert!(plane < format.planes(), "Plane out of range for format");
+        let required = linesize as usize * format.plane_height(plane, self.frame.height) as usize;
+        assert!(data.len() >= required, "Plane data is too small");
+
+        self.frame.data[plane] = data.as_ptr() as *mut u8;
+        self.frame.linesize[plane] = linesize;
+    }
+
+    /// Sets the color matrix and range of the frame. Only used for YUV formats.
+    pub fn set_colorspace(&mut self, colorspace: VideoColorspace, full_range: bool) {
+        let range = if full_range {
+            video_range_type_VIDEO_RANGE_FULL
+        } else {
+            video_range_type_VIDEO_RANGE_PARTIAL
+        };
+        self.frame.full_range = full_range;
+        if self.format().is_yuv() {
+            unsafe {
+                video_format_get_parameters(
+                    colorspace.into(),
+                    range,
+                    self.frame.color_matrix.as_mut_ptr(),
+                    self.frame.color_range_min.as_mut_ptr(),
+                    self.frame.color_range_max.as_mut_ptr(),
+                );
+            }
+        }
+    }
+
+    /// Sets whether the frame should be flipped vertically.
+    pub fn set_flip(&mut self, flip: bool) {
+        self.frame.flip = flip;
+    }
+
+    pub fn set_timestamp(&mut self, timestamp: u64) {
+        self.frame.timestamp = timestamp;
+    }
+
+    pub fn format(&self) -> VideoFormat {
+        std::convert::TryFrom::try_from(self.frame.format).unwrap_or(VideoFormat::None)
+    }
+
+    pub fn width(&self) -> u32 {
+        self.frame.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.frame.height
+    }
+
+    pub fn timestamp(&self) -> u64 {
+        self.frame.timestamp
+    }
+
+    pub(crate) fn as_ptr(&self) -> *const obs_source_frame {
+        &self.frame
+    }
+}
+
+/// A frame of an async video source passing through a video filter, see
+/// [`FilterVideoSource`](super::traits::FilterVideoSource).
+///
+/// ```ignore
+/// fn filter_video(data: &mut Option<Data>, frame: &mut AsyncFrame) {
+///     if frame.format() == VideoFormat::NV12 {
+///         let luma: Vec<&[u8]> = frame.rows(0).collect();
+///         scan_barcodes(&luma, frame.width(), frame.height());
+///     }
+/// }
+/// ```
+///
+/// The frame is only valid during the callback, so its data must be copied
+/// to be used later, e.g. on a worker thread.
+pub struct AsyncFrame<'a> {
+    frame: *mut obs_source_frame,
+    __frame: PhantomData<&'a mut obs_source_frame>,
+}
+
+impl<'a> AsyncFrame<'a> {
+    pub(crate) unsafe fn from_raw(frame: *mut obs_source_frame) -> Self {
+        Self {
+            frame,
+            __frame: PhantomData,
+        }
+    }
+
+    fn raw(&self) -> &obs_source_frame {
+        unsafe { &*self.frame }
+    }
+
+    pub fn format(&self) -> VideoFormat {
+        std::convert::TryFrom::try_from(self.raw().format).unwrap_or(VideoFormat::None)
+    }
+
+    pub fn width(&self) -> u32 {
+        self.raw().width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.raw().height
+    }
+
+    /// The timestamp of the frame, in nanoseconds.
+    pub fn timestamp(&self) -> u64 {
+        self.raw().timestamp
+    }
+
+    /// Whether the YUV values of the frame use the full range.
+    pub fn full_range(&self) -> bool {
+        self.raw().full_range
+    }
+
+    /// Whether the frame is meant to be shown flipped vertically.
+    pub fn flip(&self) -> bool {
+        self.raw().flip
+    }
+
+    /// The number of bytes between the start of two rows in `plane`.
+    pub fn linesize(&self, plane: usize) -> usize {
+        if plane >= self.format().planes() {
+            return 0;
+        }
+        self.raw().linesize[plane] as usize
+    }
+
+    /// Returns the whole of `plane`, including line padding.
+    pub fn plane(&self, plane: usize) -> Option<&[u8]> {
+        let format = self.format();
+        if plane >= format.planes() {
+            return None;
+        }
+        let raw = self.raw();
+        let ptr = raw.data[plane];
+        if ptr.is_null() {
+            return None;
+        }
+        let len = raw.linesize[plane] as usize * format.plane_height(plane, raw.height) as usize;
+        Some(unsafe { slice::from_raw_parts(ptr, len) })
+    }
+
+    /// Returns the whole of `plane` for changing the frame in place.
+    pub fn plane_mut(&mut self, plane: usize) -> Option<&mut [u8]> {
+        let len = self.plane(plane)?.len();
+        let ptr = self.raw().data[plane];
+        Some(unsafe { slice::from_raw_parts_mut(ptr, len) })
+    }
+
+    /// Returns the visible bytes of a single row of `plane`.
+    pub fn row(&self, plane: usize, row: u32) -> Option<&[u8]> {
+        let format = self.format();
+        if row >= format.plane_height(plane, self.height()) {
+            return None;
+        }
+        let start = self.linesize(plane) * row as usize;
+        let len = format.row_bytes(plane, self.width());
+        self.plane(plane).map(|data| &data[start..start + len])
+    }
+
+    /// Iterates over the visible bytes of each row of `plane`.
+    pub fn rows(&self, plane: usize) -> impl Iterator<Item = &[u8]> + '_ {
+        (0..self.format().plane_height(plane, self.height()))
+            .filter_map(move |row| self.row(plane, row))
+    }
+}
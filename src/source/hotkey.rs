@@ -1,5 +1,15 @@
-use obs_sys::{obs_hotkey_get_id, obs_hotkey_id, obs_hotkey_t};
+use crate::string::ObsString;
+use crate::unwind::handle_unwind;
+use obs_sys::{
+    obs_hotkey_get_id, obs_hotkey_id, obs_hotkey_pair_id, obs_hotkey_pair_register_frontend,
+    obs_hotkey_pair_unregister, obs_hotkey_t,
+};
+use std::ffi::c_void;
 
+/// The hotkey a callback was triggered by.
+///
+/// Callbacks are called both when the hotkey is pressed and when it is
+/// released, which `pressed` tells apart.
 pub struct Hotkey {
     key: *mut obs_hotkey_t,
     pub pressed: bool,
@@ -14,3 +24,83 @@ impl Hotkey {
         unsafe { obs_hotkey_get_id(self.key) }
     }
 }
+
+pub(crate) type HotkeyCallback<D> = Box<dyn FnMut(&mut Hotkey, &mut Option<D>)>;
+
+pub(crate) type HotkeyPairCallback<D> = Box<dyn FnMut(&mut Hotkey, &mut Option<D>) -> bool>;
+
+/// A hotkey pair waiting to be registered once its source has been created.
+pub(crate) struct HotkeyPairRegistration<D> {
+    pub names: [ObsString; 2],
+    pub descriptions: [ObsString; 2],
+    pub callbacks: [HotkeyPairCallback<D>; 2],
+}
+
+type FrontendPairCallback = Box<dyn FnMut(&mut Hotkey) -> bool>;
+
+/// A pair of global hotkeys registered with
+/// [`obs_hotkey_pair_register_frontend`], unregistered when dropped.
+///
+/// Only the hotkey for the current state fires: `func0` while in the first
+/// state and `func1` while in the second. Each callback returns `true` if the
+/// press was consumed and the pair should switch to the other state.
+pub struct HotkeyPair {
+    id: obs_hotkey_pair_id,
+    callbacks: *mut [FrontendPairCallback; 2],
+}
+
+impl HotkeyPair {
+    pub fn register_frontend<F0, F1>(
+        name0: ObsString,
+        description0: ObsString,
+        name1: ObsString,
+        description1: ObsString,
+        func0: F0,
+        func1: F1,
+    ) -> Self
+    where
+        F0: FnMut(&mut Hotkey) -> bool + 'static,
+        F1: FnMut(&mut Hotkey) -> bool + 'static,
+    {
+        let callbacks: *mut [FrontendPairCallback; 2] =
+            Box::into_raw(Box::new([Box::new(func0), Box::new(func1)]));
+        let id = unsafe {
+            obs_hotkey_pair_register_frontend(
+                name0.as_ptr(),
+                description0.as_ptr(),
+                name1.as_ptr(),
+                description1.as_ptr(),
+                Some(frontend_pair_callback::<0>),
+                Some(frontend_pair_callback::<1>),
+                callbacks as *mut c_void,
+                callbacks as *mut c_void,
+            )
+        };
+        Self { id, callbacks }
+    }
+
+    pub fn id(&self) -> obs_hotkey_pair_id {
+        self.id
+    }
+}
+
+impl Drop for HotkeyPair {
+    fn drop(&mut self) {
+        unsafe {
+            obs_hotkey_pair_unregister(self.id);
+            drop(Box::from_raw(self.callbacks));
+        }
+    }
+}
+
+unsafe extern "C" fn frontend_pair_callback<const N: usize>(
+    data: *mut c_void,
+    _id: obs_hotkey_pair_id,
+    hotkey: *mut obs_hotkey_t,
+    pressed: bool,
+) -> bool {
+    let callbacks = &mut *(data as *mut [FrontendPairCallback; 2]);
+    let mut key = Hotkey::from_raw(hotkey, pressed);
+    handle_unwind::<HotkeyPair, _>("hotkey_pair_callback", || callbacks[N](&mut key))
+        .unwrap_or(false)
+}
@@ -0,0 +1,135 @@
+#![allow(non_upper_case_globals)]
+
+use crate::native_enum;
+use bitflags::bitflags;
+use obs_sys::{
+    obs_interaction_flags_INTERACT_ALT_KEY, obs_interaction_flags_INTERACT_CAPS_KEY,
+    obs_interaction_flags_INTERACT_COMMAND_KEY, obs_interaction_flags_INTERACT_CONTROL_KEY,
+    obs_interaction_flags_INTERACT_IS_KEY_PAD, obs_interaction_flags_INTERACT_IS_LEFT,
+    obs_interaction_flags_INTERACT_IS_RIGHT, obs_interaction_flags_INTERACT_MOUSE_LEFT,
+    obs_interaction_flags_INTERACT_MOUSE_MIDDLE, obs_interaction_flags_INTERACT_MOUSE_RIGHT,
+    obs_interaction_flags_INTERACT_NUMLOCK_KEY, obs_interaction_flags_INTERACT_SHIFT_KEY,
+    obs_key_event, obs_mouse_button_type, obs_mouse_button_type_MOUSE_LEFT,
+    obs_mouse_button_type_MOUSE_MIDDLE, obs_mouse_button_type_MOUSE_RIGHT, obs_mouse_event,
+};
+
+use std::ffi::{CStr, CString};
+
+native_enum!(MouseButton, obs_mouse_button_type {
+    Left => MOUSE_LEFT,
+    Middle => MOUSE_MIDDLE,
+    Right => MOUSE_RIGHT
+});
+
+bitflags! {
+    /// Modifier keys and mouse buttons held down during an input event.
+    ///
+    /// See [OBS documentation](https://obsproject.com/docs/reference-sources.html#c.obs_source_info.mouse_click)
+    pub struct InteractionFlags: u32 {
+        const CAPS_KEY = obs_interaction_flags_INTERACT_CAPS_KEY;
+        const SHIFT_KEY = obs_interaction_flags_INTERACT_SHIFT_KEY;
+        const CONTROL_KEY = obs_interaction_flags_INTERACT_CONTROL_KEY;
+        const ALT_KEY = obs_interaction_flags_INTERACT_ALT_KEY;
+        const MOUSE_LEFT = obs_interaction_flags_INTERACT_MOUSE_LEFT;
+        const MOUSE_MIDDLE = obs_interaction_flags_INTERACT_MOUSE_MIDDLE;
+        const MOUSE_RIGHT = obs_interaction_flags_INTERACT_MOUSE_RIGHT;
+        const COMMAND_KEY = obs_interaction_flags_INTERACT_COMMAND_KEY;
+        const NUMLOCK_KEY = obs_interaction_flags_INTERACT_NUMLOCK_KEY;
+        const IS_KEY_PAD = obs_interaction_flags_INTERACT_IS_KEY_PAD;
+        const IS_LEFT = obs_interaction_flags_INTERACT_IS_LEFT;
+        const IS_RIGHT = obs_interaction_flags_INTERACT_IS_RIGHT;
+    }
+}
+
+impl Default for InteractionFlags {
+    fn default() -> Self {
+        Self::empty()
+    }
+}
+
+/// A mouse event sent to an interactive source, with coordinates relative to
+/// the top left corner of the source.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct MouseEvent {
+    pub modifiers: InteractionFlags,
+    pub x: i32,
+    pub y: i32,
+}
+
+impl MouseEvent {
+    pub fn new(x: i32, y: i32) -> Self {
+        Self {
+            modifiers: InteractionFlags::default(),
+            x,
+            y,
+        }
+    }
+
+    pub(crate) fn from_native(event: &obs_mouse_event) -> Self {
+        Self {
+            modifiers: InteractionFlags::from_bits_truncate(event.modifiers),
+            x: event.x,
+            y: event.y,
+        }
+    }
+
+    pub(crate) fn to_native(self) -> obs_mouse_event {
+        obs_mouse_event {
+            modifiers: self.modifiers.bits(),
+            x: self.x,
+            y: self.y,
+        }
+    }
+}
+
+/// A keyboard event sent to an interactive source.
+///
+/// `text` is the text the key produces. The native fields are passed through
+/// to sources that handle platform key codes themselves, such as browser
+/// sources.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct KeyEvent {
+    pub modifiers: InteractionFlags,
+    pub text: Option<String>,
+    pub native_modifiers: u32,
+    pub native_scancode: u32,
+    pub native_vkey: u32,
+}
+
+impl KeyEvent {
+    /// # Safety
+    /// `event.text` must be null or point to a nul-terminated string.
+    pub(crate) unsafe fn from_native(event: &obs_key_event) -> Self {
+        let text = if event.text.is_null() {
+            None
+        } else {
+            Some(CStr::from_ptr(event.text).to_string_lossy().into_owned())
+        };
+        Self {
+            modifiers: InteractionFlags::from_bits_truncate(event.modifiers),
+            text,
+            native_modifiers: event.native_modifiers,
+            native_scancode: event.native_scancode,
+            native_vkey: event.native_vkey,
+        }
+    }
+
+    /// Calls `func` with the native event, which borrows the text of this one.
+    pub(crate) fn with_native<R>(&self, func: impl FnOnce(&obs_key_event) -> R) -> R {
+        let text = self
+            .text
+            .as_deref()
+            .and_then(|text| CString::new(text).ok());
+        let event = obs_key_event {
+            modifiers: self.modifiers.bits(),
+            text: text
+                .as_ref()
+                .map(|text| text.as_ptr() as *mut _)
+                .unwrap_or(std::ptr::null_mut()),
+            native_modifiers: self.native_modifiers,
+            native_scancode: self.native_scancode,
+            native_vkey: self.native_vkey,
+        };
+        func(&event)
+    }
+}
@@ -1,11 +1,16 @@
+use super::SourceContext;
 use obs_sys::{
-    obs_media_state, obs_media_state_OBS_MEDIA_STATE_BUFFERING,
+    calldata_set_data, calldata_t, obs_media_state, obs_media_state_OBS_MEDIA_STATE_BUFFERING,
     obs_media_state_OBS_MEDIA_STATE_ENDED, obs_media_state_OBS_MEDIA_STATE_ERROR,
     obs_media_state_OBS_MEDIA_STATE_NONE, obs_media_state_OBS_MEDIA_STATE_OPENING,
     obs_media_state_OBS_MEDIA_STATE_PAUSED, obs_media_state_OBS_MEDIA_STATE_PLAYING,
-    obs_media_state_OBS_MEDIA_STATE_STOPPED,
+    obs_media_state_OBS_MEDIA_STATE_STOPPED, obs_source_get_signal_handler, obs_source_media_ended,
+    obs_source_media_started, obs_source_t, signal_handler_signal,
 };
 
+use std::ffi::c_void;
+use std::os::raw::c_char;
+
 /// OBS media state
 #[derive(Debug, Eq, PartialEq, Copy, Clone)]
 pub enum MediaState {
@@ -48,3 +53,70 @@ impl MediaState {
         }
     }
 }
+
+/// A change of playback a media source reports with
+/// [`SourceContext::media_notify`].
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum MediaEvent {
+    /// Playback of new media started.
+    Started,
+    /// The media played until its end.
+    Ended,
+    /// Playback resumed.
+    Played,
+    Paused,
+    Stopped,
+    /// Playback started over from the beginning.
+    Restarted,
+    /// Playback moved to the next item of a playlist.
+    Next,
+    /// Playback moved to the previous item of a playlist.
+    Previous,
+}
+
+impl SourceContext {
+    /// Tells OBS that the playback of this source changed by itself, e.g.
+    /// when a file ends or a remote player is paused, so that the media
+    /// controls of the frontend follow it.
+    ///
+    /// Changes made from a media callback must not be reported, as OBS sends
+    /// the signal after calling the callback. This only sends the signal and
+    /// does not call any callback.
+    ///
+    /// See [OBS documentation](https://obsproject.com/docs/reference-sources.html#source-signals)
+    pub fn media_notify(&mut self, event: MediaEvent) {
+        let signal: &[u8] = match event {
+            MediaEvent::Started => return unsafe { obs_source_media_started(self.source) },
+            MediaEvent::Ended => return unsafe { obs_source_media_ended(self.source) },
+            MediaEvent::Played => b"media_play\0",
+            MediaEvent::Paused => b"media_pause\0",
+            MediaEvent::Stopped => b"media_stopped\0",
+            MediaEvent::Restarted => b"media_restart\0",
+            MediaEvent::Next => b"media_next\0",
+            MediaEvent::Previous => b"media_previous\0",
+        };
+
+        // Enough for the single pointer parameter, so nothing is allocated.
+        let mut stack = [0u8; 64];
+        let mut calldata = calldata_t {
+            stack: stack.as_mut_ptr(),
+            size: 0,
+            capacity: stack.len() as _,
+            fixed: true,
+        };
+        let source: *mut obs_source_t = self.source;
+        unsafe {
+            calldata_set_data(
+                &mut calldata,
+                b"source\0".as_ptr() as *const c_char,
+                &source as *const *mut obs_source_t as *const c_void,
+                std::mem::size_of::<*mut obs_source_t>() as _,
+            );
+            signal_handler_signal(
+                obs_source_get_signal_handler(self.source),
+                signal.as_ptr() as *const c_char,
+                &mut calldata,
+            );
+        }
+    }
+}
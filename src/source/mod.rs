@@ -3,31 +3,69 @@
 use paste::item;
 
 pub mod audio;
+pub mod closure;
 pub mod context;
+pub mod dsp;
+pub mod dynamic;
 mod ffi;
-mod hotkey;
+pub mod flags;
+pub mod frame;
+pub mod hotkey;
+pub mod interaction;
 pub mod media;
 pub mod properties;
 pub mod traits;
+pub mod volume;
 
 pub use context::*;
+pub use flags::*;
+pub use frame::*;
+pub use interaction::*;
 pub use media::*;
 pub use properties::*;
 pub use traits::*;
+pub use volume::*;
+
+#[cfg(feature = "derive")]
+pub use obs_wrapper_derive::obs_object;
 
 use obs_sys::{
-    obs_filter_get_target, obs_source_active, obs_source_enabled, obs_source_get_base_height,
-    obs_source_get_base_width, obs_source_get_height, obs_source_get_id, obs_source_get_name,
-    obs_source_get_type, obs_source_get_width, obs_source_info, obs_source_media_ended,
+    obs_filter_get_target, obs_get_source_by_name, obs_icon_type,
+    obs_icon_type_OBS_ICON_TYPE_AUDIO_INPUT, obs_icon_type_OBS_ICON_TYPE_AUDIO_OUTPUT,
+    obs_icon_type_OBS_ICON_TYPE_BROWSER, obs_icon_type_OBS_ICON_TYPE_CAMERA,
+    obs_icon_type_OBS_ICON_TYPE_COLOR, obs_icon_type_OBS_ICON_TYPE_CUSTOM,
+    obs_icon_type_OBS_ICON_TYPE_DESKTOP_CAPTURE, obs_icon_type_OBS_ICON_TYPE_GAME_CAPTURE,
+    obs_icon_type_OBS_ICON_TYPE_IMAGE, obs_icon_type_OBS_ICON_TYPE_MEDIA,
+    obs_icon_type_OBS_ICON_TYPE_SLIDESHOW, obs_icon_type_OBS_ICON_TYPE_TEXT,
+    obs_icon_type_OBS_ICON_TYPE_UNKNOWN, obs_icon_type_OBS_ICON_TYPE_WINDOW_CAPTURE,
+    obs_source_active, obs_source_add_active_child, obs_source_addref, obs_source_async_decoupled,
+    obs_source_async_unbuffered, obs_source_audio_pending, obs_source_create,
+    obs_source_create_private, obs_source_dec_active, obs_source_dec_showing, obs_source_enabled,
+    obs_source_enum_filters, obs_source_enum_proc_t, obs_source_filter_add,
+    obs_source_filter_remove, obs_source_get_audio_timestamp, obs_source_get_balance_value,
+    obs_source_get_base_height, obs_source_get_base_width, obs_source_get_filter_by_name,
+    obs_source_get_flags, obs_source_get_height, obs_source_get_id, obs_source_get_monitoring_type,
+    obs_source_get_name, obs_source_get_private_settings, obs_source_get_ref,
+    obs_source_get_settings, obs_source_get_sync_offset, obs_source_get_type,
+    obs_source_get_type_data, obs_source_get_unversioned_id, obs_source_get_width,
+    obs_source_inc_active, obs_source_inc_showing, obs_source_info, obs_source_media_ended,
     obs_source_media_get_duration, obs_source_media_get_state, obs_source_media_get_time,
     obs_source_media_next, obs_source_media_play_pause, obs_source_media_previous,
     obs_source_media_restart, obs_source_media_set_time, obs_source_media_started,
-    obs_source_media_stop, obs_source_process_filter_begin, obs_source_process_filter_end,
-    obs_source_process_filter_tech_end, obs_source_set_enabled, obs_source_set_name,
+    obs_source_media_stop, obs_source_output_video, obs_source_preload_video,
+    obs_source_process_filter_begin, obs_source_process_filter_end,
+    obs_source_process_filter_tech_end, obs_source_release, obs_source_remove_active_child,
+    obs_source_send_focus, obs_source_send_key_click, obs_source_send_mouse_click,
+    obs_source_send_mouse_move, obs_source_send_mouse_wheel, obs_source_set_async_decoupled,
+    obs_source_set_async_rotation, obs_source_set_async_unbuffered, obs_source_set_balance_value,
+    obs_source_set_enabled, obs_source_set_flags, obs_source_set_monitoring_type,
+    obs_source_set_name, obs_source_set_sync_offset, obs_source_show_preloaded_video,
     obs_source_showing, obs_source_skip_video_filter, obs_source_t, obs_source_type,
     obs_source_type_OBS_SOURCE_TYPE_FILTER, obs_source_type_OBS_SOURCE_TYPE_INPUT,
     obs_source_type_OBS_SOURCE_TYPE_SCENE, obs_source_type_OBS_SOURCE_TYPE_TRANSITION,
-    obs_source_update, OBS_SOURCE_AUDIO, OBS_SOURCE_CONTROLLABLE_MEDIA, OBS_SOURCE_VIDEO,
+    obs_source_update, obs_source_video_render, obs_transition_enable_fixed, obs_transition_fixed,
+    obs_transition_force_stop, obs_transition_get_size, obs_transition_get_time,
+    obs_transition_set_size, obs_transition_swap_begin, obs_transition_swap_end,
 };
 
 use super::{
@@ -36,11 +74,16 @@ use super::{
     },
     string::ObsString,
 };
-use crate::{data::DataObj, wrapper::PtrWrapper};
+use crate::{data::DataObj, native_enum, unwind::handle_unwind, wrapper::PtrWrapper};
+
+use audio::MonitoringType;
 
 use std::{
+    any::Any,
+    convert::TryFrom,
     ffi::{CStr, CString},
     marker::PhantomData,
+    os::raw::c_void,
 };
 
 /// OBS source type
@@ -75,6 +118,23 @@ impl SourceType {
     }
 }
 
+native_enum!(IconType, obs_icon_type {
+    Unknown => OBS_ICON_TYPE_UNKNOWN,
+    Image => OBS_ICON_TYPE_IMAGE,
+    Color => OBS_ICON_TYPE_COLOR,
+    Slideshow => OBS_ICON_TYPE_SLIDESHOW,
+    AudioInput => OBS_ICON_TYPE_AUDIO_INPUT,
+    AudioOutput => OBS_ICON_TYPE_AUDIO_OUTPUT,
+    DesktopCapture => OBS_ICON_TYPE_DESKTOP_CAPTURE,
+    WindowCapture => OBS_ICON_TYPE_WINDOW_CAPTURE,
+    GameCapture => OBS_ICON_TYPE_GAME_CAPTURE,
+    Camera => OBS_ICON_TYPE_CAMERA,
+    Text => OBS_ICON_TYPE_TEXT,
+    Media => OBS_ICON_TYPE_MEDIA,
+    Browser => OBS_ICON_TYPE_BROWSER,
+    Custom => OBS_ICON_TYPE_CUSTOM
+});
+
 /// Context wrapping an OBS source - video / audio elements which are displayed to the screen.
 ///
 /// See [OBS documentation](https://obsproject.com/docs/reference-sources.html#c.obs_source_t)
@@ -83,6 +143,22 @@ pub struct SourceContext {
 }
 
 impl SourceContext {
+    /// Returns the value attached to this source's type with
+    /// [`SourceInfoBuilder::with_type_data`].
+    pub fn type_data(&self) -> Option<&TypeData> {
+        unsafe { TypeData::from_raw(obs_source_get_type_data(self.source)) }
+    }
+
+    /// Returns a [`TransitionContext`] for this source if it is a transition.
+    pub fn as_transition(&self) -> Option<TransitionContext> {
+        match SourceType::from_native(unsafe { obs_source_get_type(self.source) }) {
+            Some(SourceType::TRANSITION) => Some(TransitionContext {
+                source: self.source,
+            }),
+            _ => None,
+        }
+    }
+
     /// Run a function on the next source in the filter chain.
     ///
     /// Note: only works with sources that are filters.
@@ -98,11 +174,36 @@ impl SourceContext {
         }
     }
 
+    pub(crate) fn as_ptr(&self) -> *mut obs_source_t {
+        self.source
+    }
+
     /// Return a unique id for the filter
     pub fn id(&self) -> usize {
         self.source as usize
     }
 
+    /// Returns the base size of the source this filter is applied to, or
+    /// `None` if this source is not a filter.
+    ///
+    /// Filters that do not enable [`GetWidthSource`] and [`GetHeightSource`]
+    /// already take the size of their target, this is for filters that need
+    /// to know it elsewhere, e.g. in `video_render` or when computing a size
+    /// derived from it.
+    pub fn filter_target_size(&self) -> Option<(u32, u32)> {
+        unsafe {
+            let target = obs_filter_get_target(self.source);
+            if target.is_null() {
+                None
+            } else {
+                Some((
+                    obs_source_get_base_width(target),
+                    obs_source_get_base_height(target),
+                ))
+            }
+        }
+    }
+
     pub fn get_base_width(&self) -> u32 {
         unsafe { obs_source_get_base_width(self.source) }
     }
@@ -138,6 +239,20 @@ impl SourceContext {
         }
     }
 
+    /// The id the source type was registered with, without the `_v<version>`
+    /// suffix OBS adds for types registered with
+    /// [`SourceInfoBuilder::with_version`].
+    pub fn unversioned_id(&self) -> Option<&str> {
+        unsafe {
+            let ptr = obs_source_get_unversioned_id(self.source);
+            if ptr.is_null() {
+                None
+            } else {
+                Some(CStr::from_ptr(ptr).to_str().unwrap())
+            }
+        }
+    }
+
     pub fn name(&self) -> Option<&str> {
         unsafe {
             let ptr = obs_source_get_name(self.source);
@@ -164,6 +279,9 @@ impl SourceContext {
         unsafe { obs_source_get_height(self.source) }
     }
 
+    /// Pauses or resumes the source as if the user did, calling its media
+    /// callback. Sources reporting their own changes use
+    /// [`media_notify`](Self::media_notify) instead.
     pub fn media_play_pause(&mut self, pause: bool) {
         unsafe {
             obs_source_media_play_pause(self.source, pause);
@@ -211,19 +329,149 @@ impl SourceContext {
         MediaState::from_native(ret).expect("Invalid media state value")
     }
 
+    /// Same as [`media_notify`](Self::media_notify) with [`MediaEvent::Started`].
     pub fn media_started(&mut self) {
         unsafe {
             obs_source_media_started(self.source);
         }
     }
 
+    /// Same as [`media_notify`](Self::media_notify) with [`MediaEvent::Ended`].
     pub fn media_ended(&mut self) {
         unsafe {
             obs_source_media_ended(self.source);
         }
     }
 
-    /// Skips the video filter if it's invalid
+    /// Returns the current settings of the source, for reading them outside of
+    /// `update`, e.g. from a hotkey callback.
+    ///
+    /// See [OBS documentation](https://obsproject.com/docs/reference-sources.html#c.obs_source_get_settings)
+    pub fn settings(&self) -> DataObj<'_> {
+        unsafe { DataObj::from_raw(obs_source_get_settings(self.source)) }
+    }
+
+    /// Returns the private settings of the source, which are saved with the
+    /// source but never shown to the user in its properties.
+    ///
+    /// See [OBS documentation](https://obsproject.com/docs/reference-sources.html#c.obs_source_get_private_settings)
+    pub fn private_settings(&self) -> DataObj<'_> {
+        unsafe { DataObj::from_raw(obs_source_get_private_settings(self.source)) }
+    }
+
+    /// Sets whether async video frames are shown as soon as they arrive instead
+    /// of being buffered and synced to their timestamps, trading smoothness for
+    /// latency.
+    ///
+    /// See [OBS documentation](https://obsproject.com/docs/reference-sources.html#c.obs_source_set_async_unbuffered)
+    pub fn set_async_unbuffered(&mut self, unbuffered: bool) {
+        unsafe { obs_source_set_async_unbuffered(self.source, unbuffered) }
+    }
+
+    pub fn async_unbuffered(&self) -> bool {
+        unsafe { obs_source_async_unbuffered(self.source) }
+    }
+
+    /// Sets whether audio plays independently of video instead of being synced
+    /// to it. Only has an effect on unbuffered sources.
+    ///
+    /// See [OBS documentation](https://obsproject.com/docs/reference-sources.html#c.obs_source_set_async_decoupled)
+    pub fn set_async_decoupled(&mut self, decoupled: bool) {
+        unsafe { obs_source_set_async_decoupled(self.source, decoupled) }
+    }
+
+    pub fn async_decoupled(&self) -> bool {
+        unsafe { obs_source_async_decoupled(self.source) }
+    }
+
+    /// Rotates async video frames by `degrees`, which should be a multiple of 90.
+    pub fn set_async_rotation(&mut self, degrees: i32) {
+        unsafe { obs_source_set_async_rotation(self.source, degrees as std::os::raw::c_long) }
+    }
+
+    /// Sets whether the audio of the source is sent to the monitoring device,
+    /// the output, or both.
+    ///
+    /// See [OBS documentation](https://obsproject.com/docs/reference-sources.html#c.obs_source_set_monitoring_type)
+    pub fn set_monitoring_type(&mut self, monitoring_type: MonitoringType) {
+        unsafe { obs_source_set_monitoring_type(self.source, monitoring_type.into()) }
+    }
+
+    /// Returns the monitoring type of the source, or `None` if OBS reported
+    /// one this crate does not know about.
+    pub fn monitoring_type(&self) -> Option<MonitoringType> {
+        MonitoringType::try_from(unsafe { obs_source_get_monitoring_type(self.source) }).ok()
+    }
+
+    /// Delays the audio of the source by `offset` nanoseconds, which may be
+    /// negative, to correct its sync with video.
+    ///
+    /// See [OBS documentation](https://obsproject.com/docs/reference-sources.html#c.obs_source_set_sync_offset)
+    pub fn set_sync_offset(&mut self, offset: i64) {
+        unsafe { obs_source_set_sync_offset(self.source, offset) }
+    }
+
+    pub fn sync_offset(&self) -> i64 {
+        unsafe { obs_source_get_sync_offset(self.source) }
+    }
+
+    /// Returns the flags set on the source. Flags this crate does not know
+    /// about are left out.
+    pub fn flags(&self) -> SourceFlags {
+        SourceFlags::from_bits_truncate(unsafe { obs_source_get_flags(self.source) })
+    }
+
+    /// Replaces the flags set on the source, keeping any this crate does not
+    /// know about.
+    ///
+    /// See [OBS documentation](https://obsproject.com/docs/reference-sources.html#c.obs_source_set_flags)
+    pub fn set_flags(&mut self, flags: SourceFlags) {
+        unsafe {
+            let unknown = obs_source_get_flags(self.source) & !SourceFlags::all().bits();
+            obs_source_set_flags(self.source, unknown | flags.bits())
+        }
+    }
+
+    /// Sets the stereo balance of the source, from `0.0` (left) through `0.5`
+    /// (center) to `1.0` (right).
+    ///
+    /// See [OBS documentation](https://obsproject.com/docs/reference-sources.html#c.obs_source_set_balance_value)
+    pub fn set_balance_value(&mut self, balance: f32) {
+        unsafe { obs_source_set_balance_value(self.source, balance) }
+    }
+
+    pub fn balance_value(&self) -> f32 {
+        unsafe { obs_source_get_balance_value(self.source) }
+    }
+
+    /// Outputs a frame of an async video source, see
+    /// [`OutputFlags::ASYNC_VIDEO`].
+    ///
+    /// See [OBS documentation](https://obsproject.com/docs/reference-sources.html#c.obs_source_output_video)
+    pub fn output_video(&mut self, frame: &SourceFrame) {
+        unsafe { obs_source_output_video(self.source, frame.as_ptr()) }
+    }
+
+    /// Buffers `frame` without showing it, so that it can be shown without
+    /// delay by [`show_preloaded_video`](Self::show_preloaded_video) once the
+    /// source activates.
+    ///
+    /// See [OBS documentation](https://obsproject.com/docs/reference-sources.html#c.obs_source_preload_video)
+    pub fn preload_video(&mut self, frame: &SourceFrame) {
+        unsafe { obs_source_preload_video(self.source, frame.as_ptr()) }
+    }
+
+    /// Shows the frame buffered with [`preload_video`](Self::preload_video).
+    pub fn show_preloaded_video(&mut self) {
+        unsafe { obs_source_show_preloaded_video(self.source) }
+    }
+
+    /// Renders the filter target directly instead of this filter. Call this
+    /// from `video_render` when the filter has nothing to do, e.g. when its
+    /// parameters are the identity, to avoid rendering to an intermediate
+    /// texture.
+    ///
+    /// See [OBS documentation](https://obsproject.com/docs/reference-sources.html#c.obs_source_skip_video_filter)
     pub fn skip_video_filter(&mut self) {
         unsafe {
             obs_source_skip_video_filter(self.source);
@@ -287,17 +535,439 @@ impl SourceContext {
         }
     }
 
+    /// Renders the source, e.g. a child source drawn by a composite source in
+    /// its own `video_render`.
+    ///
+    /// See [OBS documentation](https://obsproject.com/docs/reference-sources.html#c.obs_source_video_render)
+    pub fn video_render(&mut self, _render: &mut VideoRenderContext) {
+        unsafe { obs_source_video_render(self.source) }
+    }
+
+    /// Returns whether the source has no audio ready for the current mix,
+    /// used by composite sources in `audio_render` to skip such children.
+    pub fn audio_pending(&self, _audio: &AudioThreadContext) -> bool {
+        unsafe { obs_source_audio_pending(self.source) }
+    }
+
+    /// Returns the timestamp of the audio the source has ready for the
+    /// current mix.
+    pub fn audio_timestamp(&self, _audio: &AudioThreadContext) -> u64 {
+        unsafe { obs_source_get_audio_timestamp(self.source) }
+    }
+
+    /// Marks `child` as rendered by this source, so it becomes active and
+    /// showing along with it. Returns `false` if it could not be added, e.g.
+    /// because it would create a cycle.
+    ///
+    /// Every call must be balanced by [`remove_active_child`](Self::remove_active_child)
+    /// before the child is released.
+    ///
+    /// See [OBS documentation](https://obsproject.com/docs/reference-sources.html#c.obs_source_add_active_child)
+    pub fn add_active_child(&mut self, child: &SourceContext) -> bool {
+        unsafe { obs_source_add_active_child(self.source, child.source) }
+    }
+
+    pub fn remove_active_child(&mut self, child: &SourceContext) {
+        unsafe { obs_source_remove_active_child(self.source, child.source) }
+    }
+
+    /// Marks the source as showing until the guard is dropped, for sources
+    /// displayed outside of the scene graph, e.g. in a projector or by a
+    /// composite source without [`add_active_child`](Self::add_active_child).
+    /// Returns `None` if the source is being destroyed.
+    ///
+    /// See [OBS documentation](https://obsproject.com/docs/reference-sources.html#c.obs_source_inc_showing)
+    pub fn show(&self) -> Option<ShowingGuard> {
+        let source = self.to_ref()?;
+        unsafe { obs_source_inc_showing(source.as_ptr()) }
+        Some(ShowingGuard { source })
+    }
+
+    /// Marks the source as active, i.e. shown on the program output, until
+    /// the guard is dropped. Returns `None` if the source is being destroyed.
+    ///
+    /// See [OBS documentation](https://obsproject.com/docs/reference-sources.html#c.obs_source_inc_active)
+    pub fn activate(&self) -> Option<ActiveGuard> {
+        let source = self.to_ref()?;
+        unsafe { obs_source_inc_active(source.as_ptr()) }
+        Some(ActiveGuard { source })
+    }
+
     /// Update the source settings based on a settings context.
     pub fn update_source_settings(&mut self, settings: &mut DataObj) {
         unsafe {
             obs_source_update(self.source, settings.as_ptr_mut());
         }
     }
+
+    /// Sends a mouse button press or release to the source, which must have
+    /// [`OutputFlags::INTERACTION`] set to handle it.
+    ///
+    /// See [OBS documentation](https://obsproject.com/docs/reference-sources.html#c.obs_source_send_mouse_click)
+    pub fn send_mouse_click(
+        &mut self,
+        event: &MouseEvent,
+        button: MouseButton,
+        mouse_up: bool,
+        click_count: u32,
+    ) {
+        let event = event.to_native();
+        let button: obs_sys::obs_mouse_button_type = button.into();
+        unsafe {
+            obs_source_send_mouse_click(self.source, &event, button as i32, mouse_up, click_count)
+        }
+    }
+
+    /// Sends a mouse move to the source, `mouse_leave` being set when the
+    /// mouse leaves the source.
+    pub fn send_mouse_move(&mut self, event: &MouseEvent, mouse_leave: bool) {
+        let event = event.to_native();
+        unsafe { obs_source_send_mouse_move(self.source, &event, mouse_leave) }
+    }
+
+    /// Sends a scroll of the mouse wheel to the source.
+    pub fn send_mouse_wheel(&mut self, event: &MouseEvent, x_delta: i32, y_delta: i32) {
+        let event = event.to_native();
+        unsafe { obs_source_send_mouse_wheel(self.source, &event, x_delta, y_delta) }
+    }
+
+    /// Sends a key press or release to the source.
+    pub fn send_key_click(&mut self, event: &KeyEvent, key_up: bool) {
+        let source = self.source;
+        event.with_native(|event| unsafe { obs_source_send_key_click(source, event, key_up) })
+    }
+
+    /// Tells the source that it gained or lost keyboard focus.
+    pub fn send_focus(&mut self, focus: bool) {
+        unsafe { obs_source_send_focus(self.source, focus) }
+    }
+
+    /// Returns a new reference to this source, keeping it alive for as long as
+    /// the reference exists.
+    pub fn to_ref(&self) -> Option<SourceRef> {
+        unsafe { SourceRef::from_raw(obs_source_get_ref(self.source)) }
+    }
+
+    /// Appends `filter` to the filter chain of this source.
+    ///
+    /// See [OBS documentation](https://obsproject.com/docs/reference-sources.html#c.obs_source_filter_add)
+    pub fn filter_add(&mut self, filter: &SourceContext) {
+        unsafe { obs_source_filter_add(self.source, filter.source) }
+    }
+
+    /// Removes `filter` from the filter chain of this source.
+    pub fn filter_remove(&mut self, filter: &SourceContext) {
+        unsafe { obs_source_filter_remove(self.source, filter.source) }
+    }
+
+    /// Returns the number of filters attached to this source.
+    pub fn filter_count(&self) -> usize {
+        let mut count = 0;
+        self.enum_filters(|_| count += 1);
+        count
+    }
+
+    /// Returns the filter of this source called `name`.
+    ///
+    /// See [OBS documentation](https://obsproject.com/docs/reference-sources.html#c.obs_source_get_filter_by_name)
+    pub fn filter_by_name(&self, name: &str) -> Option<SourceRef> {
+        let name = CString::new(name).ok()?;
+        unsafe { SourceRef::from_raw(obs_source_get_filter_by_name(self.source, name.as_ptr())) }
+    }
+
+    /// Calls `func` with each filter of this source, in the order they are
+    /// applied.
+    ///
+    /// See [OBS documentation](https://obsproject.com/docs/reference-sources.html#c.obs_source_enum_filters)
+    pub fn enum_filters<F: FnMut(&mut SourceContext)>(&self, mut func: F) {
+        let mut func: &mut dyn FnMut(&mut SourceContext) = &mut func;
+        unsafe {
+            obs_source_enum_filters(
+                self.source,
+                Some(enum_sources_callback),
+                &mut func as *mut _ as *mut c_void,
+            );
+        }
+    }
+}
+
+unsafe extern "C" fn enum_sources_callback(
+    _parent: *mut obs_source_t,
+    child: *mut obs_source_t,
+    param: *mut c_void,
+) {
+    let func = &mut *(param as *mut &mut dyn FnMut(&mut SourceContext));
+    let mut context = SourceContext { source: child };
+    handle_unwind::<SourceContext, _>("enum_sources", || func(&mut context));
+}
+
+/// An owned reference to an OBS source, released when dropped.
+///
+/// Dereferences to [`SourceContext`], so every operation on a source is
+/// available on it.
+pub struct SourceRef {
+    context: SourceContext,
+}
+
+impl SourceRef {
+    /// Takes ownership of a reference to `source`, returning `None` if it is
+    /// null.
+    ///
+    /// # Safety
+    /// `source` must be null or a source pointer whose reference is owned by
+    /// the caller.
+    pub unsafe fn from_raw(source: *mut obs_source_t) -> Option<Self> {
+        if source.is_null() {
+            None
+        } else {
+            Some(Self {
+                context: SourceContext { source },
+            })
+        }
+    }
+
+    /// Looks up a source by its name.
+    ///
+    /// See [OBS documentation](https://obsproject.com/docs/reference-core.html#c.obs_get_source_by_name)
+    pub(crate) fn by_name(name: &str) -> Option<Self> {
+        let name = CString::new(name).ok()?;
+        unsafe { Self::from_raw(obs_get_source_by_name(name.as_ptr())) }
+    }
+
+    /// Creates a source of the type `id`, which is listed in the frontend and
+    /// saved with the scene collection once added to a scene.
+    ///
+    /// See [OBS documentation](https://obsproject.com/docs/reference-sources.html#c.obs_source_create)
+    pub fn create(
+        id: impl Into<ObsString>,
+        name: impl Into<ObsString>,
+        settings: Option<&DataObj>,
+    ) -> Option<Self> {
+        let id = id.into();
+        let name = name.into();
+        let settings = settings.map_or(std::ptr::null_mut(), |s| s.as_ptr() as *mut _);
+        unsafe {
+            Self::from_raw(obs_source_create(
+                id.as_ptr(),
+                name.as_ptr(),
+                settings,
+                std::ptr::null_mut(),
+            ))
+        }
+    }
+
+    /// Creates a private source of the type `id`, which is not listed in the
+    /// frontend nor saved. Useful for helper sources rendered by a plugin
+    /// itself, e.g. an internal image or text source. The source is destroyed
+    /// when the last reference to it is dropped.
+    ///
+    /// See [OBS documentation](https://obsproject.com/docs/reference-sources.html#c.obs_source_create_private)
+    pub fn create_private(
+        id: impl Into<ObsString>,
+        name: impl Into<ObsString>,
+        settings: Option<&DataObj>,
+    ) -> Option<Self> {
+        let id = id.into();
+        let name = name.into();
+        let settings = settings.map_or(std::ptr::null_mut(), |s| s.as_ptr() as *mut _);
+        unsafe {
+            Self::from_raw(obs_source_create_private(
+                id.as_ptr(),
+                name.as_ptr(),
+                settings,
+            ))
+        }
+    }
+
+    pub fn as_ptr(&self) -> *mut obs_source_t {
+        self.context.source
+    }
+}
+
+impl Clone for SourceRef {
+    fn clone(&self) -> Self {
+        unsafe { obs_source_addref(self.context.source) }
+        Self {
+            context: SourceContext {
+                source: self.context.source,
+            },
+        }
+    }
+}
+
+impl std::ops::Deref for SourceRef {
+    type Target = SourceContext;
+
+    fn deref(&self) -> &SourceContext {
+        &self.context
+    }
+}
+
+impl std::ops::DerefMut for SourceRef {
+    fn deref_mut(&mut self) -> &mut SourceContext {
+        &mut self.context
+    }
+}
+
+impl Drop for SourceRef {
+    fn drop(&mut self) {
+        unsafe { obs_source_release(self.context.source) }
+    }
+}
+
+/// Keeps a source showing while held, see [`SourceContext::show`].
+#[must_use = "The source stops showing when the guard is dropped"]
+pub struct ShowingGuard {
+    source: SourceRef,
+}
+
+impl ShowingGuard {
+    pub fn source(&self) -> &SourceRef {
+        &self.source
+    }
+}
+
+impl Drop for ShowingGuard {
+    fn drop(&mut self) {
+        unsafe { obs_source_dec_showing(self.source.as_ptr()) }
+    }
+}
+
+/// Keeps a source active while held, see [`SourceContext::activate`].
+#[must_use = "The source becomes inactive when the guard is dropped"]
+pub struct ActiveGuard {
+    source: SourceRef,
+}
+
+impl ActiveGuard {
+    pub fn source(&self) -> &SourceRef {
+        &self.source
+    }
+}
+
+impl Drop for ActiveGuard {
+    fn drop(&mut self) {
+        unsafe { obs_source_dec_active(self.source.as_ptr()) }
+    }
+}
+
+/// Context wrapping an OBS transition source.
+///
+/// See [OBS documentation](https://obsproject.com/docs/reference-sources.html#transitions)
+pub struct TransitionContext {
+    source: *mut obs_source_t,
+}
+
+impl TransitionContext {
+    /// Progress of the running transition, from `0.0` to `1.0`.
+    pub fn time(&self) -> f32 {
+        unsafe { obs_transition_get_time(self.source) }
+    }
+
+    /// Sets the size the transition renders its sources at.
+    pub fn set_size(&mut self, cx: u32, cy: u32) {
+        unsafe { obs_transition_set_size(self.source, cx, cy) }
+    }
+
+    /// Returns the size the transition renders its sources at.
+    pub fn size(&self) -> (u32, u32) {
+        let mut cx = 0;
+        let mut cy = 0;
+        unsafe { obs_transition_get_size(self.source, &mut cx, &mut cy) };
+        (cx, cy)
+    }
+
+    /// Makes the transition run for a fixed `duration_ms`, ignoring the
+    /// duration chosen by the user. Used by transitions such as stingers whose
+    /// length is decided by their media.
+    pub fn enable_fixed(&mut self, enable: bool, duration_ms: u32) {
+        unsafe { obs_transition_enable_fixed(self.source, enable, duration_ms) }
+    }
+
+    /// Returns whether the transition runs for a fixed duration.
+    pub fn is_fixed(&self) -> bool {
+        unsafe { obs_transition_fixed(self.source) }
+    }
+
+    /// Immediately ends the running transition.
+    pub fn force_stop(&mut self) {
+        unsafe { obs_transition_force_stop(self.source) }
+    }
+
+    /// Starts moving the state of `source` into this transition, so the
+    /// transition can be replaced without interrupting what is shown.
+    ///
+    /// Must be followed by [`swap_end`](Self::swap_end) with the same source.
+    ///
+    /// See [OBS documentation](https://obsproject.com/docs/reference-sources.html#c.obs_transition_swap_begin)
+    pub fn swap_begin(&mut self, source: &mut TransitionContext) {
+        unsafe { obs_transition_swap_begin(self.source, source.source) }
+    }
+
+    /// Finishes a swap started with [`swap_begin`](Self::swap_begin).
+    pub fn swap_end(&mut self, source: &mut TransitionContext) {
+        unsafe { obs_transition_swap_end(self.source, source.source) }
+    }
+}
+
+/// Reports the children a source is currently rendering to OBS, from
+/// [`EnumActiveSource`]. OBS uses this to track which sources are active and
+/// showing.
+pub struct EnumActiveContext {
+    parent: *mut obs_source_t,
+    callback: obs_source_enum_proc_t,
+    param: *mut c_void,
+}
+
+impl EnumActiveContext {
+    pub(crate) unsafe fn from_raw(
+        parent: *mut obs_source_t,
+        callback: obs_source_enum_proc_t,
+        param: *mut c_void,
+    ) -> Self {
+        Self {
+            parent,
+            callback,
+            param,
+        }
+    }
+
+    /// Reports `child` as an active child of the source.
+    pub fn enum_child(&self, child: &SourceContext) {
+        if let Some(callback) = self.callback {
+            unsafe { callback(self.parent, child.source, self.param) }
+        }
+    }
 }
 
-pub struct EnumActiveContext {}
+/// Reports every child a source holds to OBS, from [`EnumAllSource`], whether
+/// or not it is currently rendered.
+pub struct EnumAllContext {
+    parent: *mut obs_source_t,
+    callback: obs_source_enum_proc_t,
+    param: *mut c_void,
+}
 
-pub struct EnumAllContext {}
+impl EnumAllContext {
+    pub(crate) unsafe fn from_raw(
+        parent: *mut obs_source_t,
+        callback: obs_source_enum_proc_t,
+        param: *mut c_void,
+    ) -> Self {
+        Self {
+            parent,
+            callback,
+            param,
+        }
+    }
+
+    /// Reports `child` as a child of the source.
+    pub fn enum_child(&self, child: &SourceContext) {
+        if let Some(callback) = self.callback {
+            unsafe { callback(self.parent, child.source, self.param) }
+        }
+    }
+}
 
 pub struct SourceInfo {
     info: Box<obs_source_info>,
@@ -311,6 +981,32 @@ impl SourceInfo {
     }
 }
 
+/// A value shared by every source of one registered type, such as shared
+/// configuration or a preloaded effect.
+///
+/// Attached with [`SourceInfoBuilder::with_type_data`] and freed by OBS when
+/// it shuts down.
+pub struct TypeData {
+    value: Box<dyn Any + Send + Sync>,
+}
+
+impl TypeData {
+    pub(crate) fn new<T: Any + Send + Sync>(value: T) -> Self {
+        Self {
+            value: Box::new(value),
+        }
+    }
+
+    pub(crate) unsafe fn from_raw<'a>(raw: *mut c_void) -> Option<&'a TypeData> {
+        (raw as *const TypeData).as_ref()
+    }
+
+    /// Returns the value if it is of type `T`.
+    pub fn downcast_ref<T: Any>(&self) -> Option<&T> {
+        self.value.downcast_ref()
+    }
+}
+
 /// The SourceInfoBuilder that handles creating the [SourceInfo](https://obsproject.com/docs/reference-sources.html#c.obs_source_info) object.
 ///
 /// For each trait that is implemented for the Source, it needs to be enabled using this builder.
@@ -329,6 +1025,7 @@ pub struct SourceInfoBuilder<T: Sourceable, D> {
     __source: PhantomData<T>,
     __data: PhantomData<D>,
     info: obs_source_info,
+    inferred_flags: OutputFlags,
 }
 
 impl<T: Sourceable, D> SourceInfoBuilder<T, D> {
@@ -337,35 +1034,207 @@ impl<T: Sourceable, D> SourceInfoBuilder<T, D> {
             __source: PhantomData,
             __data: PhantomData,
             info: obs_source_info {
-                id: T::get_id().as_ptr(),
+                id: T::get_id().as_cached_ptr(),
                 type_: T::get_type().to_native(),
                 create: Some(ffi::create_default_data::<D>),
                 destroy: Some(ffi::destroy::<D>),
                 type_data: std::ptr::null_mut(),
                 ..Default::default()
             },
+            inferred_flags: OutputFlags::all(),
+        }
+    }
+
+    /// Declares additional [`OutputFlags`] for the source, on top of those
+    /// inferred from the enabled callbacks.
+    ///
+    /// ```rs
+    /// let source = load_context
+    ///  .create_source_builder::<Overlay, Data>()
+    ///  .with_output_flags(OutputFlags::CUSTOM_DRAW | OutputFlags::DO_NOT_DUPLICATE)
+    ///  .build();
+    /// ```
+    pub fn with_output_flags(mut self, flags: OutputFlags) -> Self {
+        self.info.output_flags |= flags.bits();
+        self
+    }
+
+    /// Stops [`build`](Self::build) from setting `flags` automatically, e.g.
+    /// `OutputFlags::CONTROLLABLE_MEDIA` for a source implementing media
+    /// callbacks without wanting the frontend to show media controls for it.
+    /// Flags passed to [`with_output_flags`](Self::with_output_flags) are
+    /// still set.
+    pub fn without_inferred_flags(mut self, flags: OutputFlags) -> Self {
+        self.inferred_flags.remove(flags);
+        self
+    }
+
+    /// Attaches `value` to this source type. It can be retrieved from
+    /// [`SourceContext::type_data`], [`CreatableSourceContext::type_data`] and
+    /// [`GetNameTypeDataSource`].
+    ///
+    /// ```rs
+    /// let source = load_context
+    ///  .create_source_builder::<LutFilter, Data>()
+    ///  .with_type_data(LutVariant::Warm)
+    ///  .enable_get_name_type_data()
+    ///  .build();
+    /// ```
+    pub fn with_type_data<TD: Any + Send + Sync>(mut self, value: TD) -> Self {
+        if !self.info.type_data.is_null() {
+            unsafe { ffi::free_type_data(self.info.type_data) };
         }
+        self.info.type_data = Box::into_raw(Box::new(TypeData::new(value))) as *mut c_void;
+        self.info.free_type_data = Some(ffi::free_type_data);
+        self
+    }
+
+    /// Sets the version of the source type.
+    ///
+    /// OBS registers versioned types as `<id>_v<version>` while keeping
+    /// [`Sourceable::get_id`] as the unversioned id, so bumping the version lets
+    /// a source change its settings in a breaking way: scene collections saved
+    /// with an older version keep loading the older registration, as long as
+    /// it is still registered.
+    ///
+    /// See [OBS documentation](https://obsproject.com/docs/reference-sources.html#c.obs_source_info.version)
+    pub fn with_version(mut self, version: u32) -> Self {
+        self.info.version = version;
+        self
+    }
+
+    /// Sets the icon shown next to the source in the Add Source menu.
+    ///
+    /// See [OBS documentation](https://obsproject.com/docs/reference-sources.html#c.obs_source_info.icon_type)
+    pub fn with_icon_type(mut self, icon_type: IconType) -> Self {
+        self.info.icon_type = icon_type.into();
+        self
     }
 
     pub fn build(mut self) -> SourceInfo {
+        let mut flags = OutputFlags::empty();
+
         if self.info.video_render.is_some() {
-            self.info.output_flags |= OBS_SOURCE_VIDEO;
+            flags |= OutputFlags::VIDEO;
         }
 
         if self.info.audio_render.is_some() || self.info.filter_audio.is_some() {
-            self.info.output_flags |= OBS_SOURCE_AUDIO;
+            flags |= OutputFlags::AUDIO;
         }
 
-        if self.info.media_get_state.is_some() || self.info.media_play_pause.is_some() {
-            self.info.output_flags |= OBS_SOURCE_CONTROLLABLE_MEDIA;
+        if self.info.filter_video.is_some() {
+            flags |= OutputFlags::ASYNC_VIDEO;
         }
 
+        let info = &self.info;
+        let media_callbacks = [
+            info.media_play_pause.is_some(),
+            info.media_restart.is_some(),
+            info.media_stop.is_some(),
+            info.media_next.is_some(),
+            info.media_previous.is_some(),
+            info.media_get_duration.is_some(),
+            info.media_get_time.is_some(),
+            info.media_set_time.is_some(),
+            info.media_get_state.is_some(),
+        ];
+        if media_callbacks.iter().any(|&enabled| enabled) {
+            flags |= OutputFlags::CONTROLLABLE_MEDIA;
+        }
+
+        let interaction_callbacks = [
+            info.mouse_click.is_some(),
+            info.mouse_move.is_some(),
+            info.mouse_wheel.is_some(),
+            info.focus.is_some(),
+            info.key_click.is_some(),
+        ];
+        if interaction_callbacks.iter().any(|&enabled| enabled) {
+            flags |= OutputFlags::INTERACTION;
+        }
+
+        self.info.output_flags |= (flags & self.inferred_flags).bits();
+
         SourceInfo {
             info: Box::new(self.info),
         }
     }
 }
 
+impl<D, T: Sourceable + TransitionVideoRenderSource<D>> SourceInfoBuilder<T, D> {
+    /// Enables [`TransitionVideoRenderSource`], used in place of
+    /// [`enable_video_render`](Self::enable_video_render) for transitions.
+    pub fn enable_transition_video_render(mut self) -> Self {
+        self.info.video_render = Some(ffi::transition_video_render::<D, T>);
+        self
+    }
+}
+
+impl<D, T: Sourceable + TransitionAudioRenderSource<D>> SourceInfoBuilder<T, D> {
+    /// Enables [`TransitionAudioRenderSource`], used in place of
+    /// [`enable_audio_render`](Self::enable_audio_render) for transitions.
+    pub fn enable_transition_audio_render(mut self) -> Self {
+        self.info.audio_render = Some(ffi::transition_audio_render::<D, T>);
+        self
+    }
+}
+
+impl<D, T: Sourceable + TryCreatableSource<D>> SourceInfoBuilder<T, D> {
+    /// Enables [`TryCreatableSource`], used in place of [`enable_create`](Self::enable_create).
+    pub fn enable_try_create(mut self) -> Self {
+        self.info.create = Some(ffi::try_create::<D, T>);
+        self
+    }
+}
+
+impl<D, T: Sourceable + GetNameTypeDataSource<D>> SourceInfoBuilder<T, D> {
+    /// Enables [`GetNameTypeDataSource`], used in place of [`enable_get_name`](Self::enable_get_name).
+    pub fn enable_get_name_type_data(mut self) -> Self {
+        self.info.get_name = Some(ffi::get_name_type_data::<D, T>);
+        self
+    }
+}
+
+impl<D, T: Sourceable + TryUpdateSource<D>> SourceInfoBuilder<T, D> {
+    /// Enables [`TryUpdateSource`], used in place of [`enable_update`](Self::enable_update).
+    pub fn enable_try_update(mut self) -> Self {
+        self.info.update = Some(ffi::try_update::<D, T>);
+        self
+    }
+}
+
+impl<D, T: Sourceable + TryVideoRenderSource<D>> SourceInfoBuilder<T, D> {
+    /// Enables [`TryVideoRenderSource`], used in place of [`enable_video_render`](Self::enable_video_render).
+    pub fn enable_try_video_render(mut self) -> Self {
+        self.info.video_render = Some(ffi::try_video_render::<D, T>);
+        self
+    }
+}
+
+impl<D, T: Sourceable + TryGetPropertiesSource<D>> SourceInfoBuilder<T, D> {
+    /// Enables [`TryGetPropertiesSource`], used in place of [`enable_get_properties`](Self::enable_get_properties).
+    pub fn enable_try_get_properties(mut self) -> Self {
+        self.info.get_properties = Some(ffi::try_get_properties::<D, T>);
+        self
+    }
+}
+
+impl<D, T: Sourceable + TryVideoTickSource<D>> SourceInfoBuilder<T, D> {
+    /// Enables [`TryVideoTickSource`], used in place of [`enable_video_tick`](Self::enable_video_tick).
+    pub fn enable_try_video_tick(mut self) -> Self {
+        self.info.video_tick = Some(ffi::try_video_tick::<D, T>);
+        self
+    }
+}
+
+impl<D, T: Sourceable + TryFilterAudioSource<D>> SourceInfoBuilder<T, D> {
+    /// Enables [`TryFilterAudioSource`], used in place of [`enable_filter_audio`](Self::enable_filter_audio).
+    pub fn enable_try_filter_audio(mut self) -> Self {
+        self.info.filter_audio = Some(ffi::try_filter_audio::<D, T>);
+        self
+    }
+}
+
 macro_rules! impl_source_builder {
     ($($f:ident => $t:ident)*) => ($(
         item! {
@@ -396,6 +1265,7 @@ impl_source_builder! {
     transition_stop => TransitionStopSource
     video_tick => VideoTickSource
     filter_audio => FilterAudioSource
+    filter_video => FilterVideoSource
     get_defaults => GetDefaultsSource
     media_play_pause => MediaPlayPauseSource
     media_restart => MediaRestartSource
@@ -405,4 +1275,9 @@ impl_source_builder! {
     media_get_duration => MediaGetDurationSource
     media_get_time => MediaGetTimeSource
     media_get_state => MediaGetStateSource
+    mouse_click => MouseClickSource
+    mouse_move => MouseMoveSource
+    mouse_wheel => MouseWheelSource
+    focus => FocusSource
+    key_click => KeyClickSource
 }
@@ -1,33 +1,91 @@
 use super::ObsString;
-use crate::{native_enum, wrapper::PtrWrapper};
-use num_traits::{Bounded, Float, Num, NumCast, PrimInt, ToPrimitive, one};
+use crate::symbol::{Symbol, LIBOBS};
+use crate::{data::DataObj, native_enum, unwind::handle_unwind, wrapper::PtrWrapper};
+use num_traits::{one, Bounded, Float, Num, NumCast, PrimInt, ToPrimitive};
 use obs_sys::{
     obs_combo_format, obs_combo_format_OBS_COMBO_FORMAT_FLOAT,
     obs_combo_format_OBS_COMBO_FORMAT_INT, obs_combo_format_OBS_COMBO_FORMAT_INVALID,
     obs_combo_format_OBS_COMBO_FORMAT_STRING, obs_combo_type,
     obs_combo_type_OBS_COMBO_TYPE_EDITABLE, obs_combo_type_OBS_COMBO_TYPE_INVALID,
-    obs_combo_type_OBS_COMBO_TYPE_LIST, obs_editable_list_type,
+    obs_combo_type_OBS_COMBO_TYPE_LIST, obs_data_t, obs_editable_list_type,
     obs_editable_list_type_OBS_EDITABLE_LIST_TYPE_FILES,
     obs_editable_list_type_OBS_EDITABLE_LIST_TYPE_FILES_AND_URLS,
     obs_editable_list_type_OBS_EDITABLE_LIST_TYPE_STRINGS, obs_path_type,
     obs_path_type_OBS_PATH_DIRECTORY, obs_path_type_OBS_PATH_FILE,
-    obs_path_type_OBS_PATH_FILE_SAVE, obs_properties_add_bool, obs_properties_add_color,
-    obs_properties_add_editable_list, obs_properties_add_float, obs_properties_add_float_slider,
-    obs_properties_add_font, obs_properties_add_int, obs_properties_add_int_slider,
-    obs_properties_add_list, obs_properties_add_path, obs_properties_add_text,
-    obs_properties_create, obs_properties_destroy, obs_properties_t, obs_property_list_add_float,
-    obs_property_list_add_int, obs_property_list_add_string, obs_property_list_insert_float,
-    obs_property_list_insert_int, obs_property_list_insert_string, obs_property_list_item_disable,
-    obs_property_list_item_remove, obs_property_t, obs_text_type, obs_text_type_OBS_TEXT_DEFAULT,
-    obs_text_type_OBS_TEXT_MULTILINE, obs_text_type_OBS_TEXT_PASSWORD, size_t,
+    obs_path_type_OBS_PATH_FILE_SAVE, obs_properties_add_bool, obs_properties_add_button2,
+    obs_properties_add_color, obs_properties_add_editable_list, obs_properties_add_float,
+    obs_properties_add_float_slider, obs_properties_add_font, obs_properties_add_int,
+    obs_properties_add_int_slider, obs_properties_add_list, obs_properties_add_path,
+    obs_properties_add_text, obs_properties_apply_settings, obs_properties_create,
+    obs_properties_destroy, obs_properties_get, obs_properties_t, obs_property_float_set_limits,
+    obs_property_int_set_limits, obs_property_list_add_float, obs_property_list_add_int,
+    obs_property_list_add_string, obs_property_list_clear, obs_property_list_format,
+    obs_property_list_insert_float, obs_property_list_insert_int, obs_property_list_insert_string,
+    obs_property_list_item_count, obs_property_list_item_disable, obs_property_list_item_disabled,
+    obs_property_list_item_float, obs_property_list_item_int, obs_property_list_item_name,
+    obs_property_list_item_remove, obs_property_list_item_string, obs_property_set_description,
+    obs_property_set_enabled, obs_property_set_long_description,
+    obs_property_set_modified_callback2, obs_property_set_visible, obs_property_t, obs_text_type,
+    obs_text_type_OBS_TEXT_DEFAULT, obs_text_type_OBS_TEXT_MULTILINE,
+    obs_text_type_OBS_TEXT_PASSWORD, size_t,
 };
 
-use std::{marker::PhantomData, ops::RangeBounds, os::raw::c_int};
+#[cfg(feature = "derive")]
+pub use obs_wrapper_derive::{ObsList, ObsProperties};
+
+use serde_json::{json, Map, Value};
+use std::{
+    convert::TryFrom,
+    ffi::{c_void, CStr},
+    marker::PhantomData,
+    mem::forget,
+    ops::RangeBounds,
+    os::raw::{c_char, c_int},
+};
+
+// Added in OBS 26.1, after the version the generated bindings cover.
+#[allow(non_upper_case_globals)]
+pub(crate) static obs_property_button_set_type: Symbol<
+    unsafe extern "C" fn(*mut obs_property_t, c_int),
+> = Symbol::new(&LIBOBS, b"obs_property_button_set_type\0");
+#[allow(non_upper_case_globals)]
+pub(crate) static obs_property_button_set_url: Symbol<
+    unsafe extern "C" fn(*mut obs_property_t, *mut c_char),
+> = Symbol::new(&LIBOBS, b"obs_property_button_set_url\0");
+// Added in OBS 27.0.
+#[allow(non_upper_case_globals)]
+pub(crate) static obs_property_text_set_info_type: Symbol<
+    unsafe extern "C" fn(*mut obs_property_t, obs_text_info_type),
+> = Symbol::new(&LIBOBS, b"obs_property_text_set_info_type\0");
+
+/// `OBS_TEXT_INFO` of `enum obs_text_type`, added in OBS 27.0.
+const obs_text_type_OBS_TEXT_INFO: obs_text_type = 3;
+
+/// `enum obs_text_info_type`, added in OBS 27.0.
+#[allow(non_camel_case_types)]
+type obs_text_info_type = u32;
+const obs_text_info_type_OBS_TEXT_INFO_NORMAL: obs_text_info_type = 0;
+const obs_text_info_type_OBS_TEXT_INFO_WARNING: obs_text_info_type = 1;
+const obs_text_info_type_OBS_TEXT_INFO_ERROR: obs_text_info_type = 2;
+
+/// `OBS_BUTTON_URL` of `enum obs_button_type`.
+const OBS_BUTTON_URL: c_int = 1;
+
+/// Name of the hidden property carrying the JSON encoded [`PropertyTags`] of a
+/// properties object.
+pub const PROPERTY_TAGS_NAME: &str = "obs_wrapper_property_tags";
 
 native_enum!(TextType, obs_text_type {
     Default => OBS_TEXT_DEFAULT,
     Password => OBS_TEXT_PASSWORD,
-    Multiline => OBS_TEXT_MULTILINE
+    Multiline => OBS_TEXT_MULTILINE,
+    Info => OBS_TEXT_INFO
+});
+
+native_enum!(TextInfoType, obs_text_info_type {
+    Normal => OBS_TEXT_INFO_NORMAL,
+    Warning => OBS_TEXT_INFO_WARNING,
+    Error => OBS_TEXT_INFO_ERROR
 });
 
 native_enum!(PathType, obs_path_type {
@@ -55,17 +113,65 @@ native_enum!(EditableListType, obs_editable_list_type {
     FilesAndUrls => OBS_EDITABLE_LIST_TYPE_FILES_AND_URLS
 });
 
+/// Search metadata for a single property, so that companion UIs can build
+/// searchable settings for sources with very many options.
+///
+/// Tags are collected into a hidden property named [`PROPERTY_TAGS_NAME`],
+/// whose long description holds a JSON object keyed by property name. The same
+/// JSON is returned by the `get_property_tags` proc, which sources get once
+/// their properties first carry tags.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct PropertyTags {
+    category: Option<String>,
+    keywords: Vec<String>,
+}
+
+impl PropertyTags {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_category(mut self, category: impl Into<String>) -> Self {
+        self.category = Some(category.into());
+        self
+    }
+
+    pub fn with_keyword(mut self, keyword: impl Into<String>) -> Self {
+        self.keywords.push(keyword.into());
+        self
+    }
+
+    pub fn with_keywords<I: IntoIterator<Item = S>, S: Into<String>>(
+        mut self,
+        keywords: I,
+    ) -> Self {
+        self.keywords.extend(keywords.into_iter().map(Into::into));
+        self
+    }
+
+    fn to_json(&self) -> Value {
+        json!({
+            "category": self.category,
+            "keywords": self.keywords,
+        })
+    }
+}
+
 /// Wrapper around [`obs_properties_t`], which is used by
 /// OBS to generate a user-friendly configuration UI.
 pub struct Properties {
     pointer: *mut obs_properties_t,
+    tags: Vec<(String, PropertyTags)>,
 }
 
 impl PtrWrapper for Properties {
     type Pointer = obs_properties_t;
 
     unsafe fn from_raw(raw: *mut Self::Pointer) -> Self {
-        Self { pointer: raw }
+        Self {
+            pointer: raw,
+            tags: vec![],
+        }
     }
 
     fn as_ptr(&self) -> *const Self::Pointer {
@@ -83,38 +189,193 @@ impl Properties {
 
     pub fn add<T: ObsProp>(
         &mut self,
-        name: ObsString,
+        name: impl Into<ObsString>,
+        description: ObsString,
+        prop: T,
+    ) -> &mut Self {
+        unsafe {
+            prop.add_to_props(self.pointer, name.into(), description);
+        }
+        self
+    }
+
+    /// Adds a property along with its search metadata.
+    pub fn add_tagged<T: ObsProp>(
+        &mut self,
+        name: impl Into<ObsString>,
         description: ObsString,
         prop: T,
+        tags: PropertyTags,
     ) -> &mut Self {
+        let name = name.into();
+        self.tags.push((name.as_str().to_owned(), tags));
+        self.add(name, description, prop)
+    }
+
+    /// Attaches search metadata to a property that has already been added,
+    /// such as one created with [`Properties::add_list`].
+    pub fn tag(&mut self, name: &ObsString, tags: PropertyTags) -> &mut Self {
+        self.tags.push((name.as_str().to_owned(), tags));
+        self
+    }
+
+    /// Runs the modified callbacks of every property against `settings`, so
+    /// that visibility and other rules depending on the values are
+    /// re-evaluated.
+    ///
+    /// See [OBS documentation](https://obsproject.com/docs/reference-properties.html#c.obs_properties_apply_settings)
+    pub fn apply_settings(&mut self, settings: &DataObj) -> &mut Self {
+        unsafe {
+            obs_properties_apply_settings(self.pointer, settings.as_ptr() as *mut _);
+        }
+        self
+    }
+
+    /// Returns the property called `name`, to change it after it was added,
+    /// e.g. to narrow a range depending on the current settings.
+    pub fn get(&mut self, name: impl Into<ObsString>) -> Option<Property<'_>> {
+        let name = name.into();
+        unsafe {
+            let raw = obs_properties_get(self.pointer, name.as_ptr());
+            if raw.is_null() {
+                None
+            } else {
+                Some(Property::from_raw(raw))
+            }
+        }
+    }
+
+    /// Adds the hidden tag info property, returning the JSON it carries.
+    pub(crate) fn finish_tags(&mut self) -> Option<String> {
+        if self.tags.is_empty() {
+            return None;
+        }
+
+        let map: Map<String, Value> = self
+            .tags
+            .iter()
+            .map(|(name, tags)| (name.clone(), tags.to_json()))
+            .collect();
+        let json = Value::Object(map).to_string();
+
+        let name = ObsString::from(PROPERTY_TAGS_NAME);
+        let long_description = ObsString::from(json.as_str());
         unsafe {
-            prop.add_to_props(self.pointer, name, description);
+            let raw = obs_properties_add_text(
+                self.pointer,
+                name.as_ptr(),
+                name.as_ptr(),
+                TextType::Default.into(),
+            );
+            obs_property_set_visible(raw, false);
+            obs_property_set_long_description(raw, long_description.as_ptr());
+        }
+
+        Some(json)
+    }
+
+    /// Adds a drop-down list with every variant of `T`.
+    pub fn add_enum_list<T: ObsListEnum>(
+        &mut self,
+        name: impl Into<ObsString>,
+        description: ObsString,
+    ) -> &mut Self {
+        let mut list = self.add_list::<ObsString>(name, description);
+        for (_, value, label) in T::variants() {
+            list.push(*label, ObsString::from(*value));
         }
         self
     }
 
+    /// Adds a drop-down list of `T` values.
+    ///
+    /// OBS only supports editable lists of strings, which are added with
+    /// [`add_editable_combo`](Self::add_editable_combo).
     pub fn add_list<T: ListType>(
         &mut self,
-        name: ObsString,
+        name: impl Into<ObsString>,
         description: ObsString,
-        editable: bool,
     ) -> ListProp<T> {
+        self.add_combo(name.into(), description, ComboType::List)
+    }
+
+    fn add_combo<T: ListType>(
+        &mut self,
+        name: ObsString,
+        description: ObsString,
+        combo_type: ComboType,
+    ) -> ListProp<'_, T> {
         unsafe {
             let raw = obs_properties_add_list(
                 self.pointer,
                 name.as_ptr(),
                 description.as_ptr(),
-                if editable {
-                    ComboType::Editable
-                } else {
-                    ComboType::List
-                }
-                .into(),
+                combo_type.into(),
                 T::format().into(),
             );
             ListProp::from_raw(raw)
         }
     }
+
+    /// Adds a combo box whose items are only suggestions, so the user can
+    /// also type a value of their own, e.g. a custom URL or resolution.
+    ///
+    /// The setting is a string holding either the value of the chosen item or
+    /// the text typed in, read with `settings.get::<Cow<str>, _>(name)`.
+    ///
+    /// See [OBS documentation](https://obsproject.com/docs/reference-properties.html#c.obs_properties_add_list)
+    pub fn add_editable_combo(
+        &mut self,
+        name: impl Into<ObsString>,
+        description: ObsString,
+    ) -> ListProp<'_, ObsString> {
+        self.add_combo(name.into(), description, ComboType::Editable)
+    }
+}
+
+/// A struct whose fields each map to a property, usually implemented with
+/// `#[derive(ObsProperties)]` (requires the `derive` feature).
+///
+/// The derive uses the same `#[obs(...)]` attributes as
+/// [`ObsSettings`](crate::data::ObsSettings), so one struct can describe both
+/// the settings of a source and its UI:
+///
+/// ```ignore
+/// #[derive(ObsSettings, ObsProperties)]
+/// struct Settings {
+///     #[obs(description = "Zoom", default = 1.5, min = 1.0, max = 5.0, slider)]
+///     zoom: f64,
+///     #[obs(description = "Follow the mouse", default = true)]
+///     follow_mouse: bool,
+///     #[obs(description = "Password", text = "password")]
+///     password: String,
+///     #[obs(description = "Screen", list)]
+///     screen: Screen,
+/// }
+///
+/// impl GetPropertiesSource<Data> for Filter {
+///     fn get_properties(_data: &mut Option<Data>, properties: &mut Properties) {
+///         Settings::add_properties(properties);
+///     }
+/// }
+/// ```
+///
+/// Booleans become checkboxes, numbers become number inputs (or sliders with
+/// `slider`) limited by `min`, `max` and `step`, strings become text fields
+/// and fields marked `list` become drop-down lists of an [`ObsListEnum`].
+/// Fields marked `skip` are left out.
+pub trait ObsProperties {
+    fn add_properties(properties: &mut Properties);
+}
+
+/// A fieldless enum that is stored as a string and shown as a drop-down list,
+/// usually implemented with `#[derive(ObsList)]` (requires the `derive` feature).
+///
+/// Each variant is stored as its name unless renamed with
+/// `#[obs(key = "...")]`, and labelled with `#[obs(description = "...")]`.
+pub trait ObsListEnum: Sized + Copy + 'static {
+    /// Every variant, along with the value it is stored as and its label.
+    fn variants() -> &'static [(Self, &'static str, &'static str)];
 }
 
 impl Drop for Properties {
@@ -123,6 +384,137 @@ impl Drop for Properties {
     }
 }
 
+/// Wrapper around [`obs_property_t`], a property that has already been added
+/// to a [`Properties`].
+pub struct Property<'props> {
+    raw: *mut obs_property_t,
+    _props: PhantomData<&'props mut Properties>,
+}
+
+impl PtrWrapper for Property<'_> {
+    type Pointer = obs_property_t;
+
+    unsafe fn from_raw(raw: *mut Self::Pointer) -> Self {
+        Self {
+            raw,
+            _props: PhantomData,
+        }
+    }
+
+    fn as_ptr(&self) -> *const Self::Pointer {
+        self.raw
+    }
+}
+
+/// Called with the properties, the property and the current settings when the
+/// value of a property changes, returning `true` if the properties need to be
+/// refreshed in the UI.
+pub type ModifiedCallback = fn(&mut Properties, &mut Property, &mut DataObj) -> bool;
+
+impl Property<'_> {
+    pub fn set_visible(&mut self, visible: bool) -> &mut Self {
+        unsafe { obs_property_set_visible(self.raw, visible) }
+        self
+    }
+
+    pub fn set_enabled(&mut self, enabled: bool) -> &mut Self {
+        unsafe { obs_property_set_enabled(self.raw, enabled) }
+        self
+    }
+
+    pub fn set_description(&mut self, description: impl Into<ObsString>) -> &mut Self {
+        let description = description.into();
+        unsafe { obs_property_set_description(self.raw, description.as_ptr()) }
+        self
+    }
+
+    /// Sets the severity an [`Info`](TextType::Info) text is shown with. Does
+    /// nothing for other property types.
+    ///
+    /// Does nothing before OBS 27.0, see
+    /// [`supports_info_types`](crate::version::supports_info_types).
+    pub fn set_info_type(&mut self, info_type: TextInfoType) -> &mut Self {
+        if let Some(set_info_type) = obs_property_text_set_info_type.get() {
+            unsafe { set_info_type(self.raw, info_type.into()) }
+        }
+        self
+    }
+
+    /// Sets the function called when the value of the property changes, e.g.
+    /// to validate it and show the result in an [`Info`](TextType::Info) text:
+    ///
+    /// ```ignore
+    /// fn url_modified(props: &mut Properties, _: &mut Property, settings: &mut DataObj) -> bool {
+    ///     let valid = settings.get::<Cow<str>, _>("url").map_or(false, |url| url.starts_with("rtmp://"));
+    ///     settings.set_string("url_status", if valid { "" } else { "Not an RTMP URL" });
+    ///     if let Some(mut status) = props.get("url_status") {
+    ///         status.set_visible(!valid).set_info_type(TextInfoType::Error);
+    ///     }
+    ///     true
+    /// }
+    /// ```
+    ///
+    /// An info text shows the string stored in the settings under its name,
+    /// so updating the settings and returning `true` updates the message.
+    ///
+    /// See [OBS documentation](https://obsproject.com/docs/reference-properties.html#c.obs_property_set_modified_callback2)
+    pub fn set_modified_callback(&mut self, callback: ModifiedCallback) -> &mut Self {
+        unsafe {
+            obs_property_set_modified_callback2(
+                self.raw,
+                Some(modified_callback),
+                callback as *mut c_void,
+            )
+        }
+        self
+    }
+
+    /// Returns the property as a list of `T`, or `None` if it is not a list
+    /// or holds values of another type.
+    pub fn as_list<T: ListType>(&mut self) -> Option<ListProp<'_, T>> {
+        let format = unsafe { obs_property_list_format(self.raw) };
+        if ComboFormat::try_from(format).ok()? == T::format() {
+            Some(unsafe { ListProp::from_raw(self.raw) })
+        } else {
+            None
+        }
+    }
+
+    /// Changes the range of an integer property. Does nothing for other
+    /// property types.
+    pub fn set_int_limits(&mut self, min: i32, max: i32, step: i32) -> &mut Self {
+        unsafe { obs_property_int_set_limits(self.raw, min, max, step) }
+        self
+    }
+
+    /// Changes the range of a float property. Does nothing for other property
+    /// types.
+    pub fn set_float_limits(&mut self, min: f64, max: f64, step: f64) -> &mut Self {
+        unsafe { obs_property_float_set_limits(self.raw, min, max, step) }
+        self
+    }
+}
+
+unsafe extern "C" fn modified_callback(
+    param: *mut c_void,
+    props: *mut obs_properties_t,
+    property: *mut obs_property_t,
+    settings: *mut obs_data_t,
+) -> bool {
+    let callback: ModifiedCallback = std::mem::transmute(param);
+    let mut props = Properties::from_raw(props);
+    let mut property = Property::from_raw(property);
+    let mut settings = DataObj::from_raw(settings);
+    let refresh = handle_unwind::<Property, _>("modified_callback", || {
+        callback(&mut props, &mut property, &mut settings)
+    })
+    .unwrap_or(false);
+    // All three are owned by OBS.
+    forget(props);
+    forget(settings);
+    refresh
+}
+
 /// Wrapper around [`obs_property_t`], which is a list of possible values for a
 /// property.
 pub struct ListProp<'props, T> {
@@ -167,12 +559,74 @@ impl<T: ListType> ListProp<'_, T> {
             obs_property_list_item_disable(self.raw, index as size_t, disabled);
         }
     }
+
+    /// Removes every item, e.g. before filling the list again from a modified
+    /// callback.
+    pub fn clear(&mut self) {
+        unsafe { obs_property_list_clear(self.raw) }
+    }
+
+    pub fn len(&self) -> usize {
+        unsafe { obs_property_list_item_count(self.raw) as usize }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the label of the item at `index`.
+    pub fn name(&self, index: usize) -> Option<String> {
+        if index < self.len() {
+            unsafe { ptr_to_string(obs_property_list_item_name(self.raw, index as size_t)) }
+        } else {
+            None
+        }
+    }
+
+    /// Returns the value of the item at `index`.
+    pub fn value(&self, index: usize) -> Option<T> {
+        if index < self.len() {
+            Some(unsafe { T::get_from(self.raw, index) })
+        } else {
+            None
+        }
+    }
+
+    pub fn disabled(&self, index: usize) -> bool {
+        unsafe { obs_property_list_item_disabled(self.raw, index as size_t) }
+    }
+
+    /// Returns the index of the first item holding `value`.
+    pub fn position(&self, value: &T) -> Option<usize>
+    where
+        T: PartialEq,
+    {
+        (0..self.len()).find(|&index| unsafe { T::get_from(self.raw, index) } == *value)
+    }
+}
+
+unsafe fn ptr_to_string(ptr: *const c_char) -> Option<String> {
+    if ptr.is_null() {
+        None
+    } else {
+        Some(CStr::from_ptr(ptr).to_string_lossy().into_owned())
+    }
 }
 
+/// A type list items can hold, deciding the `obs_combo_format` of the list.
+///
+/// Integers are stored as `OBS_COMBO_FORMAT_INT` and read with
+/// [`DataObj::get`](crate::data::DataObj::get) as `i64`, floats as
+/// `OBS_COMBO_FORMAT_FLOAT` and read as `f64`.
 pub trait ListType {
     fn format() -> ComboFormat;
     fn push_into(self, ptr: *mut obs_property_t, name: ObsString);
     fn insert_into(self, ptr: *mut obs_property_t, name: ObsString, index: usize);
+    /// Reads the value of the item at `index`.
+    ///
+    /// # Safety
+    /// `ptr` must be a valid list property with more than `index` items.
+    unsafe fn get_from(ptr: *mut obs_property_t, index: usize) -> Self;
 }
 
 impl ListType for ObsString {
@@ -191,6 +645,11 @@ impl ListType for ObsString {
             obs_property_list_insert_string(ptr, index as size_t, name.as_ptr(), self.as_ptr());
         }
     }
+
+    unsafe fn get_from(ptr: *mut obs_property_t, index: usize) -> Self {
+        let value = ptr_to_string(obs_property_list_item_string(ptr, index as size_t));
+        ObsString::from(value.unwrap_or_default())
+    }
 }
 
 impl ListType for i64 {
@@ -209,8 +668,40 @@ impl ListType for i64 {
             obs_property_list_insert_int(ptr, index as size_t, name.as_ptr(), self);
         }
     }
+
+    unsafe fn get_from(ptr: *mut obs_property_t, index: usize) -> Self {
+        obs_property_list_item_int(ptr, index as size_t)
+    }
+}
+
+macro_rules! impl_list_type_int {
+    ($($t:ty),*) => {
+        $(
+            impl ListType for $t {
+                fn format() -> ComboFormat {
+                    ComboFormat::Int
+                }
+
+                fn push_into(self, ptr: *mut obs_property_t, name: ObsString) {
+                    (self as i64).push_into(ptr, name)
+                }
+
+                fn insert_into(self, ptr: *mut obs_property_t, name: ObsString, index: usize) {
+                    (self as i64).insert_into(ptr, name, index)
+                }
+
+                /// Values out of range of the type are saturated.
+                unsafe fn get_from(ptr: *mut obs_property_t, index: usize) -> Self {
+                    let value = i64::get_from(ptr, index);
+                    <$t>::try_from(value).unwrap_or(if value < 0 { <$t>::MIN } else { <$t>::MAX })
+                }
+            }
+        )*
+    };
 }
 
+impl_list_type_int!(i32, u32);
+
 impl ListType for f64 {
     fn format() -> ComboFormat {
         ComboFormat::Float
@@ -227,6 +718,28 @@ impl ListType for f64 {
             obs_property_list_insert_float(ptr, index as size_t, name.as_ptr(), self);
         }
     }
+
+    unsafe fn get_from(ptr: *mut obs_property_t, index: usize) -> Self {
+        obs_property_list_item_float(ptr, index as size_t)
+    }
+}
+
+impl ListType for f32 {
+    fn format() -> ComboFormat {
+        ComboFormat::Float
+    }
+
+    fn push_into(self, ptr: *mut obs_property_t, name: ObsString) {
+        (self as f64).push_into(ptr, name)
+    }
+
+    fn insert_into(self, ptr: *mut obs_property_t, name: ObsString, index: usize) {
+        (self as f64).insert_into(ptr, name, index)
+    }
+
+    unsafe fn get_from(ptr: *mut obs_property_t, index: usize) -> Self {
+        f64::get_from(ptr, index) as f32
+    }
 }
 
 enum NumberType {
@@ -530,3 +1043,42 @@ impl ObsProp for EditableListProp {
         );
     }
 }
+
+/// A button opening `url` in the browser, e.g. to link to documentation or
+/// to a page where the user can get a stream key.
+///
+/// Before OBS 26.1 the button is added without doing anything, see
+/// [`supports_url_buttons`](crate::version::supports_url_buttons).
+pub struct UrlButtonProp {
+    url: ObsString,
+}
+
+impl UrlButtonProp {
+    pub fn new(url: ObsString) -> Self {
+        Self { url }
+    }
+}
+
+impl ObsProp for UrlButtonProp {
+    unsafe fn add_to_props(
+        self,
+        p: *mut obs_properties_t,
+        name: ObsString,
+        description: ObsString,
+    ) {
+        let raw = obs_properties_add_button2(
+            p,
+            name.as_ptr(),
+            description.as_ptr(),
+            None,
+            std::ptr::null_mut(),
+        );
+        if let (Some(set_type), Some(set_url)) = (
+            obs_property_button_set_type.get(),
+            obs_property_button_set_url.get(),
+        ) {
+            set_type(raw, OBS_BUTTON_URL);
+            set_url(raw, self.url.as_ptr() as *mut c_char);
+        }
+    }
+}
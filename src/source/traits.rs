@@ -1,9 +1,18 @@
-use super::context::{CreatableSourceContext, GlobalContext, VideoRenderContext};
+use super::audio::{AudioDataContext, FilterAudioResult};
+use super::context::{
+    AudioThreadContext, CreatableSourceContext, GlobalContext, VideoRenderContext,
+};
+use super::frame::AsyncFrame;
+use super::media::MediaState;
 use super::properties::Properties;
-use super::{audio::AudioDataContext, media::MediaState};
-use super::{EnumActiveContext, EnumAllContext, SourceContext, SourceType};
+use super::{
+    EnumActiveContext, EnumAllContext, KeyEvent, MouseButton, MouseEvent, SourceContext,
+    SourceType, TypeData,
+};
 use crate::data::DataObj;
+use crate::graphics::GraphicsTexture;
 use crate::string::ObsString;
+use std::error::Error;
 
 pub trait Sourceable {
     fn get_id() -> ObsString;
@@ -22,6 +31,13 @@ pub trait GetNameSource<D> {
     fn get_name() -> ObsString;
 }
 
+/// Variant of [`GetNameSource`] that receives the value attached with
+/// [`SourceInfoBuilder::with_type_data`](super::SourceInfoBuilder::with_type_data),
+/// so variants of one source can be named differently.
+pub trait GetNameTypeDataSource<D> {
+    fn get_name(type_data: Option<&TypeData>) -> ObsString;
+}
+
 simple_trait!(
     get_width => GetWidthSource -> u32
     get_height => GetHeightSource -> u32
@@ -33,10 +49,30 @@ pub trait CreatableSource<D> {
     fn create(create: &mut CreatableSourceContext<D>, source: SourceContext) -> D;
 }
 
+/// Fallible variant of [`CreatableSource`].
+///
+/// Returning an error logs it along with the source id and reports the failure
+/// to OBS, which will not create the source.
+pub trait TryCreatableSource<D> {
+    fn try_create(
+        create: &mut CreatableSourceContext<D>,
+        source: SourceContext,
+    ) -> Result<D, Box<dyn Error>>;
+}
+
 pub trait UpdateSource<D> {
     fn update(data: &mut Option<D>, settings: &mut DataObj, context: &mut GlobalContext);
 }
 
+/// Fallible variant of [`UpdateSource`]. Errors are logged along with the source id.
+pub trait TryUpdateSource<D> {
+    fn try_update(
+        data: &mut Option<D>,
+        settings: &mut DataObj,
+        context: &mut GlobalContext,
+    ) -> Result<(), Box<dyn Error>>;
+}
+
 pub trait VideoRenderSource<D> {
     fn video_render(
         data: &mut Option<D>,
@@ -45,18 +81,47 @@ pub trait VideoRenderSource<D> {
     );
 }
 
+/// Fallible variant of [`VideoRenderSource`]. Errors are logged along with the
+/// type and callback, and leave the rest of the frame undrawn.
+pub trait TryVideoRenderSource<D> {
+    fn try_video_render(
+        data: &mut Option<D>,
+        context: &mut GlobalContext,
+        render: &mut VideoRenderContext,
+    ) -> Result<(), Box<dyn Error>>;
+}
+
 pub trait AudioRenderSource<D> {
-    fn audio_render(data: &mut Option<D>, context: &mut GlobalContext);
+    fn audio_render(
+        data: &mut Option<D>,
+        context: &mut GlobalContext,
+        audio: &mut AudioThreadContext,
+    );
 }
 
 pub trait GetPropertiesSource<D> {
     fn get_properties(data: &mut Option<D>, properties: &mut Properties);
 }
 
+/// Fallible variant of [`GetPropertiesSource`]. Errors are logged along with
+/// the type and callback, and the properties added until then are shown.
+pub trait TryGetPropertiesSource<D> {
+    fn try_get_properties(
+        data: &mut Option<D>,
+        properties: &mut Properties,
+    ) -> Result<(), Box<dyn Error>>;
+}
+
 pub trait VideoTickSource<D> {
     fn video_tick(data: &mut Option<D>, seconds: f32);
 }
 
+/// Fallible variant of [`VideoTickSource`]. Errors are logged along with the
+/// type and callback, on every tick they occur.
+pub trait TryVideoTickSource<D> {
+    fn try_video_tick(data: &mut Option<D>, seconds: f32) -> Result<(), Box<dyn Error>>;
+}
+
 pub trait EnumActiveSource<D> {
     fn enum_active_sources(data: &mut Option<D>, context: &EnumActiveContext);
 }
@@ -70,8 +135,56 @@ simple_trait!(
     transition_stop => TransitionStopSource
 );
 
+/// Draws a running transition using [`obs_transition_video_render`](obs_sys::obs_transition_video_render).
+///
+/// `a` and `b` are the rendered outgoing and incoming sources and `t` is the
+/// progress of the transition from `0.0` to `1.0`. Nothing is drawn for frames
+/// where either texture is unavailable.
+pub trait TransitionVideoRenderSource<D> {
+    fn transition_video_render(
+        data: &mut Option<D>,
+        a: &GraphicsTexture,
+        b: &GraphicsTexture,
+        t: f32,
+        cx: u32,
+        cy: u32,
+    );
+}
+
+/// Mixes the audio of a running transition using
+/// [`obs_transition_audio_render`](obs_sys::obs_transition_audio_render).
+///
+/// Each method returns the volume of its source at transition progress `t`,
+/// defaulting to a linear crossfade.
+pub trait TransitionAudioRenderSource<D> {
+    fn mix_a(_data: &mut Option<D>, t: f32) -> f32 {
+        1.0 - t
+    }
+
+    fn mix_b(_data: &mut Option<D>, t: f32) -> f32 {
+        t
+    }
+}
+
 pub trait FilterAudioSource<D> {
-    fn filter_audio(data: &mut Option<D>, audio: &mut AudioDataContext);
+    /// Filters a block of audio, either in place or by returning other audio
+    /// or none at all.
+    fn filter_audio(data: &mut Option<D>, audio: &mut AudioDataContext) -> FilterAudioResult;
+}
+
+/// Fallible variant of [`FilterAudioSource`]. Errors are logged along with the
+/// type and callback, and the audio is passed on as it was left.
+pub trait TryFilterAudioSource<D> {
+    fn try_filter_audio(
+        data: &mut Option<D>,
+        audio: &mut AudioDataContext,
+    ) -> Result<FilterAudioResult, Box<dyn Error>>;
+}
+
+pub trait FilterVideoSource<D> {
+    /// Reads or changes a frame of the async source the filter is applied to,
+    /// before it is shown.
+    fn filter_video(data: &mut Option<D>, frame: &mut AsyncFrame);
 }
 
 pub trait MediaPlayPauseSource<D> {
@@ -94,3 +207,36 @@ simple_trait!(
     get_duration => MediaGetDurationSource -> i64
     get_time => MediaGetTimeSource -> i64
 );
+
+/// Receives mouse button presses and releases while the user interacts with
+/// the source.
+///
+/// Enabling any of the interaction callbacks sets `OutputFlags::INTERACTION`,
+/// which lets the frontend open an interaction window for the source.
+pub trait MouseClickSource<D> {
+    fn mouse_click(
+        data: &mut Option<D>,
+        event: &MouseEvent,
+        button: MouseButton,
+        mouse_up: bool,
+        click_count: u32,
+    );
+}
+
+/// Receives mouse movement over the source, `mouse_leave` being set when the
+/// mouse leaves it.
+pub trait MouseMoveSource<D> {
+    fn mouse_move(data: &mut Option<D>, event: &MouseEvent, mouse_leave: bool);
+}
+
+pub trait MouseWheelSource<D> {
+    fn mouse_wheel(data: &mut Option<D>, event: &MouseEvent, x_delta: i32, y_delta: i32);
+}
+
+pub trait FocusSource<D> {
+    fn focus(data: &mut Option<D>, focus: bool);
+}
+
+pub trait KeyClickSource<D> {
+    fn key_click(data: &mut Option<D>, event: &KeyEvent, key_up: bool);
+}
@@ -0,0 +1,205 @@
+#![allow(non_upper_case_globals)]
+
+use super::SourceContext;
+use crate::native_enum;
+use crate::unwind::handle_unwind;
+use obs_sys::{
+    obs_fader_add_callback, obs_fader_attach_source, obs_fader_create, obs_fader_destroy,
+    obs_fader_detach_source, obs_fader_get_db, obs_fader_get_deflection, obs_fader_get_mul,
+    obs_fader_remove_callback, obs_fader_set_db, obs_fader_set_deflection, obs_fader_set_mul,
+    obs_fader_t, obs_fader_type, obs_fader_type_OBS_FADER_CUBIC, obs_fader_type_OBS_FADER_IEC,
+    obs_fader_type_OBS_FADER_LOG, obs_peak_meter_type, obs_peak_meter_type_SAMPLE_PEAK_METER,
+    obs_peak_meter_type_TRUE_PEAK_METER, obs_volmeter_add_callback, obs_volmeter_attach_source,
+    obs_volmeter_create, obs_volmeter_destroy, obs_volmeter_detach_source,
+    obs_volmeter_get_nr_channels, obs_volmeter_get_update_interval, obs_volmeter_remove_callback,
+    obs_volmeter_set_peak_meter_type, obs_volmeter_set_update_interval, obs_volmeter_t,
+    MAX_AUDIO_CHANNELS,
+};
+
+use std::{ffi::c_void, slice};
+
+native_enum!(FaderType, obs_fader_type {
+    Cubic => OBS_FADER_CUBIC,
+    IEC => OBS_FADER_IEC,
+    Log => OBS_FADER_LOG
+});
+
+native_enum!(PeakMeterType, obs_peak_meter_type {
+    SamplePeak => SAMPLE_PEAK_METER,
+    TruePeak => TRUE_PEAK_METER
+});
+
+type FaderCallback = Box<dyn FnMut(f32) + Send>;
+type VolmeterCallback = Box<dyn FnMut(&VolmeterLevels) + Send>;
+
+/// Wrapper around `obs_fader_t`, which maps the volume of a source onto a
+/// fader position, the way the OBS mixer does.
+///
+/// See [OBS documentation](https://obsproject.com/docs/reference-libobs-audio-controls.html)
+pub struct Fader {
+    raw: *mut obs_fader_t,
+    callbacks: Vec<*mut FaderCallback>,
+}
+
+impl Fader {
+    pub fn new(fader_type: FaderType) -> Self {
+        Self {
+            raw: unsafe { obs_fader_create(fader_type.into()) },
+            callbacks: vec![],
+        }
+    }
+
+    /// Links the fader to the volume of `source`, replacing any previously
+    /// attached source.
+    pub fn attach_source(&mut self, source: &SourceContext) -> bool {
+        unsafe { obs_fader_attach_source(self.raw, source.as_ptr()) }
+    }
+
+    pub fn detach_source(&mut self) {
+        unsafe { obs_fader_detach_source(self.raw) }
+    }
+
+    /// Sets the volume in decibels, returning whether it was within range.
+    pub fn set_db(&mut self, db: f32) -> bool {
+        unsafe { obs_fader_set_db(self.raw, db) }
+    }
+
+    pub fn db(&self) -> f32 {
+        unsafe { obs_fader_get_db(self.raw) }
+    }
+
+    /// Sets the fader position, from `0.0` to `1.0`.
+    pub fn set_deflection(&mut self, deflection: f32) -> bool {
+        unsafe { obs_fader_set_deflection(self.raw, deflection) }
+    }
+
+    pub fn deflection(&self) -> f32 {
+        unsafe { obs_fader_get_deflection(self.raw) }
+    }
+
+    /// Sets the volume as a linear multiplier.
+    pub fn set_mul(&mut self, mul: f32) -> bool {
+        unsafe { obs_fader_set_mul(self.raw, mul) }
+    }
+
+    pub fn mul(&self) -> f32 {
+        unsafe { obs_fader_get_mul(self.raw) }
+    }
+
+    /// Adds a callback called with the new volume in decibels whenever it
+    /// changes, from whichever thread changed it.
+    pub fn add_callback<F: FnMut(f32) + Send + 'static>(&mut self, func: F) {
+        let data: *mut FaderCallback = Box::into_raw(Box::new(Box::new(func)));
+        unsafe { obs_fader_add_callback(self.raw, Some(fader_callback), data as *mut c_void) }
+        self.callbacks.push(data);
+    }
+}
+
+impl Drop for Fader {
+    fn drop(&mut self) {
+        unsafe {
+            for data in self.callbacks.drain(..) {
+                obs_fader_remove_callback(self.raw, Some(fader_callback), data as *mut c_void);
+                drop(Box::from_raw(data));
+            }
+            obs_fader_destroy(self.raw);
+        }
+    }
+}
+
+unsafe extern "C" fn fader_callback(param: *mut c_void, db: f32) {
+    let func = &mut *(param as *mut FaderCallback);
+    handle_unwind::<Fader, _>("fader_callback", || func(db));
+}
+
+/// Audio levels of every channel of a source, in dBFS.
+pub struct VolmeterLevels<'a> {
+    pub magnitude: &'a [f32],
+    pub peak: &'a [f32],
+    pub input_peak: &'a [f32],
+}
+
+/// Wrapper around `obs_volmeter_t`, which measures the audio levels of a
+/// source and reports them to callbacks on the audio thread.
+///
+/// See [OBS documentation](https://obsproject.com/docs/reference-libobs-audio-controls.html)
+pub struct Volmeter {
+    raw: *mut obs_volmeter_t,
+    callbacks: Vec<*mut VolmeterCallback>,
+}
+
+impl Volmeter {
+    pub fn new(fader_type: FaderType) -> Self {
+        Self {
+            raw: unsafe { obs_volmeter_create(fader_type.into()) },
+            callbacks: vec![],
+        }
+    }
+
+    /// Starts measuring `source`, replacing any previously attached source.
+    pub fn attach_source(&mut self, source: &SourceContext) -> bool {
+        unsafe { obs_volmeter_attach_source(self.raw, source.as_ptr()) }
+    }
+
+    pub fn detach_source(&mut self) {
+        unsafe { obs_volmeter_detach_source(self.raw) }
+    }
+
+    pub fn set_peak_meter_type(&mut self, peak_meter_type: PeakMeterType) {
+        unsafe { obs_volmeter_set_peak_meter_type(self.raw, peak_meter_type.into()) }
+    }
+
+    /// Sets how often the levels are reported, in milliseconds.
+    pub fn set_update_interval(&mut self, ms: u32) {
+        unsafe { obs_volmeter_set_update_interval(self.raw, ms) }
+    }
+
+    pub fn update_interval(&self) -> u32 {
+        unsafe { obs_volmeter_get_update_interval(self.raw) }
+    }
+
+    /// Number of channels of the attached source.
+    pub fn channels(&self) -> usize {
+        unsafe { obs_volmeter_get_nr_channels(self.raw) as usize }
+    }
+
+    /// Adds a callback receiving the levels of the attached source at every
+    /// update interval. Unused channels are reported as silent.
+    pub fn add_callback<F: FnMut(&VolmeterLevels) + Send + 'static>(&mut self, func: F) {
+        let data: *mut VolmeterCallback = Box::into_raw(Box::new(Box::new(func)));
+        unsafe { obs_volmeter_add_callback(self.raw, Some(volmeter_callback), data as *mut c_void) }
+        self.callbacks.push(data);
+    }
+}
+
+impl Drop for Volmeter {
+    fn drop(&mut self) {
+        unsafe {
+            for data in self.callbacks.drain(..) {
+                obs_volmeter_remove_callback(
+                    self.raw,
+                    Some(volmeter_callback),
+                    data as *mut c_void,
+                );
+                drop(Box::from_raw(data));
+            }
+            obs_volmeter_destroy(self.raw);
+        }
+    }
+}
+
+unsafe extern "C" fn volmeter_callback(
+    param: *mut c_void,
+    magnitude: *const f32,
+    peak: *const f32,
+    input_peak: *const f32,
+) {
+    let func = &mut *(param as *mut VolmeterCallback);
+    let channels = MAX_AUDIO_CHANNELS as usize;
+    let levels = VolmeterLevels {
+        magnitude: slice::from_raw_parts(magnitude, channels),
+        peak: slice::from_raw_parts(peak, channels),
+        input_peak: slice::from_raw_parts(input_peak, channels),
+    };
+    handle_unwind::<Volmeter, _>("volmeter_callback", || func(&levels));
+}
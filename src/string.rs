@@ -1,6 +1,17 @@
-use std::{ffi::CString, ptr::null};
+use std::collections::HashSet;
+use std::ffi::CString;
+use std::hash::{Hash, Hasher};
+use std::os::raw::c_char;
+use std::ptr::null;
+use std::sync::Mutex;
 
-#[derive(Debug, Eq, PartialEq, Hash, Clone)]
+/// Copies of runtime strings handed to OBS, which keeps some strings such as
+/// source names and ids without copying them.
+static CACHE: Mutex<Option<HashSet<CString>>> = Mutex::new(None);
+
+/// A nul terminated string, either a literal made with [`obs_string!`] or
+/// created at runtime, e.g. from a translation or a device name.
+#[derive(Debug, Clone)]
 pub enum ObsString {
     Static(&'static str),
     Dynamic(CString),
@@ -16,11 +27,24 @@ impl ObsString {
 
     pub fn as_str(&self) -> &str {
         match self {
-            Self::Static(s) => *s,
+            Self::Static(s) => s.strip_suffix('\0').unwrap_or(s),
             Self::Dynamic(s) => s.as_c_str().to_str().unwrap(),
         }
     }
 
+    /// The bytes of the string, without the nul terminator. Unlike
+    /// [`as_str`](Self::as_str) this works for runtime strings that are not
+    /// UTF-8.
+    pub fn as_bytes(&self) -> &[u8] {
+        match self {
+            Self::Static(s) => {
+                let bytes = s.as_bytes();
+                bytes.strip_suffix(b"\0").unwrap_or(bytes)
+            }
+            Self::Dynamic(s) => s.as_bytes(),
+        }
+    }
+
     pub fn as_ptr(&self) -> *const std::os::raw::c_char {
         match self {
             Self::Static(s) => (*s).as_ptr() as *const std::os::raw::c_char,
@@ -31,6 +55,54 @@ impl ObsString {
     pub fn ptr_or_null(opt: &Option<Self>) -> *const std::os::raw::c_char {
         opt.as_ref().map(|s| s.as_ptr()).unwrap_or(null())
     }
+
+    /// Returns a pointer that stays valid until the module is unloaded, for
+    /// strings OBS keeps without copying, such as the names returned by
+    /// `get_name` callbacks.
+    ///
+    /// Runtime strings are copied once per distinct value, so returning the
+    /// same string from a callback again does not use more memory. Other
+    /// strings, like property labels and list items, are copied by OBS and
+    /// can use [`as_ptr`](Self::as_ptr).
+    pub fn as_cached_ptr(&self) -> *const c_char {
+        let string = match self {
+            Self::Static(_) => return self.as_ptr(),
+            Self::Dynamic(string) => string.as_c_str(),
+        };
+        let mut cache = CACHE.lock().unwrap_or_else(|e| e.into_inner());
+        let cache = cache.get_or_insert_with(HashSet::new);
+        if let Some(cached) = cache.get(string) {
+            return cached.as_ptr();
+        }
+        // The heap buffer of a `CString` does not move with it, so the
+        // pointer stays valid as the set grows.
+        let cached = string.to_owned();
+        let pointer = cached.as_ptr();
+        cache.insert(cached);
+        pointer
+    }
+}
+
+/// Frees the strings returned by [`ObsString::as_cached_ptr`], once OBS no
+/// longer uses them.
+pub(crate) fn clear_cache() {
+    let cache = CACHE.lock().unwrap_or_else(|e| e.into_inner()).take();
+    drop(cache);
+}
+
+// Literals carry their nul terminator, so strings compare by their contents.
+impl PartialEq for ObsString {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_bytes() == other.as_bytes()
+    }
+}
+
+impl Eq for ObsString {}
+
+impl Hash for ObsString {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_bytes().hash(state)
+    }
 }
 
 impl<T: Into<Vec<u8>>> From<T> for ObsString {
@@ -0,0 +1,155 @@
+//! Functions newer than the generated bindings, looked up when first called
+//! instead of being imported. A plugin importing a function its OBS does not
+//! have fails to load at all, while a missing [`Symbol`] only resolves to
+//! `None`:
+//!
+//! ```ignore
+//! // Added in OBS 27.0.
+//! static obs_feature_set: Symbol<unsafe extern "C" fn(*mut obs_source_t, bool)> =
+//!     Symbol::new(&LIBOBS, b"obs_feature_set\0");
+//!
+//! if let Some(obs_feature_set) = obs_feature_set.get() {
+//!     unsafe { obs_feature_set(source, true) }
+//! }
+//! ```
+
+use std::ffi::c_void;
+use std::marker::PhantomData;
+use std::mem;
+use std::os::raw::c_char;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// A library symbols are looked up in.
+pub(crate) struct Library {
+    /// The name of the loaded module on Windows.
+    #[cfg_attr(not(windows), allow(dead_code))]
+    module: &'static [u8],
+    /// Returns the address of a function imported from the library, to find
+    /// the file it was loaded from on other platforms.
+    #[cfg_attr(windows, allow(dead_code))]
+    anchor: fn() -> *const c_void,
+}
+
+fn libobs_anchor() -> *const c_void {
+    obs_sys::obs_get_version as *const c_void
+}
+
+pub(crate) const LIBOBS: Library = Library::new(b"obs\0", libobs_anchor);
+
+impl Library {
+    pub(crate) const fn new(module: &'static [u8], anchor: fn() -> *const c_void) -> Self {
+        Self { module, anchor }
+    }
+
+    #[cfg(windows)]
+    unsafe fn lookup(&self, name: &'static [u8]) -> *mut c_void {
+        extern "system" {
+            fn GetModuleHandleA(module: *const c_char) -> *mut c_void;
+            fn GetProcAddress(module: *mut c_void, name: *const c_char) -> *mut c_void;
+        }
+
+        let module = GetModuleHandleA(self.module.as_ptr() as *const c_char);
+        if module.is_null() {
+            return std::ptr::null_mut();
+        }
+        GetProcAddress(module, name.as_ptr() as *const c_char)
+    }
+
+    #[cfg(any(target_os = "linux", target_os = "macos"))]
+    unsafe fn lookup(&self, name: &'static [u8]) -> *mut c_void {
+        #[repr(C)]
+        struct DlInfo {
+            dli_fname: *const c_char,
+            dli_fbase: *mut c_void,
+            dli_sname: *const c_char,
+            dli_saddr: *mut c_void,
+        }
+
+        #[cfg_attr(target_os = "linux", link(name = "dl"))]
+        extern "C" {
+            fn dladdr(addr: *const c_void, info: *mut DlInfo) -> i32;
+            fn dlopen(filename: *const c_char, flags: i32) -> *mut c_void;
+            fn dlsym(handle: *mut c_void, symbol: *const c_char) -> *mut c_void;
+            fn dlclose(handle: *mut c_void) -> i32;
+        }
+
+        const RTLD_LAZY: i32 = 1;
+        #[cfg(target_os = "linux")]
+        const RTLD_NOLOAD: i32 = 4;
+        #[cfg(target_os = "macos")]
+        const RTLD_NOLOAD: i32 = 0x10;
+        #[cfg(target_os = "linux")]
+        const RTLD_DEFAULT: *mut c_void = std::ptr::null_mut();
+        #[cfg(target_os = "macos")]
+        const RTLD_DEFAULT: *mut c_void = -2isize as *mut c_void;
+
+        let name = name.as_ptr() as *const c_char;
+
+        // The library may have been loaded without exposing its symbols
+        // globally, so it is looked up by the file the anchor lives in first.
+        let mut info: DlInfo = mem::zeroed();
+        if dladdr((self.anchor)(), &mut info) != 0 && !info.dli_fname.is_null() {
+            let handle = dlopen(info.dli_fname, RTLD_LAZY | RTLD_NOLOAD);
+            if !handle.is_null() {
+                let address = dlsym(handle, name);
+                // The library stays loaded by the reference the plugin holds.
+                dlclose(handle);
+                return address;
+            }
+        }
+
+        dlsym(RTLD_DEFAULT, name)
+    }
+
+    #[cfg(not(any(windows, target_os = "linux", target_os = "macos")))]
+    unsafe fn lookup(&self, _name: &'static [u8]) -> *mut c_void {
+        std::ptr::null_mut()
+    }
+}
+
+const UNRESOLVED: usize = 0;
+const MISSING: usize = 1;
+
+/// A function of type `F` looked up by name the first time it is used.
+pub(crate) struct Symbol<F> {
+    library: &'static Library,
+    name: &'static [u8],
+    address: AtomicUsize,
+    __func: PhantomData<F>,
+}
+
+impl<F: Copy> Symbol<F> {
+    /// `name` must be NUL terminated, and `F` an `unsafe extern "C" fn`
+    /// matching the declaration of the function in C.
+    pub(crate) const fn new(library: &'static Library, name: &'static [u8]) -> Self {
+        Self {
+            library,
+            name,
+            address: AtomicUsize::new(UNRESOLVED),
+            __func: PhantomData,
+        }
+    }
+
+    /// Returns the function, or `None` if the running OBS does not have it.
+    pub(crate) fn get(&self) -> Option<F> {
+        debug_assert_eq!(mem::size_of::<F>(), mem::size_of::<usize>());
+
+        let mut address = self.address.load(Ordering::Acquire);
+        if address == UNRESOLVED {
+            address = match unsafe { self.library.lookup(self.name) } as usize {
+                0 => MISSING,
+                address => address,
+            };
+            self.address.store(address, Ordering::Release);
+        }
+
+        match address {
+            MISSING => None,
+            address => Some(unsafe { mem::transmute_copy::<usize, F>(&address) }),
+        }
+    }
+
+    pub(crate) fn is_available(&self) -> bool {
+        self.get().is_some()
+    }
+}
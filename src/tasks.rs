@@ -0,0 +1,67 @@
+#![allow(non_upper_case_globals)]
+
+use crate::native_enum;
+use crate::unwind::handle_unwind;
+use obs_sys::{
+    obs_queue_task, obs_task_type, obs_task_type_OBS_TASK_GRAPHICS, obs_task_type_OBS_TASK_UI,
+};
+use std::ffi::c_void;
+
+native_enum!(TaskDestination, obs_task_type {
+    Ui => OBS_TASK_UI,
+    Graphics => OBS_TASK_GRAPHICS
+});
+
+type Task = Box<dyn FnOnce() + Send>;
+
+/// Runs `func` on the UI or graphics thread without waiting for it to finish.
+///
+/// Useful for handing work from a background thread to OBS, since most of the
+/// OBS API is not safe to call from arbitrary threads.
+///
+/// See [OBS documentation](https://obsproject.com/docs/reference-core.html#c.obs_queue_task)
+pub fn queue<F: FnOnce() + Send + 'static>(destination: TaskDestination, func: F) {
+    let task: Box<Task> = Box::new(Box::new(func));
+    unsafe {
+        obs_queue_task(
+            destination.into(),
+            Some(run_task),
+            Box::into_raw(task) as *mut c_void,
+            false,
+        );
+    }
+}
+
+/// Runs `func` on the UI or graphics thread, blocking until it has finished
+/// and returning its result.
+///
+/// Returns `None` if `func` panicked. Must not be called from the destination
+/// thread while it is waiting on the caller, as that would deadlock.
+pub fn queue_and_wait<R: Send, F: FnOnce() -> R + Send>(
+    destination: TaskDestination,
+    func: F,
+) -> Option<R> {
+    let mut result = None;
+    let mut func = Some(func);
+    let mut task = || result = func.take().map(|func| func());
+    let mut task: &mut (dyn FnMut() + Send) = &mut task;
+    unsafe {
+        obs_queue_task(
+            destination.into(),
+            Some(run_borrowed_task),
+            &mut task as *mut &mut (dyn FnMut() + Send) as *mut c_void,
+            true,
+        );
+    }
+    result
+}
+
+unsafe extern "C" fn run_task(param: *mut c_void) {
+    let task = Box::from_raw(param as *mut Task);
+    handle_unwind::<Task, _>("queue", task);
+}
+
+unsafe extern "C" fn run_borrowed_task(param: *mut c_void) {
+    let task = &mut *(param as *mut &mut (dyn FnMut() + Send));
+    handle_unwind::<Task, _>("queue_and_wait", task);
+}
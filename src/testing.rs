@@ -0,0 +1,324 @@
+//! Running plugin code against a real libobs in-process, so it can be covered
+//! by `cargo test` instead of only by trying it out in OBS:
+//!
+//! ```ignore
+//! #[test]
+//! fn zooms_in() {
+//!     let mut obs = HarnessOptions::new().with_resolution(320, 180).start().unwrap();
+//!     assert!(obs.load::<ScrollFocusModule>());
+//!
+//!     let mut source = SourceRef::create("scroll_focus_filter", "test", None).unwrap();
+//!     let mut settings = DataObj::new();
+//!     settings.set("zoom", &2.0);
+//!     source.update_source_settings(&mut settings);
+//!
+//!     obs.run_frames(10);
+//!     let pixels = obs.render(&source, 320, 180).unwrap();
+//!     // ...
+//! }
+//! ```
+//!
+//! libobs can only be started once per process at a time, so tests using the
+//! harness run one after another even when the test runner uses threads.
+//! Video needs a graphics module that can create a context, which may not be
+//! the case on a CI machine without a GPU, see
+//! [`HarnessOptions::without_video`](crate::testing::HarnessOptions::without_video).
+
+use crate::module::{LoadContext, Module, ModuleContext};
+use crate::source::SourceContext;
+use crate::string::ObsString;
+use crate::unwind::handle_unwind;
+use obs_sys::{
+    gs_clear, gs_color_format_GS_RGBA, gs_ortho, gs_stage_texture, gs_stagesurface_create,
+    gs_stagesurface_destroy, gs_stagesurface_map, gs_stagesurface_unmap, gs_texrender_begin,
+    gs_texrender_create, gs_texrender_destroy, gs_texrender_end, gs_texrender_get_texture,
+    gs_zstencil_format_GS_ZS_NONE, obs_audio_info, obs_enter_graphics, obs_get_total_frames,
+    obs_initialized, obs_leave_graphics, obs_reset_audio, obs_reset_video,
+    obs_scale_type_OBS_SCALE_BICUBIC, obs_shutdown, obs_source_video_render, obs_startup,
+    obs_video_info, speaker_layout_SPEAKERS_STEREO, vec4, video_colorspace_VIDEO_CS_DEFAULT,
+    video_format_VIDEO_FORMAT_RGBA, video_range_type_VIDEO_RANGE_DEFAULT, GS_CLEAR_COLOR,
+    OBS_VIDEO_SUCCESS,
+};
+
+use std::ptr;
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+static RUNNING: Mutex<()> = Mutex::new(());
+
+/// Failure to start libobs.
+#[derive(Debug)]
+pub enum HarnessError {
+    /// libobs failed to start, or was already started by something else.
+    Startup,
+    /// Resetting video failed with the given `OBS_VIDEO_*` code.
+    Video(i32),
+    Audio,
+}
+
+impl std::fmt::Display for HarnessError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HarnessError::Startup => write!(f, "Failed to start libobs"),
+            HarnessError::Video(code) => write!(f, "Failed to reset video: {}", code),
+            HarnessError::Audio => write!(f, "Failed to reset audio"),
+        }
+    }
+}
+
+impl std::error::Error for HarnessError {}
+
+/// How libobs is set up by [`HarnessOptions::start`].
+pub struct HarnessOptions {
+    graphics_module: ObsString,
+    video: bool,
+    width: u32,
+    height: u32,
+    fps: u32,
+    sample_rate: u32,
+}
+
+impl Default for HarnessOptions {
+    fn default() -> Self {
+        Self {
+            graphics_module: if cfg!(windows) {
+                ObsString::from("libobs-d3d11")
+            } else {
+                ObsString::from("libobs-opengl")
+            },
+            video: true,
+            width: 1280,
+            height: 720,
+            fps: 30,
+            sample_rate: 48000,
+        }
+    }
+}
+
+impl HarnessOptions {
+    /// Creates options for a 1280x720 canvas at 30 FPS with 48kHz stereo
+    /// audio.
+    #[must_use = "You must call start() to start libobs"]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the graphics module video is rendered with.
+    #[must_use = "You must call start() to start libobs"]
+    pub fn with_graphics_module(mut self, module: impl Into<ObsString>) -> Self {
+        self.graphics_module = module.into();
+        self
+    }
+
+    /// Starts libobs without video, so no graphics module is needed. Sources
+    /// are then never ticked or rendered.
+    #[must_use = "You must call start() to start libobs"]
+    pub fn without_video(mut self) -> Self {
+        self.video = false;
+        self
+    }
+
+    #[must_use = "You must call start() to start libobs"]
+    pub fn with_resolution(mut self, width: u32, height: u32) -> Self {
+        self.width = width;
+        self.height = height;
+        self
+    }
+
+    #[must_use = "You must call start() to start libobs"]
+    pub fn with_fps(mut self, fps: u32) -> Self {
+        self.fps = fps;
+        self
+    }
+
+    #[must_use = "You must call start() to start libobs"]
+    pub fn with_sample_rate(mut self, sample_rate: u32) -> Self {
+        self.sample_rate = sample_rate;
+        self
+    }
+
+    /// Starts libobs, waiting for any other harness in the process to be
+    /// dropped first.
+    pub fn start(self) -> Result<TestHarness, HarnessError> {
+        let guard = RUNNING.lock().unwrap_or_else(|e| e.into_inner());
+
+        unsafe {
+            if obs_initialized()
+                || !obs_startup("en-US\0".as_ptr() as _, ptr::null(), ptr::null_mut())
+            {
+                return Err(HarnessError::Startup);
+            }
+        }
+        // From here on dropping the harness shuts libobs down again.
+        let harness = TestHarness {
+            modules: vec![],
+            contexts: vec![],
+            fps: self.fps,
+            video: self.video,
+            _guard: guard,
+        };
+
+        if self.video {
+            let mut ovi = obs_video_info {
+                graphics_module: self.graphics_module.as_ptr(),
+                fps_num: self.fps,
+                fps_den: 1,
+                base_width: self.width,
+                base_height: self.height,
+                output_width: self.width,
+                output_height: self.height,
+                output_format: video_format_VIDEO_FORMAT_RGBA,
+                adapter: 0,
+                gpu_conversion: true,
+                colorspace: video_colorspace_VIDEO_CS_DEFAULT,
+                range: video_range_type_VIDEO_RANGE_DEFAULT,
+                scale_type: obs_scale_type_OBS_SCALE_BICUBIC,
+            };
+            let code = unsafe { obs_reset_video(&mut ovi) };
+            if code != OBS_VIDEO_SUCCESS as i32 {
+                return Err(HarnessError::Video(code));
+            }
+        }
+
+        let oai = obs_audio_info {
+            samples_per_sec: self.sample_rate,
+            speakers: speaker_layout_SPEAKERS_STEREO,
+        };
+        if !unsafe { obs_reset_audio(&oai) } {
+            return Err(HarnessError::Audio);
+        }
+
+        Ok(harness)
+    }
+}
+
+/// A running libobs, shut down when dropped.
+///
+/// Any [`SourceRef`](crate::source::SourceRef) and other references taken
+/// while the harness runs must be dropped before it is.
+pub struct TestHarness {
+    /// Unloads each module loaded with [`load`](Self::load).
+    modules: Vec<Box<dyn FnOnce()>>,
+    /// The types registered by modules that failed to load.
+    contexts: Vec<LoadContext>,
+    fps: u32,
+    video: bool,
+    _guard: MutexGuard<'static, ()>,
+}
+
+impl TestHarness {
+    /// Starts libobs with the default [`HarnessOptions`].
+    pub fn start() -> Result<Self, HarnessError> {
+        HarnessOptions::new().start()
+    }
+
+    /// Loads `M` in-process, as OBS would load the module built from it,
+    /// returning the result of [`Module::load`]. Like in OBS, a module that
+    /// fails to load is dropped without [`Module::unload`] being called.
+    ///
+    /// The module has no `obs_module_t`, so functions depending on the module
+    /// pointer, such as looking up its data files, are not available.
+    pub fn load<M: Module + 'static>(&mut self) -> bool {
+        crate::module::load_module();
+        let mut module = M::new(unsafe { ModuleContext::new(ptr::null_mut()) });
+        let mut context = unsafe { LoadContext::new() };
+        let loaded = handle_unwind::<M, _>("load", || module.load(&mut context)).unwrap_or(false);
+        if !loaded {
+            // OBS frees a module that failed to load without unloading it,
+            // but keeps the types it registered until shutdown.
+            handle_unwind::<M, _>("drop", || drop(module));
+            self.contexts.push(context);
+            return false;
+        }
+
+        handle_unwind::<M, _>("post_load", || module.post_load());
+        self.modules.push(Box::new(move || {
+            handle_unwind::<M, _>("unload", || module.unload());
+            handle_unwind::<M, _>("drop", || drop(module));
+            drop(context);
+        }));
+        true
+    }
+
+    /// Waits until OBS has ticked and rendered `frames` more video frames,
+    /// returning `false` if video is disabled or it took over twice as long as
+    /// expected.
+    pub fn run_frames(&self, frames: u32) -> bool {
+        if !self.video {
+            return false;
+        }
+        let target = unsafe { obs_get_total_frames() }.wrapping_add(frames);
+        let deadline =
+            Instant::now() + Duration::from_secs_f64(2.0 * frames as f64 / self.fps as f64);
+        loop {
+            // The counter may wrap around during long runs.
+            let remaining = target.wrapping_sub(unsafe { obs_get_total_frames() }) as i32;
+            if remaining <= 0 {
+                return true;
+            }
+            if Instant::now() > deadline {
+                return false;
+            }
+            std::thread::sleep(Duration::from_millis(1));
+        }
+    }
+
+    /// Renders `source` into a `width`x`height` texture cleared to
+    /// transparent black, returning its RGBA pixels row by row. Returns `None`
+    /// if video is disabled or rendering failed.
+    pub fn render(&self, source: &SourceContext, width: u32, height: u32) -> Option<Vec<u8>> {
+        if !self.video {
+            return None;
+        }
+
+        unsafe {
+            obs_enter_graphics();
+            let texrender =
+                gs_texrender_create(gs_color_format_GS_RGBA, gs_zstencil_format_GS_ZS_NONE);
+            let stagesurf = gs_stagesurface_create(width, height, gs_color_format_GS_RGBA);
+            let mut pixels = None;
+
+            if !texrender.is_null()
+                && !stagesurf.is_null()
+                && gs_texrender_begin(texrender, width, height)
+            {
+                let clear = vec4::default();
+                gs_clear(GS_CLEAR_COLOR, &clear, 0.0, 0);
+                gs_ortho(0.0, width as f32, 0.0, height as f32, -100.0, 100.0);
+                obs_source_video_render(source.as_ptr());
+                gs_texrender_end(texrender);
+
+                gs_stage_texture(stagesurf, gs_texrender_get_texture(texrender));
+                let mut data = ptr::null_mut();
+                let mut linesize = 0;
+                if gs_stagesurface_map(stagesurf, &mut data, &mut linesize) {
+                    let row = width as usize * 4;
+                    let mut buffer = Vec::with_capacity(row * height as usize);
+                    for y in 0..height as usize {
+                        let line = data.add(y * linesize as usize);
+                        buffer.extend_from_slice(std::slice::from_raw_parts(line, row));
+                    }
+                    gs_stagesurface_unmap(stagesurf);
+                    pixels = Some(buffer);
+                }
+            }
+
+            gs_stagesurface_destroy(stagesurf);
+            gs_texrender_destroy(texrender);
+            obs_leave_graphics();
+            pixels
+        }
+    }
+}
+
+impl Drop for TestHarness {
+    fn drop(&mut self) {
+        // Same order as OBS: sources are destroyed before modules unload.
+        unsafe { obs_shutdown() };
+        for module in self.modules.drain(..).rev() {
+            module();
+        }
+        self.contexts.clear();
+        crate::module::unload_module();
+    }
+}
@@ -0,0 +1,85 @@
+use crate::video::VideoInfo;
+use obs_sys::obs_get_video_frame_time;
+
+extern "C" {
+    // Part of `util/platform.h`, which is not covered by the generated bindings.
+    fn os_gettime_ns() -> u64;
+}
+
+/// Returns the current system time in nanoseconds, using the same clock OBS
+/// uses for frame and audio timestamps.
+pub fn now_ns() -> u64 {
+    unsafe { os_gettime_ns() }
+}
+
+/// Returns the timestamp of the video frame currently being rendered.
+pub fn video_frame_time() -> u64 {
+    unsafe { obs_get_video_frame_time() }
+}
+
+/// Returns the interval between two frames at `fps_num / fps_den` frames per
+/// second, in nanoseconds.
+pub fn frame_interval_ns(fps_num: u32, fps_den: u32) -> u64 {
+    if fps_num == 0 {
+        return 0;
+    }
+    1_000_000_000u64 * fps_den as u64 / fps_num as u64
+}
+
+/// Returns the interval between two frames of the main canvas, in nanoseconds.
+///
+/// Returns `None` if video has not been initialised yet.
+pub fn canvas_frame_interval_ns() -> Option<u64> {
+    VideoInfo::get().map(|info| frame_interval_ns(info.fps_num, info.fps_den))
+}
+
+/// Produces evenly paced timestamps for an async source, starting at the time
+/// of creation.
+///
+/// Timestamps are computed from the frame count rather than accumulated, so
+/// they do not drift for frame rates whose interval is not a whole number of
+/// nanoseconds.
+#[derive(Debug, Clone)]
+pub struct FrameClock {
+    start: u64,
+    fps_num: u32,
+    fps_den: u32,
+    frame: u64,
+}
+
+impl FrameClock {
+    pub fn new(fps_num: u32, fps_den: u32) -> Self {
+        Self {
+            start: now_ns(),
+            fps_num,
+            fps_den,
+            frame: 0,
+        }
+    }
+
+    /// Creates a clock running at the frame rate of the main canvas.
+    pub fn from_canvas() -> Option<Self> {
+        VideoInfo::get().map(|info| Self::new(info.fps_num, info.fps_den))
+    }
+
+    /// Returns the timestamp of the next frame.
+    pub fn next_timestamp(&mut self) -> u64 {
+        let ts = self.timestamp_of(self.frame);
+        self.frame += 1;
+        ts
+    }
+
+    /// Restarts the clock from the current time.
+    pub fn reset(&mut self) {
+        self.start = now_ns();
+        self.frame = 0;
+    }
+
+    fn timestamp_of(&self, frame: u64) -> u64 {
+        if self.fps_num == 0 {
+            return self.start;
+        }
+        let elapsed = frame as u128 * 1_000_000_000 * self.fps_den as u128 / self.fps_num as u128;
+        self.start + elapsed as u64
+    }
+}
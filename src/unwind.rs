@@ -0,0 +1,201 @@
+//! Panics must never unwind into OBS, so every callback the wrapper hands to
+//! OBS runs inside [`handle_unwind`]. When a callback panics the panic is
+//! logged together with the name of the callback, the panic message and,
+//! if enabled, a backtrace. Plugins can additionally install their own hook
+//! with [`set_panic_hook`] to forward crashes to their own reporting, and
+//! describe their state with [`crash::annotate`](crate::crash::annotate).
+//!
+//! ```no_run
+//! use obs_wrapper::unwind;
+//!
+//! unwind::set_capture_backtrace(true);
+//! unwind::set_panic_hook(|report| {
+//!     eprintln!("{} panicked: {}", report.callback(), report.message());
+//! });
+//! ```
+
+use crate::{crash, profiler};
+use std::any::{type_name, Any};
+use std::backtrace::Backtrace;
+use std::cell::{Cell, RefCell};
+use std::fmt::Display;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Once, RwLock};
+
+type PanicHook = Box<dyn Fn(&PanicReport) + Send + Sync>;
+
+static PANIC_HOOK: RwLock<Option<PanicHook>> = RwLock::new(None);
+static CAPTURE_BACKTRACE: AtomicBool = AtomicBool::new(false);
+static INSTALL_HOOK: Once = Once::new();
+
+thread_local! {
+    static CALLBACK_DEPTH: Cell<usize> = const { Cell::new(0) };
+    static LAST_PANIC: RefCell<Option<(Option<String>, Option<Backtrace>)>> = const { RefCell::new(None) };
+}
+
+/// Details of a panic caught at the boundary between OBS and a plugin.
+pub struct PanicReport<'a> {
+    callback: &'a str,
+    message: &'a str,
+    location: Option<&'a str>,
+    backtrace: Option<&'a Backtrace>,
+}
+
+impl<'a> PanicReport<'a> {
+    /// The callback that panicked, e.g. `my_plugin::MySource::video_render`.
+    pub fn callback(&self) -> &str {
+        self.callback
+    }
+
+    /// The message the panic was raised with.
+    pub fn message(&self) -> &str {
+        self.message
+    }
+
+    /// The `file:line:column` the panic was raised at.
+    pub fn location(&self) -> Option<&str> {
+        self.location
+    }
+
+    /// The backtrace of the panic, only captured when
+    /// [`set_capture_backtrace`] is enabled.
+    pub fn backtrace(&self) -> Option<&Backtrace> {
+        self.backtrace
+    }
+}
+
+/// Installs a hook that is called with every panic caught in a callback,
+/// replacing any previously installed hook.
+///
+/// The hook runs after the panic has been logged, on whichever thread OBS
+/// invoked the callback on.
+pub fn set_panic_hook<F: Fn(&PanicReport) + Send + Sync + 'static>(hook: F) {
+    *PANIC_HOOK.write().unwrap_or_else(|e| e.into_inner()) = Some(Box::new(hook));
+}
+
+/// Removes the hook installed with [`set_panic_hook`].
+pub fn reset_panic_hook() {
+    *PANIC_HOOK.write().unwrap_or_else(|e| e.into_inner()) = None;
+}
+
+/// Sets whether a backtrace is captured for panics in callbacks. Capturing is
+/// disabled by default since it is expensive.
+pub fn set_capture_backtrace(enabled: bool) {
+    CAPTURE_BACKTRACE.store(enabled, Ordering::Relaxed);
+}
+
+/// Chains onto the std panic hook so that the location and backtrace of a
+/// panic are recorded while the stack of the panicking callback still exists.
+///
+/// Called once when the module is loaded, rather than by every callback.
+pub(crate) fn install_hook() {
+    INSTALL_HOOK.call_once(|| {
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if CALLBACK_DEPTH.with(|depth| depth.get()) > 0 {
+                let location = info
+                    .location()
+                    .map(|l| format!("{}:{}:{}", l.file(), l.line(), l.column()));
+                let backtrace = if CAPTURE_BACKTRACE.load(Ordering::Relaxed) {
+                    Some(Backtrace::force_capture())
+                } else {
+                    None
+                };
+                LAST_PANIC.with(|last| *last.borrow_mut() = Some((location, backtrace)));
+            }
+            previous(info);
+        }));
+    });
+}
+
+fn payload_message(payload: &(dyn Any + Send)) -> &str {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.as_str()
+    } else {
+        "Box<dyn Any>"
+    }
+}
+
+fn report_panic(callback: &str, payload: Box<dyn Any + Send>) {
+    let message = payload_message(payload.as_ref());
+    let (location, backtrace) = LAST_PANIC
+        .with(|last| last.borrow_mut().take())
+        .unwrap_or((None, None));
+
+    match &location {
+        Some(location) => crate::error!(
+            "Panic in callback {} at {}: {}",
+            callback,
+            location,
+            message
+        ),
+        None => crate::error!("Panic in callback {}: {}", callback, message),
+    }
+    if let Some(backtrace) = &backtrace {
+        crate::error!("Backtrace:\n{}", backtrace);
+    }
+    if !crash::annotations().is_empty() {
+        crate::error!("Crash annotations:");
+        crash::log_annotations();
+    }
+
+    let report = PanicReport {
+        callback,
+        message,
+        location: location.as_deref(),
+        backtrace: backtrace.as_ref(),
+    };
+
+    if let Ok(hook) = PANIC_HOOK.read() {
+        if let Some(hook) = hook.as_ref() {
+            // A panicking hook must not unwind into OBS either.
+            let _ = panic::catch_unwind(AssertUnwindSafe(|| hook(&report)));
+        }
+    }
+}
+
+/// Runs `func`, catching any panic so that it does not unwind into OBS.
+///
+/// The callback is reported as `<T>::<callback>`, so `T` should be the type
+/// implementing the callback. Returns `None` if `func` panicked.
+///
+/// Callbacks registered through this crate are already wrapped, this only
+/// needs to be used for callbacks passed to OBS through [`obs_sys`](crate::obs_sys).
+pub fn handle_unwind<T: ?Sized, R>(callback: &str, func: impl FnOnce() -> R) -> Option<R> {
+    let _scope = profiler::callback_scope(type_name::<T>(), callback);
+
+    CALLBACK_DEPTH.with(|depth| depth.set(depth.get() + 1));
+    let result = panic::catch_unwind(AssertUnwindSafe(func));
+    CALLBACK_DEPTH.with(|depth| depth.set(depth.get() - 1));
+
+    match result {
+        Ok(value) => Some(value),
+        Err(payload) => {
+            report_panic(&format!("{}::{}", type_name::<T>(), callback), payload);
+            None
+        }
+    }
+}
+
+/// Runs `func` like [`handle_unwind`], also logging the error it returns as
+/// coming from `<T>::<callback>`. Returns `None` if `func` panicked or failed.
+pub fn handle_result<T: ?Sized, R, E: Display>(
+    callback: &str,
+    func: impl FnOnce() -> Result<R, E>,
+) -> Option<R> {
+    match handle_unwind::<T, _>(callback, func)? {
+        Ok(value) => Some(value),
+        Err(err) => {
+            crate::error!(
+                "Error in callback {}::{}: {}",
+                type_name::<T>(),
+                callback,
+                err
+            );
+            None
+        }
+    }
+}
@@ -0,0 +1,154 @@
+//! The version of libobs a plugin is running against, which may be newer than
+//! the one it was compiled for:
+//!
+//! ```ignore
+//! if obs_wrapper::version() >= Version::new(26, 1, 0) {
+//!     props.add(..., UrlButtonProp::new(url));
+//! }
+//! ```
+//!
+//! Functions newer than the generated bindings are looked up by this crate
+//! when first used, so plugins using them still load on an older OBS, where
+//! the wrappers do nothing or return `None`. The `supports_*` functions report
+//! whether they were found, for a single build to degrade gracefully.
+
+use obs_sys::{
+    obs_get_version, obs_get_version_string, LIBOBS_API_MAJOR_VER, LIBOBS_API_MINOR_VER,
+    LIBOBS_API_PATCH_VER,
+};
+
+use std::ffi::CStr;
+use std::fmt;
+
+/// A libobs version, ordered by its components.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Version {
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
+}
+
+impl Version {
+    /// The version of the headers the bindings were generated from.
+    pub const BINDINGS: Version = Version::new(
+        LIBOBS_API_MAJOR_VER,
+        LIBOBS_API_MINOR_VER,
+        LIBOBS_API_PATCH_VER,
+    );
+
+    pub const fn new(major: u32, minor: u32, patch: u32) -> Self {
+        Self {
+            major,
+            minor,
+            patch,
+        }
+    }
+
+    /// Unpacks a version made by `MAKE_SEMANTIC_VERSION`, as returned by
+    /// `obs_get_version`.
+    pub const fn from_packed(packed: u32) -> Self {
+        Self::new(packed >> 24, (packed >> 16) & 0xFF, packed & 0xFFFF)
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+/// Returns the version of the running libobs.
+///
+/// See [OBS documentation](https://obsproject.com/docs/reference-core.html#c.obs_get_version)
+pub fn version() -> Version {
+    Version::from_packed(unsafe { obs_get_version() })
+}
+
+/// Returns the full version string of the running libobs, which may carry a
+/// suffix such as `-rc1` or a distribution tag.
+pub fn version_string() -> Option<String> {
+    unsafe {
+        let ptr = obs_get_version_string();
+        if ptr.is_null() {
+            None
+        } else {
+            Some(CStr::from_ptr(ptr).to_string_lossy().into_owned())
+        }
+    }
+}
+
+/// Returns whether the running libobs is at least `major.minor.patch`.
+pub fn at_least(major: u32, minor: u32, patch: u32) -> bool {
+    version() >= Version::new(major, minor, patch)
+}
+
+/// Whether media controls such as
+/// [`SourceContext::media_play_pause`](crate::source::SourceContext::media_play_pause)
+/// are available, added in OBS 26.0.
+pub fn supports_media_controls() -> bool {
+    at_least(26, 0, 0)
+}
+
+/// Whether the virtual camera can be controlled with
+/// [`frontend::start_virtualcam`](crate::frontend::start_virtualcam), added in
+/// OBS 26.0.
+#[cfg(feature = "frontend")]
+pub fn supports_virtualcam() -> bool {
+    use crate::frontend::{
+        obs_frontend_start_virtualcam, obs_frontend_stop_virtualcam, obs_frontend_virtualcam_active,
+    };
+    obs_frontend_start_virtualcam.is_available()
+        && obs_frontend_stop_virtualcam.is_available()
+        && obs_frontend_virtualcam_active.is_available()
+}
+
+/// Whether services can declare their protocol and codecs, as done with
+/// [`GetProtocolService`](crate::service::GetProtocolService), added in
+/// OBS 29.1. Services are required to declare their protocol from then on.
+pub fn supports_service_protocols() -> bool {
+    at_least(29, 1, 0)
+}
+
+/// Whether plugins can add docks by id with
+/// [`frontend::add_dock`](crate::frontend::add_dock), added in OBS 30.0.
+#[cfg(feature = "frontend")]
+pub fn supports_docks() -> bool {
+    use crate::frontend::{obs_frontend_add_dock_by_id, obs_frontend_remove_dock};
+    obs_frontend_add_dock_by_id.is_available() && obs_frontend_remove_dock.is_available()
+}
+
+/// Whether buttons can open URLs, as used by
+/// [`UrlButtonProp`](crate::source::UrlButtonProp), added in OBS 26.1.
+pub fn supports_url_buttons() -> bool {
+    use crate::source::properties::{obs_property_button_set_type, obs_property_button_set_url};
+    obs_property_button_set_type.is_available() && obs_property_button_set_url.is_available()
+}
+
+/// Whether info texts can be shown as warnings or errors, as set by
+/// [`Property::set_info_type`](crate::source::properties::Property::set_info_type),
+/// added in OBS 27.0.
+pub fn supports_info_types() -> bool {
+    crate::source::properties::obs_property_text_set_info_type.is_available()
+}
+
+/// Whether rendering is sRGB aware, so linear sampling and
+/// `gs_effect_set_texture_srgb` behave as documented, added in OBS 27.0.
+pub fn supports_srgb() -> bool {
+    at_least(27, 0, 0)
+}
+
+/// Whether scene items have blending modes, as set by
+/// [`SceneItem::set_blending_mode`](crate::scene::SceneItem::set_blending_mode),
+/// added in OBS 27.0.
+pub fn supports_blending_modes() -> bool {
+    use crate::scene::{obs_sceneitem_get_blending_mode, obs_sceneitem_set_blending_mode};
+    obs_sceneitem_set_blending_mode.is_available() && obs_sceneitem_get_blending_mode.is_available()
+}
+
+/// Whether DMA-BUFs can be imported with
+/// [`GraphicsTexture::from_dmabuf`](crate::graphics::GraphicsTexture::from_dmabuf),
+/// added in OBS 27.1.
+#[cfg(target_os = "linux")]
+pub fn supports_dmabuf() -> bool {
+    crate::graphics::gs_texture_create_from_dmabuf.is_available()
+}
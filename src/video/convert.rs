@@ -0,0 +1,731 @@
+use super::{VideoColorspace, VideoDataContext, VideoFormat};
+
+/// A plane of an image: `plane_height` rows of visible bytes, each starting
+/// `stride` bytes after the previous one.
+#[derive(Clone, Copy)]
+pub struct Plane<'a> {
+    pub data: &'a [u8],
+    pub stride: usize,
+}
+
+/// A writable plane of an image, see [`Plane`].
+pub struct PlaneMut<'a> {
+    pub data: &'a mut [u8],
+    pub stride: usize,
+}
+
+/// How YUV values relate to RGB in a conversion.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct YuvParams {
+    /// The matrix used. `VideoColorspace::Default` is BT.601, as it is in
+    /// OBS, and `VideoColorspace::SRGB` uses the BT.709 matrix.
+    pub colorspace: VideoColorspace,
+    /// Whether values use the full 0-255 range instead of 16-235 for luma and
+    /// 16-240 for chroma.
+    pub full_range: bool,
+}
+
+impl Default for YuvParams {
+    /// Partial range BT.601, the OBS defaults.
+    fn default() -> Self {
+        Self {
+            colorspace: VideoColorspace::Default,
+            full_range: false,
+        }
+    }
+}
+
+/// Why [`convert`] could not convert an image.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum ConvertError {
+    /// Only NV12, I420, RGBA, BGRA and BGRX are supported.
+    UnsupportedFormat(VideoFormat),
+    /// The plane with this index was not passed.
+    MissingPlane(usize),
+    /// The stride of the plane with this index is shorter than a row, or the
+    /// plane does not hold every row.
+    PlaneTooSmall(usize),
+}
+
+impl std::fmt::Display for ConvertError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConvertError::UnsupportedFormat(format) => {
+                write!(f, "Unsupported video format {:?}", format)
+            }
+            ConvertError::MissingPlane(plane) => write!(f, "Plane {} is missing", plane),
+            ConvertError::PlaneTooSmall(plane) => write!(f, "Plane {} is too small", plane),
+        }
+    }
+}
+
+impl std::error::Error for ConvertError {}
+
+/// Converts a `width` x `height` image from `src_format` to `dst_format`.
+///
+/// `yuv` describes the YUV side of the conversion, and is ignored between two
+/// RGB or two YUV formats. Chroma is subsampled by averaging each 2x2 block of
+/// pixels.
+pub fn convert(
+    src_format: VideoFormat,
+    src: &[Plane],
+    dst_format: VideoFormat,
+    dst: &mut [PlaneMut],
+    width: u32,
+    height: u32,
+    yuv: YuvParams,
+) -> Result<(), ConvertError> {
+    check_format(src_format)?;
+    check_format(dst_format)?;
+    for plane in 0..src_format.planes() {
+        let src = src.get(plane).ok_or(ConvertError::MissingPlane(plane))?;
+        check_plane(src_format, plane, src.data.len(), src.stride, width, height)?;
+    }
+    for plane in 0..dst_format.planes() {
+        let dst = dst.get(plane).ok_or(ConvertError::MissingPlane(plane))?;
+        check_plane(dst_format, plane, dst.data.len(), dst.stride, width, height)?;
+    }
+
+    let (width, height) = (width as usize, height as usize);
+    let matrix = Matrix::new(yuv);
+
+    match (src_format.is_yuv(), dst_format.is_yuv()) {
+        (false, false) => {
+            for y in 0..height {
+                for x in 0..width {
+                    let pixel = read_rgba(src_format, &src[0], x, y);
+                    write_rgba(dst_format, &mut dst[0], x, y, pixel);
+                }
+            }
+        }
+        (true, false) => {
+            for y in 0..height {
+                for x in 0..width {
+                    let (luma, cb, cr) = read_yuv(src_format, src, x, y);
+                    let [r, g, b] = matrix.to_rgb(luma, cb, cr);
+                    write_rgba(dst_format, &mut dst[0], x, y, [r, g, b, 255]);
+                }
+            }
+        }
+        (false, true) => {
+            for y in 0..height {
+                for x in 0..width {
+                    let [r, g, b, _] = read_rgba(src_format, &src[0], x, y);
+                    dst[0].data[y * dst[0].stride + x] = matrix.luma(rgb_f32([r, g, b]));
+                }
+            }
+            for cy in 0..height.div_ceil(2) {
+                for cx in 0..width.div_ceil(2) {
+                    // Edge blocks of odd sizes repeat their last row or column.
+                    let mut sum = [0.; 3];
+                    for (x, y) in [(0, 0), (1, 0), (0, 1), (1, 1)] {
+                        let x = (cx * 2 + x).min(width - 1);
+                        let y = (cy * 2 + y).min(height - 1);
+                        let [r, g, b, _] = read_rgba(src_format, &src[0], x, y);
+                        let rgb = rgb_f32([r, g, b]);
+                        sum.iter_mut().zip(rgb.iter()).for_each(|(s, c)| *s += c);
+                    }
+                    let (cb, cr) = matrix.chroma(sum.map(|s| s / 4.));
+                    write_chroma(dst_format, dst, cx, cy, cb, cr);
+                }
+            }
+        }
+        (true, true) => {
+            for y in 0..height {
+                let row = &src[0].data[y * src[0].stride..][..width];
+                dst[0].data[y * dst[0].stride..][..width].copy_from_slice(row);
+            }
+            for cy in 0..height.div_ceil(2) {
+                for cx in 0..width.div_ceil(2) {
+                    let (cb, cr) = read_chroma(src_format, src, cx, cy);
+                    write_chroma(dst_format, dst, cx, cy, cb, cr);
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Converts `frame` to `dst_format`, see [`convert`].
+pub fn convert_frame(
+    frame: &VideoDataContext,
+    dst_format: VideoFormat,
+    dst: &mut [PlaneMut],
+    yuv: YuvParams,
+) -> Result<(), ConvertError> {
+    let mut src = Vec::with_capacity(frame.format().planes());
+    for plane in 0..frame.format().planes() {
+        let data = frame
+            .plane(plane)
+            .ok_or(ConvertError::MissingPlane(plane))?;
+        src.push(Plane {
+            data,
+            stride: frame.linesize(plane),
+        });
+    }
+    convert(
+        frame.format(),
+        &src,
+        dst_format,
+        dst,
+        frame.width(),
+        frame.height(),
+        yuv,
+    )
+}
+
+/// Number of bytes a tightly packed `width` x `height` image in `format`
+/// takes, with its planes one after the other.
+pub fn packed_size(format: VideoFormat, width: u32, height: u32) -> usize {
+    (0..format.planes())
+        .map(|plane| format.row_bytes(plane, width) * format.plane_height(plane, height) as usize)
+        .sum()
+}
+
+/// Splits a buffer of at least [`packed_size`] bytes into the tightly packed
+/// planes of a `width` x `height` image in `format`, e.g. to convert into a
+/// buffer handed to a virtual camera. Returns `None` if it is too small.
+pub fn packed_planes_mut(
+    format: VideoFormat,
+    width: u32,
+    height: u32,
+    mut buffer: &mut [u8],
+) -> Option<Vec<PlaneMut<'_>>> {
+    if buffer.len() < packed_size(format, width, height) {
+        return None;
+    }
+    let mut planes = Vec::with_capacity(format.planes());
+    for plane in 0..format.planes() {
+        let stride = format.row_bytes(plane, width);
+        let len = stride * format.plane_height(plane, height) as usize;
+        let (data, rest) = buffer.split_at_mut(len);
+        planes.push(PlaneMut { data, stride });
+        buffer = rest;
+    }
+    Some(planes)
+}
+
+fn check_format(format: VideoFormat) -> Result<(), ConvertError> {
+    match format {
+        VideoFormat::NV12
+        | VideoFormat::I420
+        | VideoFormat::RGBA
+        | VideoFormat::BGRA
+        | VideoFormat::BGRX => Ok(()),
+        _ => Err(ConvertError::UnsupportedFormat(format)),
+    }
+}
+
+fn check_plane(
+    format: VideoFormat,
+    plane: usize,
+    len: usize,
+    stride: usize,
+    width: u32,
+    height: u32,
+) -> Result<(), ConvertError> {
+    let row = format.row_bytes(plane, width);
+    let rows = format.plane_height(plane, height) as usize;
+    let needed = match rows {
+        0 => 0,
+        rows => stride * (rows - 1) + row,
+    };
+    if stride < row || len < needed {
+        Err(ConvertError::PlaneTooSmall(plane))
+    } else {
+        Ok(())
+    }
+}
+
+fn read_rgba(format: VideoFormat, plane: &Plane, x: usize, y: usize) -> [u8; 4] {
+    let p = &plane.data[y * plane.stride + x * 4..][..4];
+    match format {
+        VideoFormat::RGBA => [p[0], p[1], p[2], p[3]],
+        VideoFormat::BGRA => [p[2], p[1], p[0], p[3]],
+        _ => [p[2], p[1], p[0], 255],
+    }
+}
+
+fn write_rgba(
+    format: VideoFormat,
+    plane: &mut PlaneMut,
+    x: usize,
+    y: usize,
+    [r, g, b, a]: [u8; 4],
+) {
+    let p = &mut plane.data[y * plane.stride + x * 4..][..4];
+    match format {
+        VideoFormat::RGBA => p.copy_from_slice(&[r, g, b, a]),
+        VideoFormat::BGRA => p.copy_from_slice(&[b, g, r, a]),
+        _ => p.copy_from_slice(&[b, g, r, 255]),
+    }
+}
+
+fn read_yuv(format: VideoFormat, planes: &[Plane], x: usize, y: usize) -> (u8, u8, u8) {
+    let luma = planes[0].data[y * planes[0].stride + x];
+    let (cb, cr) = read_chroma(format, planes, x / 2, y / 2);
+    (luma, cb, cr)
+}
+
+fn read_chroma(format: VideoFormat, planes: &[Plane], cx: usize, cy: usize) -> (u8, u8) {
+    if format == VideoFormat::NV12 {
+        let p = &planes[1].data[cy * planes[1].stride + cx * 2..][..2];
+        (p[0], p[1])
+    } else {
+        (
+            planes[1].data[cy * planes[1].stride + cx],
+            planes[2].data[cy * planes[2].stride + cx],
+        )
+    }
+}
+
+fn write_chroma(
+    format: VideoFormat,
+    planes: &mut [PlaneMut],
+    cx: usize,
+    cy: usize,
+    cb: u8,
+    cr: u8,
+) {
+    if format == VideoFormat::NV12 {
+        let stride = planes[1].stride;
+        planes[1].data[cy * stride + cx * 2..][..2].copy_from_slice(&[cb, cr]);
+    } else {
+        let stride = planes[1].stride;
+        planes[1].data[cy * stride + cx] = cb;
+        let stride = planes[2].stride;
+        planes[2].data[cy * stride + cx] = cr;
+    }
+}
+
+fn rgb_f32(rgb: [u8; 3]) -> [f32; 3] {
+    rgb.map(|c| c as f32)
+}
+
+fn clamp_u8(value: f32) -> u8 {
+    value.round().clamp(0., 255.) as u8
+}
+
+/// The coefficients of a YUV matrix, with the range of its values.
+struct Matrix {
+    kr: f32,
+    kb: f32,
+    /// Offset and scale of luma, and scale of chroma, from full range.
+    y_offset: f32,
+    y_scale: f32,
+    c_scale: f32,
+}
+
+impl Matrix {
+    fn new(yuv: YuvParams) -> Self {
+        let (kr, kb) = match yuv.colorspace {
+            VideoColorspace::CS709 | VideoColorspace::SRGB => (0.2126, 0.0722),
+            VideoColorspace::Default | VideoColorspace::CS601 => (0.299, 0.114),
+        };
+        let (y_offset, y_scale, c_scale) = if yuv.full_range {
+            (0., 1., 1.)
+        } else {
+            (16., 219. / 255., 224. / 255.)
+        };
+        Self {
+            kr,
+            kb,
+            y_offset,
+            y_scale,
+            c_scale,
+        }
+    }
+
+    fn luma(&self, [r, g, b]: [f32; 3]) -> u8 {
+        let kg = 1. - self.kr - self.kb;
+        let y = self.kr * r + kg * g + self.kb * b;
+        clamp_u8(y * self.y_scale + self.y_offset)
+    }
+
+    fn chroma(&self, [r, g, b]: [f32; 3]) -> (u8, u8) {
+        let kg = 1. - self.kr - self.kb;
+        let y = self.kr * r + kg * g + self.kb * b;
+        let cb = (b - y) / (2. * (1. - self.kb));
+        let cr = (r - y) / (2. * (1. - self.kr));
+        (
+            clamp_u8(cb * self.c_scale + 128.),
+            clamp_u8(cr * self.c_scale + 128.),
+        )
+    }
+
+    fn to_rgb(&self, luma: u8, cb: u8, cr: u8) -> [u8; 3] {
+        let kg = 1. - self.kr - self.kb;
+        let y = (luma as f32 - self.y_offset) / self.y_scale;
+        let cb = (cb as f32 - 128.) / self.c_scale;
+        let cr = (cr as f32 - 128.) / self.c_scale;
+        let r = y + 2. * (1. - self.kr) * cr;
+        let b = y + 2. * (1. - self.kb) * cb;
+        let g = y
+            - (2. * self.kb * (1. - self.kb) / kg) * cb
+            - (2. * self.kr * (1. - self.kr) / kg) * cr;
+        [clamp_u8(r), clamp_u8(g), clamp_u8(b)]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PALETTE: [[u8; 4]; 6] = [
+        [200, 30, 60, 255],
+        [20, 180, 90, 255],
+        [90, 90, 220, 255],
+        [128, 128, 128, 255],
+        [240, 220, 40, 255],
+        [16, 16, 16, 255],
+    ];
+
+    /// An RGBA image whose 2x2 blocks have a single colour each, so that
+    /// subsampling the chroma loses nothing.
+    fn blocks(width: usize, height: usize, stride: usize) -> Vec<u8> {
+        let mut data = vec![0; stride * height];
+        for y in 0..height {
+            for x in 0..width {
+                let color = PALETTE[(x / 2 + y / 2 * 3) % PALETTE.len()];
+                data[y * stride + x * 4..][..4].copy_from_slice(&color);
+            }
+        }
+        data
+    }
+
+    fn to_packed(
+        src_format: VideoFormat,
+        src: &[Plane],
+        dst_format: VideoFormat,
+        width: u32,
+        height: u32,
+        yuv: YuvParams,
+    ) -> Vec<u8> {
+        let mut buffer = vec![0; packed_size(dst_format, width, height)];
+        let mut dst = packed_planes_mut(dst_format, width, height, &mut buffer).unwrap();
+        convert(src_format, src, dst_format, &mut dst, width, height, yuv).unwrap();
+        buffer
+    }
+
+    fn packed_planes(format: VideoFormat, width: u32, height: u32, buffer: &[u8]) -> Vec<Plane> {
+        let mut planes = Vec::new();
+        let mut offset = 0;
+        for plane in 0..format.planes() {
+            let stride = format.row_bytes(plane, width);
+            let len = stride * format.plane_height(plane, height) as usize;
+            planes.push(Plane {
+                data: &buffer[offset..offset + len],
+                stride,
+            });
+            offset += len;
+        }
+        planes
+    }
+
+    fn round_trip(format: VideoFormat, width: u32, height: u32, yuv: YuvParams) {
+        let rgba = blocks(width as usize, height as usize, width as usize * 4);
+        let src = [Plane {
+            data: &rgba,
+            stride: width as usize * 4,
+        }];
+        let yuv_data = to_packed(VideoFormat::RGBA, &src, format, width, height, yuv);
+        let planes = packed_planes(format, width, height, &yuv_data);
+        let back = to_packed(format, &planes, VideoFormat::RGBA, width, height, yuv);
+
+        for (i, (a, b)) in rgba.iter().zip(back.iter()).enumerate() {
+            assert!(
+                (*a as i32 - *b as i32).abs() <= 3,
+                "{:?} {}x{} byte {}: {} became {}",
+                format,
+                width,
+                height,
+                i,
+                a,
+                b
+            );
+        }
+    }
+
+    fn yuv_of(rgb: [u8; 3], yuv: YuvParams) -> (u8, u8, u8) {
+        let rgba = [rgb[0], rgb[1], rgb[2], 255].repeat(4);
+        let src = [Plane {
+            data: &rgba,
+            stride: 8,
+        }];
+        let nv12 = to_packed(VideoFormat::RGBA, &src, VideoFormat::NV12, 2, 2, yuv);
+        (nv12[0], nv12[4], nv12[5])
+    }
+
+    #[test]
+    fn nv12_round_trip() {
+        round_trip(VideoFormat::NV12, 8, 6, YuvParams::default());
+        round_trip(
+            VideoFormat::NV12,
+            8,
+            6,
+            YuvParams {
+                colorspace: VideoColorspace::CS709,
+                full_range: true,
+            },
+        );
+    }
+
+    #[test]
+    fn i420_round_trip() {
+        round_trip(VideoFormat::I420, 8, 6, YuvParams::default());
+        round_trip(
+            VideoFormat::I420,
+            8,
+            6,
+            YuvParams {
+                colorspace: VideoColorspace::CS709,
+                full_range: false,
+            },
+        );
+    }
+
+    #[test]
+    fn odd_sizes_round_trip() {
+        round_trip(VideoFormat::NV12, 5, 3, YuvParams::default());
+        round_trip(VideoFormat::I420, 7, 1, YuvParams::default());
+        round_trip(VideoFormat::I420, 1, 5, YuvParams::default());
+    }
+
+    #[test]
+    fn odd_sizes_repeat_the_edge_pixels() {
+        // The last column is a single pixel wide, and is repeated to fill its
+        // block rather than averaged with whatever follows it.
+        let rgba = [[255, 0, 0, 255], [255, 0, 0, 255], [0, 0, 255, 255]].concat();
+        let src = [Plane {
+            data: &rgba,
+            stride: 12,
+        }];
+        let yuv = YuvParams::default();
+        let i420 = to_packed(VideoFormat::RGBA, &src, VideoFormat::I420, 3, 1, yuv);
+        assert_eq!(i420.len(), 3 + 2 + 2);
+
+        let (_, red_cb, red_cr) = yuv_of([255, 0, 0], yuv);
+        let (_, blue_cb, blue_cr) = yuv_of([0, 0, 255], yuv);
+        assert_eq!(&i420[3..], &[red_cb, blue_cb, red_cr, blue_cr]);
+    }
+
+    #[test]
+    fn padded_strides() {
+        let (width, height) = (6, 4);
+        let packed = blocks(width, height, width * 4);
+        let padded = blocks(width, height, width * 4 + 12);
+        let yuv = YuvParams::default();
+
+        let expected = to_packed(
+            VideoFormat::RGBA,
+            &[Plane {
+                data: &packed,
+                stride: width * 4,
+            }],
+            VideoFormat::NV12,
+            width as u32,
+            height as u32,
+            yuv,
+        );
+
+        let mut luma = vec![0xAA; 10 * height];
+        let mut chroma = vec![0xAA; 9 * height / 2];
+        convert(
+            VideoFormat::RGBA,
+            &[Plane {
+                data: &padded,
+                stride: width * 4 + 12,
+            }],
+            VideoFormat::NV12,
+            &mut [
+                PlaneMut {
+                    data: &mut luma,
+                    stride: 10,
+                },
+                PlaneMut {
+                    data: &mut chroma,
+                    stride: 9,
+                },
+            ],
+            width as u32,
+            height as u32,
+            yuv,
+        )
+        .unwrap();
+
+        for y in 0..height {
+            assert_eq!(&luma[y * 10..][..width], &expected[y * width..][..width]);
+            assert!(luma[y * 10 + width..][..4].iter().all(|&b| b == 0xAA));
+        }
+        let expected_chroma = &expected[width * height..];
+        for y in 0..height / 2 {
+            assert_eq!(
+                &chroma[y * 9..][..width],
+                &expected_chroma[y * width..][..width]
+            );
+            assert!(chroma[y * 9 + width..][..3].iter().all(|&b| b == 0xAA));
+        }
+    }
+
+    #[test]
+    fn missing_plane() {
+        let rgba = blocks(2, 2, 8);
+        let mut luma = [0; 4];
+        let result = convert(
+            VideoFormat::RGBA,
+            &[Plane {
+                data: &rgba,
+                stride: 8,
+            }],
+            VideoFormat::NV12,
+            &mut [PlaneMut {
+                data: &mut luma,
+                stride: 2,
+            }],
+            2,
+            2,
+            YuvParams::default(),
+        );
+        assert_eq!(result, Err(ConvertError::MissingPlane(1)));
+
+        let result = convert(
+            VideoFormat::I420,
+            &[],
+            VideoFormat::RGBA,
+            &mut [],
+            2,
+            2,
+            YuvParams::default(),
+        );
+        assert_eq!(result, Err(ConvertError::MissingPlane(0)));
+    }
+
+    #[test]
+    fn plane_too_small() {
+        let rgba = blocks(4, 2, 16);
+        let src = [Plane {
+            data: &rgba,
+            stride: 16,
+        }];
+        let mut luma = [0; 8];
+        let mut chroma = [0; 4];
+
+        // A stride shorter than a row.
+        let result = convert(
+            VideoFormat::RGBA,
+            &src,
+            VideoFormat::NV12,
+            &mut [
+                PlaneMut {
+                    data: &mut luma,
+                    stride: 3,
+                },
+                PlaneMut {
+                    data: &mut chroma,
+                    stride: 4,
+                },
+            ],
+            4,
+            2,
+            YuvParams::default(),
+        );
+        assert_eq!(result, Err(ConvertError::PlaneTooSmall(0)));
+
+        // A plane without room for every row.
+        let result = convert(
+            VideoFormat::RGBA,
+            &src,
+            VideoFormat::NV12,
+            &mut [
+                PlaneMut {
+                    data: &mut luma,
+                    stride: 4,
+                },
+                PlaneMut {
+                    data: &mut chroma[..3],
+                    stride: 4,
+                },
+            ],
+            4,
+            2,
+            YuvParams::default(),
+        );
+        assert_eq!(result, Err(ConvertError::PlaneTooSmall(1)));
+
+        let result = convert(
+            VideoFormat::RGBA,
+            &[Plane {
+                data: &rgba[..20],
+                stride: 16,
+            }],
+            VideoFormat::BGRA,
+            &mut [PlaneMut {
+                data: &mut [0; 32],
+                stride: 16,
+            }],
+            4,
+            2,
+            YuvParams::default(),
+        );
+        assert_eq!(result, Err(ConvertError::PlaneTooSmall(0)));
+    }
+
+    #[test]
+    fn unsupported_format() {
+        let result = convert(
+            VideoFormat::YUY2,
+            &[],
+            VideoFormat::RGBA,
+            &mut [],
+            2,
+            2,
+            YuvParams::default(),
+        );
+        assert_eq!(
+            result,
+            Err(ConvertError::UnsupportedFormat(VideoFormat::YUY2))
+        );
+    }
+
+    #[test]
+    fn reference_values() {
+        let bt601 = YuvParams {
+            colorspace: VideoColorspace::CS601,
+            full_range: false,
+        };
+        let bt709 = YuvParams {
+            colorspace: VideoColorspace::CS709,
+            full_range: false,
+        };
+        let bt601_full = YuvParams {
+            colorspace: VideoColorspace::CS601,
+            full_range: true,
+        };
+        let bt709_full = YuvParams {
+            colorspace: VideoColorspace::CS709,
+            full_range: true,
+        };
+
+        assert_eq!(yuv_of([255, 255, 255], bt601), (235, 128, 128));
+        assert_eq!(yuv_of([0, 0, 0], bt601), (16, 128, 128));
+        assert_eq!(yuv_of([255, 255, 255], bt601_full), (255, 128, 128));
+        assert_eq!(yuv_of([0, 0, 0], bt601_full), (0, 128, 128));
+
+        assert_eq!(yuv_of([255, 0, 0], bt601), (81, 90, 240));
+        assert_eq!(yuv_of([255, 0, 0], bt709), (63, 102, 240));
+        assert_eq!(yuv_of([255, 0, 0], bt601_full), (76, 85, 255));
+        assert_eq!(yuv_of([255, 0, 0], bt709_full), (54, 99, 255));
+
+        // The OBS default colorspace is BT.601, and sRGB uses the BT.709
+        // matrix.
+        assert_eq!(
+            yuv_of([255, 0, 0], YuvParams::default()),
+            yuv_of([255, 0, 0], bt601)
+        );
+        let srgb = YuvParams {
+            colorspace: VideoColorspace::SRGB,
+            full_range: false,
+        };
+        assert_eq!(yuv_of([255, 0, 0], srgb), yuv_of([255, 0, 0], bt709));
+    }
+}
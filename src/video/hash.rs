@@ -0,0 +1,104 @@
+use super::VideoDataContext;
+use crate::graphics::MappedTexture;
+use std::hash::Hasher;
+use twox_hash::XxHash64;
+
+/// Types whose pixel contents can be hashed to detect duplicated frames.
+pub trait FrameHash {
+    /// Returns a fast, non-cryptographic hash of the visible pixels.
+    fn frame_hash(&self) -> u64;
+}
+
+/// Hashes a tightly packed pixel buffer.
+pub fn hash_bytes(data: &[u8]) -> u64 {
+    let mut hasher = XxHash64::with_seed(0);
+    hasher.write(data);
+    hasher.finish()
+}
+
+impl FrameHash for VideoDataContext {
+    /// Only the visible bytes of each row are hashed, so line padding does not
+    /// affect the result.
+    fn frame_hash(&self) -> u64 {
+        let mut hasher = XxHash64::with_seed(0);
+        for plane in 0..self.format().planes() {
+            for row in self.rows(plane) {
+                hasher.write(row);
+            }
+        }
+        hasher.finish()
+    }
+}
+
+impl FrameHash for MappedTexture<'_> {
+    /// Hashes the whole mapped buffer, including any line padding.
+    fn frame_hash(&self) -> u64 {
+        hash_bytes(self)
+    }
+}
+
+/// The result of feeding a frame into a [`FreezeDetector`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum FrameStatus {
+    /// The frame differs from the previous one.
+    New,
+    /// The frame is identical to the previous one, which has now been seen
+    /// this many times in a row.
+    Duplicate(u32),
+    /// The frame has been repeated at least as many times as the detector's
+    /// threshold.
+    Frozen(u32),
+}
+
+/// Detects captures that stopped updating by counting how many consecutive
+/// frames share the same hash.
+#[derive(Debug, Clone)]
+pub struct FreezeDetector {
+    last: Option<u64>,
+    repeats: u32,
+    threshold: u32,
+}
+
+impl FreezeDetector {
+    /// Creates a detector that reports [`FrameStatus::Frozen`] once the same
+    /// frame has been seen `threshold` times in a row.
+    pub fn new(threshold: u32) -> Self {
+        Self {
+            last: None,
+            repeats: 0,
+            threshold,
+        }
+    }
+
+    /// Hashes `frame` and records it.
+    pub fn push_frame<F: FrameHash + ?Sized>(&mut self, frame: &F) -> FrameStatus {
+        self.push_hash(frame.frame_hash())
+    }
+
+    /// Records a frame by its precomputed hash.
+    pub fn push_hash(&mut self, hash: u64) -> FrameStatus {
+        if self.last == Some(hash) {
+            self.repeats = self.repeats.saturating_add(1);
+        } else {
+            self.last = Some(hash);
+            self.repeats = 1;
+            return FrameStatus::New;
+        }
+
+        if self.repeats >= self.threshold {
+            FrameStatus::Frozen(self.repeats)
+        } else {
+            FrameStatus::Duplicate(self.repeats)
+        }
+    }
+
+    pub fn is_frozen(&self) -> bool {
+        self.last.is_some() && self.repeats >= self.threshold
+    }
+
+    /// Forgets the previous frame, e.g. after the source was reconfigured.
+    pub fn reset(&mut self) {
+        self.last = None;
+        self.repeats = 0;
+    }
+}
@@ -0,0 +1,395 @@
+#![allow(non_upper_case_globals)]
+
+use crate::native_enum;
+use crate::unwind::handle_unwind;
+use obs_sys::{
+    obs_add_raw_video_callback, obs_get_video, obs_get_video_info, obs_remove_raw_video_callback,
+    obs_video_info, video_colorspace, video_colorspace_VIDEO_CS_601, video_colorspace_VIDEO_CS_709,
+    video_colorspace_VIDEO_CS_DEFAULT, video_colorspace_VIDEO_CS_SRGB, video_data, video_format,
+    video_format_VIDEO_FORMAT_AYUV, video_format_VIDEO_FORMAT_BGR3, video_format_VIDEO_FORMAT_BGRA,
+    video_format_VIDEO_FORMAT_BGRX, video_format_VIDEO_FORMAT_I40A, video_format_VIDEO_FORMAT_I420,
+    video_format_VIDEO_FORMAT_I422, video_format_VIDEO_FORMAT_I42A, video_format_VIDEO_FORMAT_I444,
+    video_format_VIDEO_FORMAT_NONE, video_format_VIDEO_FORMAT_NV12, video_format_VIDEO_FORMAT_RGBA,
+    video_format_VIDEO_FORMAT_UYVY, video_format_VIDEO_FORMAT_Y800, video_format_VIDEO_FORMAT_YUVA,
+    video_format_VIDEO_FORMAT_YUY2, video_format_VIDEO_FORMAT_YVYU, video_output_active,
+    video_output_get_format, video_output_get_frame_rate, video_output_get_frame_time,
+    video_output_get_height, video_output_get_width, video_scale_info, video_t,
+};
+
+use std::{ffi::c_void, slice};
+
+/// Conversions between NV12, I420 and RGB frames
+pub mod convert;
+/// Frame hashing and duplicate frame detection
+pub mod hash;
+
+native_enum!(VideoFormat, video_format {
+    None => VIDEO_FORMAT_NONE,
+    I420 => VIDEO_FORMAT_I420,
+    NV12 => VIDEO_FORMAT_NV12,
+    YVYU => VIDEO_FORMAT_YVYU,
+    YUY2 => VIDEO_FORMAT_YUY2,
+    UYVY => VIDEO_FORMAT_UYVY,
+    RGBA => VIDEO_FORMAT_RGBA,
+    BGRA => VIDEO_FORMAT_BGRA,
+    BGRX => VIDEO_FORMAT_BGRX,
+    Y800 => VIDEO_FORMAT_Y800,
+    I444 => VIDEO_FORMAT_I444,
+    BGR3 => VIDEO_FORMAT_BGR3,
+    I422 => VIDEO_FORMAT_I422,
+    I40A => VIDEO_FORMAT_I40A,
+    I42A => VIDEO_FORMAT_I42A,
+    YUVA => VIDEO_FORMAT_YUVA,
+    AYUV => VIDEO_FORMAT_AYUV
+});
+
+native_enum!(VideoColorspace, video_colorspace {
+    Default => VIDEO_CS_DEFAULT,
+    CS601 => VIDEO_CS_601,
+    CS709 => VIDEO_CS_709,
+    SRGB => VIDEO_CS_SRGB
+});
+
+impl VideoFormat {
+    /// Number of planes a frame of this format is made of.
+    pub fn planes(self) -> usize {
+        match self {
+            Self::None => 0,
+            Self::NV12 => 2,
+            Self::I420 | Self::I444 | Self::I422 => 3,
+            Self::I40A | Self::I42A | Self::YUVA => 4,
+            _ => 1,
+        }
+    }
+
+    /// Whether the format stores luma and chroma rather than RGB.
+    pub fn is_yuv(self) -> bool {
+        !matches!(
+            self,
+            Self::None | Self::RGBA | Self::BGRA | Self::BGRX | Self::Y800 | Self::BGR3
+        )
+    }
+
+    /// Number of visible bytes in a single row of `plane`, excluding any
+    /// padding OBS may add to the line size.
+    pub fn row_bytes(self, plane: usize, width: u32) -> usize {
+        let width = width as usize;
+        match (self, plane) {
+            (Self::None, _) => 0,
+            (Self::RGBA, 0) | (Self::BGRA, 0) | (Self::BGRX, 0) | (Self::AYUV, 0) => width * 4,
+            (Self::BGR3, 0) => width * 3,
+            (Self::YVYU, 0) | (Self::YUY2, 0) | (Self::UYVY, 0) => width * 2,
+            (Self::NV12, 1) => width.div_ceil(2) * 2,
+            (Self::I420, 1..=2) | (Self::I422, 1..=2) => width.div_ceil(2),
+            (Self::I40A, 1..=2) | (Self::I42A, 1..=2) => width.div_ceil(2),
+            (_, p) if p < self.planes() => width,
+            _ => 0,
+        }
+    }
+
+    /// Number of rows in `plane` for a frame that is `height` pixels tall.
+    pub fn plane_height(self, plane: usize, height: u32) -> u32 {
+        match (self, plane) {
+            (Self::NV12, 1) | (Self::I420, 1..=2) | (Self::I40A, 1..=2) => height.div_ceil(2),
+            (_, p) if p < self.planes() => height,
+            _ => 0,
+        }
+    }
+}
+
+/// The dimensions and format of the main OBS canvas.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct VideoInfo {
+    pub fps_num: u32,
+    pub fps_den: u32,
+    pub base_width: u32,
+    pub base_height: u32,
+    pub output_width: u32,
+    pub output_height: u32,
+    pub output_format: VideoFormat,
+}
+
+impl VideoInfo {
+    /// Returns the current video settings, or `None` if video has not been
+    /// initialised yet.
+    pub fn get() -> Option<Self> {
+        let mut ovi = obs_video_info::default();
+        if unsafe { obs_get_video_info(&mut ovi) } {
+            Some(Self {
+                fps_num: ovi.fps_num,
+                fps_den: ovi.fps_den,
+                base_width: ovi.base_width,
+                base_height: ovi.base_height,
+                output_width: ovi.output_width,
+                output_height: ovi.output_height,
+                output_format: std::convert::TryFrom::try_from(ovi.output_format)
+                    .unwrap_or(VideoFormat::None),
+            })
+        } else {
+            None
+        }
+    }
+}
+
+/// Reference to a video pipeline, either the main one from
+/// [`GlobalContext::video`](crate::source::context::GlobalContext::video) or the
+/// one an output is attached to.
+///
+/// The pipeline is owned by OBS, so this is only a handle to it.
+pub struct VideoRef {
+    pointer: *mut video_t,
+}
+
+impl VideoRef {
+    pub(crate) unsafe fn from_raw(pointer: *mut video_t) -> Option<Self> {
+        if pointer.is_null() {
+            None
+        } else {
+            Some(Self { pointer })
+        }
+    }
+
+    /// The main video pipeline, or `None` if video has not been initialised.
+    pub(crate) fn main() -> Option<Self> {
+        unsafe { Self::from_raw(obs_get_video()) }
+    }
+
+    pub fn format(&self) -> VideoFormat {
+        std::convert::TryFrom::try_from(unsafe { video_output_get_format(self.pointer) })
+            .unwrap_or(VideoFormat::None)
+    }
+
+    pub fn width(&self) -> u32 {
+        unsafe { video_output_get_width(self.pointer) }
+    }
+
+    pub fn height(&self) -> u32 {
+        unsafe { video_output_get_height(self.pointer) }
+    }
+
+    pub fn frame_rate(&self) -> f64 {
+        unsafe { video_output_get_frame_rate(self.pointer) }
+    }
+
+    /// Duration of a single frame in nanoseconds.
+    pub fn frame_time(&self) -> u64 {
+        unsafe { video_output_get_frame_time(self.pointer) }
+    }
+
+    /// Whether any output is currently receiving frames from the pipeline.
+    pub fn active(&self) -> bool {
+        unsafe { video_output_active(self.pointer) }
+    }
+
+    pub fn as_ptr(&self) -> *mut video_t {
+        self.pointer
+    }
+}
+
+/// Builds a conversion to `format` at `width` x `height`, keeping the canvas
+/// colorspace and range.
+pub(crate) fn scale_info(format: VideoFormat, width: u32, height: u32) -> video_scale_info {
+    let mut ovi = obs_video_info::default();
+    unsafe {
+        obs_get_video_info(&mut ovi);
+    }
+    video_scale_info {
+        format: format.into(),
+        width,
+        height,
+        range: ovi.range,
+        colorspace: ovi.colorspace,
+    }
+}
+
+/// Wrapper around [`video_data`], a raw video frame delivered by OBS.
+///
+/// OBS pads every line to its own alignment, so rows should always be read
+/// through [`VideoDataContext::row`] or [`VideoDataContext::rows`] rather
+/// than assuming the planes are tightly packed.
+pub struct VideoDataContext {
+    pointer: *mut video_data,
+    format: VideoFormat,
+    width: u32,
+    height: u32,
+}
+
+impl VideoDataContext {
+    pub(crate) unsafe fn from_raw(
+        pointer: *mut video_data,
+        format: VideoFormat,
+        width: u32,
+        height: u32,
+    ) -> Self {
+        Self {
+            pointer,
+            format,
+            width,
+            height,
+        }
+    }
+
+    pub fn format(&self) -> VideoFormat {
+        self.format
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn timestamp(&self) -> u64 {
+        unsafe {
+            self.pointer
+                .as_ref()
+                .expect("Video pointer was null!")
+                .timestamp
+        }
+    }
+
+    /// The number of bytes between the start of two rows in `plane`.
+    pub fn linesize(&self, plane: usize) -> usize {
+        if plane >= self.format.planes() {
+            return 0;
+        }
+        unsafe {
+            self.pointer
+                .as_ref()
+                .expect("Video pointer was null!")
+                .linesize[plane] as usize
+        }
+    }
+
+    /// Returns the whole of `plane`, including line padding.
+    pub fn plane(&self, plane: usize) -> Option<&'_ [u8]> {
+        if plane >= self.format.planes() {
+            return None;
+        }
+        unsafe {
+            let raw = self.pointer.as_ref()?;
+            let ptr = raw.data[plane];
+            if ptr.is_null() {
+                return None;
+            }
+            let len = raw.linesize[plane] as usize
+                * self.format.plane_height(plane, self.height) as usize;
+            Some(slice::from_raw_parts(ptr, len))
+        }
+    }
+
+    /// Returns the visible bytes of a single row of `plane`.
+    pub fn row(&self, plane: usize, row: u32) -> Option<&'_ [u8]> {
+        if row >= self.format.plane_height(plane, self.height) {
+            return None;
+        }
+        let linesize = self.linesize(plane);
+        let start = linesize * row as usize;
+        let len = self.format.row_bytes(plane, self.width);
+        self.plane(plane).map(|data| &data[start..start + len])
+    }
+
+    /// Iterates over the visible bytes of each row of `plane`.
+    pub fn rows(&self, plane: usize) -> impl Iterator<Item = &'_ [u8]> + '_ {
+        (0..self.format.plane_height(plane, self.height))
+            .filter_map(move |row| self.row(plane, row))
+    }
+
+    /// Copies every plane into `buffer` with the line padding removed, which
+    /// is the layout most virtual camera backends expect.
+    pub fn copy_packed(&self, buffer: &mut Vec<u8>) {
+        buffer.clear();
+        for plane in 0..self.format.planes() {
+            for row in self.rows(plane) {
+                buffer.extend_from_slice(row);
+            }
+        }
+    }
+}
+
+struct RawVideoCallbackData {
+    func: Box<dyn FnMut(&VideoDataContext) + Send>,
+    format: VideoFormat,
+    width: u32,
+    height: u32,
+}
+
+/// A callback receiving every composited program frame, registered with
+/// [`obs_add_raw_video_callback`].
+///
+/// The callback runs on the OBS video thread and is removed when this handle
+/// is dropped.
+pub struct RawVideoCallback {
+    data: *mut RawVideoCallbackData,
+}
+
+impl RawVideoCallback {
+    /// Registers `func` to receive frames in the canvas output format and size.
+    ///
+    /// Returns `None` if video has not been initialised yet.
+    pub fn new<F: FnMut(&VideoDataContext) + Send + 'static>(func: F) -> Option<Self> {
+        let info = VideoInfo::get()?;
+        Some(Self::register(
+            None,
+            info.output_format,
+            info.output_width,
+            info.output_height,
+            Box::new(func),
+        ))
+    }
+
+    /// Registers `func` to receive frames converted to `format` and scaled to
+    /// `width` x `height`.
+    pub fn with_conversion<F: FnMut(&VideoDataContext) + Send + 'static>(
+        format: VideoFormat,
+        width: u32,
+        height: u32,
+        func: F,
+    ) -> Self {
+        let conversion = scale_info(format, width, height);
+        Self::register(Some(conversion), format, width, height, Box::new(func))
+    }
+
+    fn register(
+        conversion: Option<video_scale_info>,
+        format: VideoFormat,
+        width: u32,
+        height: u32,
+        func: Box<dyn FnMut(&VideoDataContext) + Send>,
+    ) -> Self {
+        let data = Box::into_raw(Box::new(RawVideoCallbackData {
+            func,
+            format,
+            width,
+            height,
+        }));
+        unsafe {
+            obs_add_raw_video_callback(
+                conversion
+                    .as_ref()
+                    .map(|c| c as *const _)
+                    .unwrap_or(std::ptr::null()),
+                Some(raw_video_callback),
+                data as *mut c_void,
+            );
+        }
+        Self { data }
+    }
+}
+
+impl Drop for RawVideoCallback {
+    fn drop(&mut self) {
+        unsafe {
+            obs_remove_raw_video_callback(Some(raw_video_callback), self.data as *mut c_void);
+            drop(Box::from_raw(self.data));
+        }
+    }
+}
+
+unsafe extern "C" fn raw_video_callback(param: *mut c_void, frame: *mut video_data) {
+    let data = &mut *(param as *mut RawVideoCallbackData);
+    let frame = VideoDataContext::from_raw(frame, data.format, data.width, data.height);
+    handle_unwind::<RawVideoCallback, _>("raw_video_callback", || (data.func)(&frame));
+}
@@ -0,0 +1,76 @@
+//! A [`View`] renders its own set of channels independently of the main
+//! output, which is useful for multiviews or for previewing a single source:
+//!
+//! ```ignore
+//! let mut view = View::new();
+//! view.set_source(0, Some(&source));
+//! display.add_draw_callback(move |render, _cx, _cy| view.render(render));
+//! ```
+
+use crate::source::{context::VideoRenderContext, SourceContext, SourceRef};
+use obs_sys::{
+    obs_view_create, obs_view_destroy, obs_view_get_source, obs_view_render, obs_view_set_source,
+    obs_view_t, MAX_CHANNELS,
+};
+
+/// Wrapper around [`obs_view_t`], a set of output channels rendered
+/// separately from the main view.
+///
+/// See [OBS documentation](https://obsproject.com/docs/reference-core.html#views)
+pub struct View {
+    raw: *mut obs_view_t,
+}
+
+// The view itself is reference counted and locked by OBS.
+unsafe impl Send for View {}
+
+impl View {
+    pub fn new() -> Self {
+        Self {
+            raw: unsafe { obs_view_create() },
+        }
+    }
+
+    /// Sets the source rendered on `channel`, or clears it with `None`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `channel` is not below `MAX_CHANNELS`.
+    pub fn set_source(&mut self, channel: u32, source: Option<&SourceContext>) {
+        assert!(channel < MAX_CHANNELS, "Channel out of range");
+        let source = source
+            .map(|source| source.as_ptr())
+            .unwrap_or(std::ptr::null_mut());
+        unsafe { obs_view_set_source(self.raw, channel, source) }
+    }
+
+    /// Returns the source rendered on `channel`.
+    pub fn source(&self, channel: u32) -> Option<SourceRef> {
+        if channel >= MAX_CHANNELS {
+            return None;
+        }
+        unsafe { SourceRef::from_raw(obs_view_get_source(self.raw, channel)) }
+    }
+
+    /// Renders every channel of the view, e.g. from a
+    /// [`Display`](crate::display::Display) draw callback.
+    pub fn render(&self, _render: &mut VideoRenderContext) {
+        unsafe { obs_view_render(self.raw) }
+    }
+
+    pub fn as_ptr(&self) -> *mut obs_view_t {
+        self.raw
+    }
+}
+
+impl Default for View {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for View {
+    fn drop(&mut self) {
+        unsafe { obs_view_destroy(self.raw) }
+    }
+}
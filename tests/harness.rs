@@ -0,0 +1,72 @@
+#![cfg(feature = "testing")]
+
+use obs_wrapper::{obs_string, prelude::*, testing::HarnessOptions};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static LOADED_UNLOADS: AtomicUsize = AtomicUsize::new(0);
+static FAILED_UNLOADS: AtomicUsize = AtomicUsize::new(0);
+
+struct LoadedModule {
+    context: ModuleContext,
+}
+
+impl Module for LoadedModule {
+    fn new(context: ModuleContext) -> Self {
+        Self { context }
+    }
+    fn get_ctx(&self) -> &ModuleContext {
+        &self.context
+    }
+    fn unload(&mut self) {
+        LOADED_UNLOADS.fetch_add(1, Ordering::SeqCst);
+    }
+    fn description() -> ObsString {
+        obs_string!("A module that loads")
+    }
+    fn name() -> ObsString {
+        obs_string!("Loaded Module")
+    }
+    fn author() -> ObsString {
+        obs_string!("obs-wrapper")
+    }
+}
+
+struct FailedModule {
+    context: ModuleContext,
+}
+
+impl Module for FailedModule {
+    fn new(context: ModuleContext) -> Self {
+        Self { context }
+    }
+    fn get_ctx(&self) -> &ModuleContext {
+        &self.context
+    }
+    fn load(&mut self, _load_context: &mut LoadContext) -> bool {
+        false
+    }
+    fn unload(&mut self) {
+        FAILED_UNLOADS.fetch_add(1, Ordering::SeqCst);
+    }
+    fn description() -> ObsString {
+        obs_string!("A module that fails to load")
+    }
+    fn name() -> ObsString {
+        obs_string!("Failed Module")
+    }
+    fn author() -> ObsString {
+        obs_string!("obs-wrapper")
+    }
+}
+
+#[test]
+fn only_loaded_modules_are_unloaded() {
+    let mut obs = HarnessOptions::new().without_video().start().unwrap();
+    assert!(obs.load::<LoadedModule>());
+    assert!(!obs.load::<FailedModule>());
+    assert!(!obs.run_frames(1));
+    drop(obs);
+
+    assert_eq!(LOADED_UNLOADS.load(Ordering::SeqCst), 1);
+    assert_eq!(FAILED_UNLOADS.load(Ordering::SeqCst), 0);
+}